@@ -3,7 +3,19 @@ use std::collections::HashMap;
 
 #[derive(Debug, Default, serde::Deserialize)]
 pub struct Config {
+    #[serde(default)]
     pub test: HashMap<String, bool>,
+    #[serde(default)]
+    pub gui: GuiConfig,
+}
+
+/// Settings for the `gui-*` tests.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct GuiConfig {
+    /// The command used to start a virtual X server, like `Xvfb`, when the `DISPLAY` environment
+    /// variable is not set. The display name (e.g. `:99`) is passed as the first argument. The GUI
+    /// tests are skipped if there is no display and this is not set.
+    pub xvfb: Option<String>,
 }
 
 impl Config {
@@ -198,7 +198,7 @@ mod pretty {
     use crate::plugin::index::ScanStatus;
     use crate::plugin::preset_discovery::*;
     use crate::tests::TestList;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use yansi::Paint;
 
     pub fn print_tests(list: &TestList, config: &Config) {
@@ -302,6 +302,25 @@ mod pretty {
                 ],
             };
 
+            if !library.invalidation_sources.is_empty() {
+                group.items.push(ReportItem::Table(
+                    library
+                        .invalidation_sources
+                        .iter()
+                        .map(|source| {
+                            let path = Path::new(&source.directory).join(&source.filename_glob);
+                            let value = if source.recursive_scan {
+                                format!("{} (recursive)", path.display())
+                            } else {
+                                path.display().to_string()
+                            };
+
+                            ("invalidation".to_string(), value)
+                        })
+                        .collect(),
+                ));
+            }
+
             // per plugin info
             for plugin in library.plugins {
                 num_plugins += 1;
@@ -1,9 +1,12 @@
 //! Contains functions for loading and interacting with CLAP plugins.
 
 pub mod ext;
+pub mod ffi;
 pub mod index;
 pub mod instance;
+pub mod invalidation;
 pub mod library;
 pub mod preset_discovery;
 pub mod process;
+pub mod state_converter;
 pub mod util;
@@ -10,14 +10,33 @@ pub mod audio_ports;
 pub mod audio_ports_activation;
 pub mod audio_ports_config;
 pub mod configurable_audio_ports;
+pub mod context_menu;
+pub mod extensible_audio_ports;
+pub mod gain_adjustment_metering;
+pub mod gui;
 pub mod latency;
+pub mod mini_curve_display;
+pub mod note_name;
 pub mod note_ports;
+pub mod param_indication;
 pub mod params;
+pub mod posix_fd_support;
 pub mod preset_load;
+pub mod project_location;
+pub mod remote_controls;
+pub mod render;
+pub mod resource_directory;
 pub mod state;
+pub mod state_context;
 pub mod surround;
 pub mod tail;
 pub mod thread_pool;
+pub mod timer_support;
+pub mod track_info;
+pub mod triggers;
+pub mod tuning;
+pub mod undo_context;
+pub mod undo_delta;
 pub mod voice_info;
 
 /// An abstraction for a CLAP plugin extension.
@@ -9,7 +9,7 @@ use clap_sys::ext::configurable_audio_ports::{
     clap_plugin_configurable_audio_ports,
 };
 use clap_sys::ext::surround::CLAP_PORT_SURROUND;
-use std::ffi::CStr;
+use std::ffi::{CStr, c_void};
 use std::fmt::{Debug, Display};
 use std::ptr::{NonNull, null};
 
@@ -139,6 +139,15 @@ impl<'a> AudioPortsRequestInfo<'a> {
             AudioPortsRequestInfo::Untyped { .. } => None,
         }
     }
+
+    /// The type-specific port details pointer passed to the plugin alongside the port type.
+    pub fn port_details(&self) -> *const c_void {
+        match self {
+            AudioPortsRequestInfo::Surround { channel_map } => channel_map.as_ptr() as *const _,
+            AudioPortsRequestInfo::Ambisonic { config, .. } => *config as *const clap_ambisonic_config as *const _,
+            _ => null(),
+        }
+    }
 }
 
 fn convert_requests<'a>(
@@ -151,11 +160,7 @@ fn convert_requests<'a>(
             port_index: r.port_index,
             channel_count: r.request_info.channel_count(),
             port_type: r.request_info.port_type().map_or(null(), |f| f.as_ptr()),
-            port_details: match r.request_info {
-                AudioPortsRequestInfo::Surround { channel_map } => channel_map.as_ptr() as *const _,
-                AudioPortsRequestInfo::Ambisonic { config, .. } => config as *const clap_ambisonic_config as *const _,
-                _ => null(),
-            },
+            port_details: r.request_info.port_details(),
         })
         .collect::<Vec<_>>()
 }
@@ -0,0 +1,360 @@
+//! Abstractions for interacting with the `context-menu` extension, and the host's context menu
+//! builder the plugin adds its menu items to.
+
+use super::Extension;
+use crate::cli::fail_test;
+use crate::cli::tracing::{Span, record};
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::{self, CHECK_POINTER, Proxy, Proxyable, clap_call};
+use anyhow::{Context, Result};
+use clap_sys::ext::context_menu::*;
+use clap_sys::id::clap_id;
+use std::ffi::{CStr, c_void};
+use std::ptr::NonNull;
+use std::sync::Mutex;
+use std::thread::ThreadId;
+
+/// Abstraction for the `context-menu` extension covering the main thread functionality.
+pub struct ContextMenu<'a> {
+    plugin: &'a Plugin<'a>,
+    context_menu: NonNull<clap_plugin_context_menu>,
+}
+
+/// A single item the plugin added to a context menu through `clap_context_menu_builder::add_item()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextMenuItem {
+    Entry {
+        label: String,
+        is_enabled: bool,
+        action_id: clap_id,
+    },
+    CheckEntry {
+        label: String,
+        is_enabled: bool,
+        is_checked: bool,
+        action_id: clap_id,
+    },
+    Separator,
+    BeginSubmenu {
+        label: String,
+        is_enabled: bool,
+    },
+    EndSubmenu,
+    Title {
+        title: String,
+        is_enabled: bool,
+    },
+}
+
+/// The host's implementation of `clap_context_menu_builder`. This records every item the plugin
+/// adds, and it checks that the plugin properly closes every submenu it opens.
+#[derive(Debug)]
+pub struct ContextMenuBuilder {
+    /// The thread ID for the thread this object was created on. The menu is populated from the
+    /// main thread, so all callbacks should be made from this thread.
+    expected_thread_id: ThreadId,
+
+    /// The items added by the plugin so far.
+    items: Mutex<Vec<ContextMenuItem>>,
+    /// The number of submenus that have been opened but not yet closed.
+    submenu_depth: Mutex<usize>,
+
+    /// The first error that occurred while the plugin was adding items, if any. Once an error has
+    /// occurred, all further `add_item()` calls return `false`.
+    result: Mutex<Result<()>>,
+}
+
+impl<'a> Extension for ContextMenu<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_CONTEXT_MENU, CLAP_EXT_CONTEXT_MENU_COMPAT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_context_menu;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            context_menu: extension_struct,
+        }
+    }
+}
+
+impl ContextMenu<'_> {
+    /// Ask the plugin to populate a context menu for the target. Returns `None` if the plugin
+    /// returned `false`, and an error if the plugin added invalid items or if the submenus are not
+    /// properly nested.
+    pub fn populate(&self, target: &clap_context_menu_target) -> Result<Option<Vec<ContextMenuItem>>> {
+        let builder = ContextMenuBuilder::new();
+        let context_menu = self.context_menu.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_context_menu::populate",
+            record! { target_kind: target.kind, target_id: target.id },
+        );
+        let result = unsafe {
+            clap_call! { context_menu=>populate(plugin, target, Proxy::vtable(&builder)) }
+        };
+
+        span.finish(record!(result: result));
+
+        let items = builder.finish()?;
+        if result { Ok(Some(items)) } else { Ok(None) }
+    }
+
+    /// Perform the action with the given ID for the target. Returns whether the plugin handled the
+    /// action.
+    pub fn perform(&self, target: &clap_context_menu_target, action_id: clap_id) -> bool {
+        let context_menu = self.context_menu.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_context_menu::perform",
+            record! { target_kind: target.kind, target_id: target.id, action_id: action_id },
+        );
+        let result = unsafe {
+            clap_call! { context_menu=>perform(plugin, target, action_id) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+}
+
+impl ContextMenuItem {
+    /// The action ID for entries and check entries. Other items don't have an action ID.
+    pub fn action_id(&self) -> Option<clap_id> {
+        match self {
+            ContextMenuItem::Entry { action_id, .. } | ContextMenuItem::CheckEntry { action_id, .. } => {
+                Some(*action_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the item is enabled. Separators and the ends of submenus are always enabled.
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ContextMenuItem::Entry { is_enabled, .. }
+            | ContextMenuItem::CheckEntry { is_enabled, .. }
+            | ContextMenuItem::BeginSubmenu { is_enabled, .. }
+            | ContextMenuItem::Title { is_enabled, .. } => *is_enabled,
+            ContextMenuItem::Separator | ContextMenuItem::EndSubmenu => true,
+        }
+    }
+}
+
+impl Proxyable for ContextMenuBuilder {
+    type Vtable = clap_context_menu_builder;
+
+    fn init(&self) -> Self::Vtable {
+        clap_context_menu_builder {
+            ctx: CHECK_POINTER,
+            add_item: Some(Self::add_item),
+            supports: Some(Self::supports),
+        }
+    }
+}
+
+impl ContextMenuBuilder {
+    /// Create a new, empty context menu builder.
+    pub fn new() -> Proxy<Self> {
+        Proxy::new(Self {
+            expected_thread_id: std::thread::current().id(),
+            items: Mutex::new(Vec::new()),
+            submenu_depth: Mutex::new(0),
+            result: Mutex::new(Ok(())),
+        })
+    }
+
+    /// Return the items the plugin added to the menu, or the first error that occurred while the
+    /// plugin was adding them. This also returns an error if a submenu was never closed.
+    pub fn finish(&self) -> Result<Vec<ContextMenuItem>> {
+        std::mem::replace(&mut *self.result.lock().unwrap(), Ok(()))?;
+
+        let submenu_depth = *self.submenu_depth.lock().unwrap();
+        anyhow::ensure!(
+            submenu_depth == 0,
+            "The plugin did not close {submenu_depth} submenu(s) with 'CLAP_CONTEXT_MENU_ITEM_END_SUBMENU'."
+        );
+
+        Ok(std::mem::take(&mut *self.items.lock().unwrap()))
+    }
+
+    /// Checks that this function is called from the same thread the builder was created on.
+    fn assert_same_thread(&self) -> Result<()> {
+        let current_thread_id = std::thread::current().id();
+        anyhow::ensure!(
+            current_thread_id == self.expected_thread_id,
+            "'clap_context_menu_builder' methods may only be called from the same thread the menu is being populated \
+             on (thread {:?}), but it was called from thread {:?}",
+            self.expected_thread_id,
+            current_thread_id
+        );
+        Ok(())
+    }
+
+    #[track_caller]
+    fn wrap<R>(
+        builder: *const clap_context_menu_builder,
+        function_name: &str,
+        f: impl FnOnce(&Self) -> Result<R>,
+    ) -> Option<R> {
+        let state = unsafe {
+            Proxy::<Self>::from_vtable(builder).unwrap_or_else(|e| {
+                fail_test!("{}: {}", function_name, e);
+            })
+        };
+
+        if Proxy::vtable(&state).ctx != CHECK_POINTER {
+            fail_test!("{}: plugin messed with the 'ctx' pointer", function_name);
+        }
+
+        match f(&state) {
+            Ok(result) => Some(result),
+            Err(error) => {
+                let mut guard = state.result.lock().unwrap();
+                if guard.is_ok() {
+                    *guard = Err(error.context(function_name.to_string()));
+                }
+
+                None
+            }
+        }
+    }
+
+    unsafe extern "C" fn add_item(
+        builder: *const clap_context_menu_builder,
+        item_kind: clap_context_menu_item_kind,
+        item_data: *const c_void,
+    ) -> bool {
+        let span = Span::begin("clap_context_menu_builder::add_item", record! { item_kind: item_kind });
+
+        Self::wrap(builder, span.name(), |this| {
+            this.assert_same_thread()?;
+            if this.result.lock().unwrap().is_err() {
+                return Ok(false);
+            }
+
+            let item = unsafe { Self::parse_item(item_kind, item_data) }?;
+            let mut submenu_depth = this.submenu_depth.lock().unwrap();
+            match item {
+                ContextMenuItem::BeginSubmenu { .. } => *submenu_depth += 1,
+                ContextMenuItem::EndSubmenu => {
+                    anyhow::ensure!(
+                        *submenu_depth > 0,
+                        "'CLAP_CONTEXT_MENU_ITEM_END_SUBMENU' was added without a matching \
+                         'CLAP_CONTEXT_MENU_ITEM_BEGIN_SUBMENU'."
+                    );
+                    *submenu_depth -= 1;
+                }
+                _ => (),
+            }
+
+            this.items.lock().unwrap().push(item);
+
+            span.finish(record!(result: true));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn supports(
+        builder: *const clap_context_menu_builder,
+        item_kind: clap_context_menu_item_kind,
+    ) -> bool {
+        let span = Span::begin("clap_context_menu_builder::supports", record! { item_kind: item_kind });
+
+        Self::wrap(builder, span.name(), |this| {
+            this.assert_same_thread()?;
+
+            // The validator's menus are never shown, so every item kind is supported
+            let result = item_kind <= CLAP_CONTEXT_MENU_ITEM_TITLE;
+
+            span.finish(record!(result: result));
+            Ok(result)
+        })
+        .unwrap_or(false)
+    }
+
+    /// Parse the item data for an `add_item()` call. Labels and titles may not be empty.
+    unsafe fn parse_item(item_kind: clap_context_menu_item_kind, item_data: *const c_void) -> Result<ContextMenuItem> {
+        let has_data = matches!(
+            item_kind,
+            CLAP_CONTEXT_MENU_ITEM_ENTRY
+                | CLAP_CONTEXT_MENU_ITEM_CHECK_ENTRY
+                | CLAP_CONTEXT_MENU_ITEM_BEGIN_SUBMENU
+                | CLAP_CONTEXT_MENU_ITEM_TITLE
+        );
+        if has_data {
+            anyhow::ensure!(
+                !item_data.is_null(),
+                "'item_data' is a null pointer for item kind {item_kind}."
+            );
+        }
+
+        unsafe {
+            match item_kind {
+                CLAP_CONTEXT_MENU_ITEM_ENTRY => {
+                    let entry = &*(item_data as *const clap_context_menu_entry);
+
+                    Ok(ContextMenuItem::Entry {
+                        label: util::cstr_ptr_to_mandatory_string(entry.label)
+                            .context("Invalid label for 'CLAP_CONTEXT_MENU_ITEM_ENTRY'")?,
+                        is_enabled: entry.is_enabled,
+                        action_id: entry.action_id,
+                    })
+                }
+                CLAP_CONTEXT_MENU_ITEM_CHECK_ENTRY => {
+                    let entry = &*(item_data as *const clap_context_menu_check_entry);
+
+                    Ok(ContextMenuItem::CheckEntry {
+                        label: util::cstr_ptr_to_mandatory_string(entry.label)
+                            .context("Invalid label for 'CLAP_CONTEXT_MENU_ITEM_CHECK_ENTRY'")?,
+                        is_enabled: entry.is_enabled,
+                        is_checked: entry.is_checked,
+                        action_id: entry.action_id,
+                    })
+                }
+                CLAP_CONTEXT_MENU_ITEM_SEPARATOR => Ok(ContextMenuItem::Separator),
+                CLAP_CONTEXT_MENU_ITEM_BEGIN_SUBMENU => {
+                    let submenu = &*(item_data as *const clap_context_menu_submenu);
+
+                    Ok(ContextMenuItem::BeginSubmenu {
+                        label: util::cstr_ptr_to_mandatory_string(submenu.label)
+                            .context("Invalid label for 'CLAP_CONTEXT_MENU_ITEM_BEGIN_SUBMENU'")?,
+                        is_enabled: submenu.is_enabled,
+                    })
+                }
+                CLAP_CONTEXT_MENU_ITEM_END_SUBMENU => Ok(ContextMenuItem::EndSubmenu),
+                CLAP_CONTEXT_MENU_ITEM_TITLE => {
+                    let title = &*(item_data as *const clap_context_menu_item_title);
+
+                    Ok(ContextMenuItem::Title {
+                        title: util::cstr_ptr_to_mandatory_string(title.title)
+                            .context("Invalid title for 'CLAP_CONTEXT_MENU_ITEM_TITLE'")?,
+                        is_enabled: title.is_enabled,
+                    })
+                }
+                _ => anyhow::bail!("Unknown context menu item kind {item_kind}."),
+            }
+        }
+    }
+}
+
+/// Read and validate a context menu target passed to the host by the plugin.
+///
+/// # Safety
+///
+/// `target` should be a null pointer or point to a valid `clap_context_menu_target`.
+pub unsafe fn read_target(target: *const clap_context_menu_target) -> Result<clap_context_menu_target> {
+    anyhow::ensure!(!target.is_null(), "'target' is a null pointer");
+
+    let target = unsafe { *target };
+    anyhow::ensure!(
+        target.kind == CLAP_CONTEXT_MENU_TARGET_KIND_GLOBAL || target.kind == CLAP_CONTEXT_MENU_TARGET_KIND_PARAM,
+        "Unknown context menu target kind {}.",
+        target.kind
+    );
+
+    Ok(target)
+}
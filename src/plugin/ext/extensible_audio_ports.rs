@@ -0,0 +1,80 @@
+//! Abstractions for interacting with the draft `extensible-audio-ports` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::ext::configurable_audio_ports::AudioPortsRequestInfo;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::draft::extensible_audio_ports::*;
+use std::ffi::CStr;
+use std::ptr::{NonNull, null};
+
+/// Abstraction for the `extensible-audio-ports` extension covering the main thread functionality.
+pub struct ExtensibleAudioPorts<'a> {
+    plugin: &'a Plugin<'a>,
+    extensible_audio_ports: NonNull<clap_plugin_extensible_audio_ports>,
+}
+
+impl<'a> Extension for ExtensibleAudioPorts<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_EXTENSIBLE_AUDIO_PORTS];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_extensible_audio_ports;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            extensible_audio_ports: extension_struct,
+        }
+    }
+}
+
+impl<'a> ExtensibleAudioPorts<'a> {
+    /// Ask the plugin to add a new input or output port at the end of its port list. Returns
+    /// whether the plugin added the port.
+    pub fn add_port(&self, is_input: bool, request_info: AudioPortsRequestInfo) -> bool {
+        self.plugin.status().assert_inactive();
+
+        let extensible_audio_ports = self.extensible_audio_ports.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_extensible_audio_ports::add_port",
+            record! { is_input: is_input, details: request_info },
+        );
+        let result = unsafe {
+            clap_call! {
+                extensible_audio_ports=>add_port(
+                    plugin,
+                    is_input,
+                    request_info.channel_count(),
+                    request_info.port_type().map_or(null(), |port_type| port_type.as_ptr()),
+                    request_info.port_details(),
+                )
+            }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Ask the plugin to remove the input or output port at `index`. Returns whether the plugin
+    /// removed the port.
+    pub fn remove_port(&self, is_input: bool, index: u32) -> bool {
+        self.plugin.status().assert_inactive();
+
+        let extensible_audio_ports = self.extensible_audio_ports.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_extensible_audio_ports::remove_port",
+            record! { is_input: is_input, index: index },
+        );
+        let result = unsafe {
+            clap_call! { extensible_audio_ports=>remove_port(plugin, is_input, index) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+}
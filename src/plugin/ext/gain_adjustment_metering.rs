@@ -0,0 +1,47 @@
+//! Abstractions for interacting with the draft `gain-adjustment-metering` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::ffi::gain_adjustment_metering::*;
+use crate::plugin::instance::PluginAudioThread;
+use crate::plugin::util::clap_call;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `gain-adjustment-metering` extension covering the audio thread
+/// functionality.
+pub struct GainAdjustmentMetering<'a> {
+    plugin: &'a PluginAudioThread<'a>,
+    gain_adjustment_metering: NonNull<clap_plugin_gain_adjustment_metering>,
+}
+
+impl<'a> Extension for GainAdjustmentMetering<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_GAIN_ADJUSTMENT_METERING];
+
+    type Plugin = &'a PluginAudioThread<'a>;
+    type Struct = clap_plugin_gain_adjustment_metering;
+
+    unsafe fn new(plugin: &'a PluginAudioThread<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            gain_adjustment_metering: extension_struct,
+        }
+    }
+}
+
+impl<'a> GainAdjustmentMetering<'a> {
+    /// Get the gain adjustment in decibels the plugin applied to the last sample of the most recent
+    /// process call.
+    pub fn get(&self) -> f64 {
+        let gain_adjustment_metering = self.gain_adjustment_metering.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gain_adjustment_metering::get_adjustment_metering", ());
+        let result = unsafe {
+            clap_call! { gain_adjustment_metering=>get_adjustment_metering(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+}
@@ -0,0 +1,261 @@
+//! Abstractions for interacting with the `gui` extension.
+
+use super::Extension;
+use crate::cli::tracing::{Span, record};
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use anyhow::Result;
+use clap_sys::ext::gui::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `gui` extension covering the main thread functionality.
+pub struct Gui<'a> {
+    plugin: &'a Plugin<'a>,
+    gui: NonNull<clap_plugin_gui>,
+}
+
+impl<'a> Extension for Gui<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_GUI];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_gui;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            gui: extension_struct,
+        }
+    }
+}
+
+impl Gui<'_> {
+    /// Check whether the plugin supports a windowing API, either embedded or floating.
+    pub fn is_api_supported(&self, api: &CStr, is_floating: bool) -> bool {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_gui::is_api_supported",
+            record! { api: api.to_string_lossy(), is_floating: is_floating },
+        );
+        let result = unsafe {
+            clap_call! { gui=>is_api_supported(plugin, api.as_ptr(), is_floating) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Create the plugin's GUI. Returns an error if the plugin returned `false`. The GUI needs to
+    /// be destroyed again using [`destroy()`][Self::destroy()].
+    pub fn create(&self, api: &CStr, is_floating: bool) -> Result<()> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_gui::create",
+            record! { api: api.to_string_lossy(), is_floating: is_floating },
+        );
+        let result = unsafe {
+            clap_call! { gui=>create(plugin, api.as_ptr(), is_floating) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            self.plugin.shared().gui.store(Some(is_floating));
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "'clap_plugin_gui::create()' returned false for the '{}' API (floating: {is_floating}).",
+                api.to_string_lossy()
+            );
+        }
+    }
+
+    /// Destroy the plugin's GUI.
+    pub fn destroy(&self) {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_gui::destroy", ());
+        unsafe {
+            clap_call! { gui=>destroy(plugin) };
+        }
+
+        self.plugin.shared().gui.store(None);
+    }
+
+    /// Set the GUI's absolute scaling factor. Returns `false` if the plugin rejected the scale,
+    /// which it's allowed to do when it queries the scale from the OS itself.
+    pub fn set_scale(&self, scale: f64) -> bool {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::set_scale", record!(scale: scale));
+        let result = unsafe {
+            clap_call! { gui=>set_scale(plugin, scale) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Get the GUI's current size in pixels. Returns an error if the plugin returned `false`.
+    pub fn get_size(&self) -> Result<(u32, u32)> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let mut width = 0;
+        let mut height = 0;
+        let span = Span::begin("clap_plugin_gui::get_size", ());
+        let result = unsafe {
+            clap_call! { gui=>get_size(plugin, &mut width, &mut height) }
+        };
+
+        span.finish(record!(result: result, width: width, height: height));
+
+        if result {
+            Ok((width, height))
+        } else {
+            anyhow::bail!("'clap_plugin_gui::get_size()' returned false.");
+        }
+    }
+
+    /// Whether the user can resize the GUI by dragging its window's edges.
+    pub fn can_resize(&self) -> bool {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::can_resize", ());
+        let result = unsafe {
+            clap_call! { gui=>can_resize(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Get the GUI's resize hints. Returns `None` if the plugin returned `false`.
+    pub fn get_resize_hints(&self) -> Option<clap_gui_resize_hints> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::get_resize_hints", ());
+        unsafe {
+            let mut hints = clap_gui_resize_hints { ..std::mem::zeroed() };
+            let result = clap_call! { gui=>get_resize_hints(plugin, &mut hints) };
+
+            span.finish(record!(result: result));
+            result.then_some(hints)
+        }
+    }
+
+    /// Let the plugin adjust a size to the closest size its GUI can be resized to. Returns `None`
+    /// if the plugin returned `false`.
+    pub fn adjust_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let mut adjusted_width = width;
+        let mut adjusted_height = height;
+        let span = Span::begin("clap_plugin_gui::adjust_size", record! { width: width, height: height });
+        let result = unsafe {
+            clap_call! { gui=>adjust_size(plugin, &mut adjusted_width, &mut adjusted_height) }
+        };
+
+        span.finish(record!(result: result, width: adjusted_width, height: adjusted_height));
+        result.then_some((adjusted_width, adjusted_height))
+    }
+
+    /// Resize the GUI. Returns an error if the plugin returned `false`.
+    pub fn set_size(&self, width: u32, height: u32) -> Result<()> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::set_size", record! { width: width, height: height });
+        let result = unsafe {
+            clap_call! { gui=>set_size(plugin, width, height) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!("'clap_plugin_gui::set_size({width}, {height})' returned false.");
+        }
+    }
+
+    /// Embed the GUI in a parent window. Returns an error if the plugin returned `false`.
+    pub fn set_parent(&self, window: &clap_window) -> Result<()> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::set_parent", ());
+        let result = unsafe {
+            clap_call! { gui=>set_parent(plugin, window) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!("'clap_plugin_gui::set_parent()' returned false.");
+        }
+    }
+
+    /// Suggest a title for a floating GUI's window.
+    pub fn suggest_title(&self, title: &CStr) {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin(
+            "clap_plugin_gui::suggest_title",
+            record!(title: title.to_string_lossy()),
+        );
+        unsafe {
+            clap_call! { gui=>suggest_title(plugin, title.as_ptr()) };
+        }
+    }
+
+    /// Show the GUI. Returns an error if the plugin returned `false`.
+    pub fn show(&self) -> Result<()> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::show", ());
+        let result = unsafe {
+            clap_call! { gui=>show(plugin) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!("'clap_plugin_gui::show()' returned false.");
+        }
+    }
+
+    /// Hide the GUI. Returns an error if the plugin returned `false`.
+    pub fn hide(&self) -> Result<()> {
+        let gui = self.gui.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_gui::hide", ());
+        let result = unsafe {
+            clap_call! { gui=>hide(plugin) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!("'clap_plugin_gui::hide()' returned false.");
+        }
+    }
+}
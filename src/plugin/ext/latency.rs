@@ -6,7 +6,6 @@ use clap_sys::ext::latency::{CLAP_EXT_LATENCY, clap_plugin_latency};
 use std::ffi::CStr;
 use std::ptr::NonNull;
 
-#[allow(unused)]
 pub struct Latency<'a> {
     plugin: &'a Plugin<'a>,
     latency: NonNull<clap_plugin_latency>,
@@ -27,7 +26,6 @@ impl<'a> Extension for Latency<'a> {
 }
 
 impl<'a> Latency<'a> {
-    #[allow(unused)]
     pub fn get(&self) -> u32 {
         self.plugin.status().assert_is_not(PluginStatus::Deactivated);
 
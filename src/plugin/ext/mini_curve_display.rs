@@ -0,0 +1,189 @@
+//! Abstractions for interacting with the draft `mini-curve-display` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::ffi::mini_curve_display::*;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::{c_char_slice_to_string, clap_call};
+use anyhow::{Context, Result};
+use clap_sys::string_sizes::CLAP_NAME_SIZE;
+use std::ffi::{CStr, c_char};
+use std::ptr::NonNull;
+
+/// Abstraction for the `mini-curve-display` extension covering the main thread functionality.
+pub struct MiniCurveDisplay<'a> {
+    plugin: &'a Plugin<'a>,
+    mini_curve_display: NonNull<clap_plugin_mini_curve_display>,
+}
+
+impl<'a> Extension for MiniCurveDisplay<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_MINI_CURVE_DISPLAY];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_mini_curve_display;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            mini_curve_display: extension_struct,
+        }
+    }
+}
+
+/// A curve rendered by the plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiniCurve {
+    /// One of the `CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_*` constants.
+    pub kind: i32,
+    pub values: Vec<u16>,
+}
+
+impl<'a> MiniCurveDisplay<'a> {
+    /// Get the number of curves the plugin wants to display.
+    pub fn curve_count(&self) -> u32 {
+        let mini_curve_display = self.mini_curve_display.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_mini_curve_display::get_curve_count", ());
+        let result = unsafe {
+            clap_call! { mini_curve_display=>get_curve_count(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Tell the plugin whether its curves are being observed. The host's `mini-curve-display`
+    /// extension uses this to check whether the plugin is allowed to report changes.
+    pub fn set_observed(&self, is_observed: bool) {
+        let mini_curve_display = self.mini_curve_display.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        self.plugin.shared().mini_curve_display_observed.store(is_observed);
+
+        let _span = Span::begin(
+            "clap_plugin_mini_curve_display::set_observed",
+            record!(is_observed: is_observed),
+        );
+        unsafe {
+            clap_call! { mini_curve_display=>set_observed(plugin, is_observed) }
+        }
+    }
+
+    /// Let the plugin render up to `num_curves` curves with `num_values` values each. The value
+    /// buffers are filled with `fill_value` beforehand, so values the plugin did not write can be
+    /// detected by rendering twice with different fill values. Returns an error if the plugin
+    /// claims to have rendered more curves than there was room for.
+    pub fn render(&self, num_curves: u32, num_values: u32, fill_value: u16) -> Result<Vec<MiniCurve>> {
+        let mini_curve_display = self.mini_curve_display.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let mut values: Vec<Vec<u16>> = (0..num_curves).map(|_| vec![fill_value; num_values as usize]).collect();
+        let mut curves: Vec<clap_mini_curve_display_curve_data> = values
+            .iter_mut()
+            .map(|values| clap_mini_curve_display_curve_data {
+                curve_kind: CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED as i32,
+                values: values.as_mut_ptr(),
+                values_count: num_values,
+            })
+            .collect();
+
+        let span = Span::begin(
+            "clap_plugin_mini_curve_display::render",
+            record! { curves_size: num_curves, values_count: num_values },
+        );
+        let num_rendered = unsafe {
+            clap_call! { mini_curve_display=>render(plugin, curves.as_mut_ptr(), num_curves) }
+        };
+        span.finish(record!(result: num_rendered));
+
+        if num_rendered > num_curves {
+            anyhow::bail!(
+                "'clap_plugin_mini_curve_display::render()' returned {num_rendered}, but there was only room for \
+                 {num_curves} curves."
+            );
+        }
+
+        for (curve_idx, (curve, values)) in curves.iter().zip(&values).enumerate() {
+            if !std::ptr::eq(curve.values, values.as_ptr()) || curve.values_count != num_values {
+                anyhow::bail!(
+                    "'clap_plugin_mini_curve_display::render()' modified the value buffer pointer or size for curve \
+                     {curve_idx}."
+                );
+            }
+        }
+
+        Ok(curves
+            .into_iter()
+            .zip(values)
+            .take(num_rendered as usize)
+            .map(|(curve, values)| MiniCurve {
+                kind: curve.curve_kind,
+                values,
+            })
+            .collect())
+    }
+
+    /// Get the names for a curve's X and Y axes. Returns `None` if the plugin returns false.
+    /// Returns an error if the names are not null-terminated or if they contain invalid UTF-8.
+    pub fn axis_names(&self, curve_index: u32) -> Result<Option<(String, String)>> {
+        let mini_curve_display = self.mini_curve_display.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let mut x_name = [0 as c_char; CLAP_NAME_SIZE];
+        let mut y_name = [0 as c_char; CLAP_NAME_SIZE];
+
+        let span = Span::begin(
+            "clap_plugin_mini_curve_display::get_axis_name",
+            record!(curve_index: curve_index),
+        );
+        let result = unsafe {
+            clap_call! {
+                mini_curve_display=>get_axis_name(
+                    plugin,
+                    curve_index,
+                    x_name.as_mut_ptr(),
+                    y_name.as_mut_ptr(),
+                    CLAP_NAME_SIZE as u32,
+                )
+            }
+        };
+        span.finish(record!(result: result));
+
+        if !result {
+            return Ok(None);
+        }
+
+        let x_name = c_char_slice_to_string(&x_name)
+            .with_context(|| format!("Could not read the X axis name for curve {curve_index}"))?;
+        let y_name = c_char_slice_to_string(&y_name)
+            .with_context(|| format!("Could not read the Y axis name for curve {curve_index}"))?;
+
+        Ok(Some((x_name, y_name)))
+    }
+}
+
+/// Get the hints the host hands out through `clap_host_mini_curve_display::get_hints()` for a
+/// curve kind. These are the ranges a host would typically draw these kinds of curves with, in the
+/// units the draft specifies for that kind. Returns `None` for unspecified or unknown kinds.
+pub fn curve_kind_hints(kind: u32) -> Option<clap_mini_curve_display_curve_hints> {
+    // The x values are in Hz, seconds, or dB depending on the kind, the y values are in dB except
+    // for the phase response (radians) and time series (unspecified)
+    let (x_range, y_range) = match kind {
+        CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_RESPONSE => ((20.0, 20_000.0), (-24.0, 24.0)),
+        CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_PHASE_RESPONSE => {
+            ((20.0, 20_000.0), (-std::f64::consts::PI, std::f64::consts::PI))
+        }
+        CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TRANSFER_CURVE => ((-60.0, 0.0), (-60.0, 0.0)),
+        CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_REDUCTION => ((0.0, 5.0), (-24.0, 0.0)),
+        CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TIME_SERIES => ((0.0, 5.0), (-1.0, 1.0)),
+        _ => return None,
+    };
+
+    Some(clap_mini_curve_display_curve_hints {
+        x_min: x_range.0,
+        x_max: x_range.1,
+        y_min: y_range.0,
+        y_max: y_range.1,
+    })
+}
@@ -0,0 +1,114 @@
+//! Abstractions for interacting with the `note-name` extension.
+
+use super::Extension;
+use crate::cli::tracing::{Recordable, Recorder, Span, record};
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::{c_char_slice_to_string, clap_call};
+use anyhow::{Context, Result};
+use clap_sys::ext::note_name::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `note-name` extension covering the main thread functionality.
+pub struct NoteName<'a> {
+    plugin: &'a Plugin<'a>,
+    note_name: NonNull<clap_plugin_note_name>,
+}
+
+/// A single named note, or a range of named notes if any of the port, key, or channel fields are
+/// set to -1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteNameInfo {
+    pub name: String,
+    pub port: i16,
+    pub key: i16,
+    pub channel: i16,
+}
+
+impl<'a> Extension for NoteName<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_NOTE_NAME];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_note_name;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            note_name: extension_struct,
+        }
+    }
+}
+
+impl NoteName<'_> {
+    /// Get all of the plugin's note names. Returns an error if the plugin fails to return one of the
+    /// names it says it has, or if a name is empty or not valid UTF-8.
+    pub fn names(&self) -> Result<Vec<NoteNameInfo>> {
+        let num_names = self.get_raw_count();
+
+        let mut names = Vec::with_capacity(num_names as usize);
+        for index in 0..num_names {
+            let note_name = self.get_raw_note_name(index)?;
+
+            let name = c_char_slice_to_string(&note_name.name)
+                .with_context(|| format!("Could not read the name for note name {index}"))?;
+            if name.is_empty() {
+                anyhow::bail!("The name for note name {index} is empty.");
+            }
+
+            names.push(NoteNameInfo {
+                name,
+                port: note_name.port,
+                key: note_name.key,
+                channel: note_name.channel,
+            });
+        }
+
+        Ok(names)
+    }
+
+    fn get_raw_count(&self) -> u32 {
+        let note_name = self.note_name.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_note_name::count", ());
+        let result = unsafe {
+            clap_call! { note_name=>count(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    fn get_raw_note_name(&self, index: u32) -> Result<clap_note_name> {
+        let note_name = self.note_name.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_note_name::get", record! { index: index });
+
+        unsafe {
+            let mut result = clap_note_name { ..std::mem::zeroed() };
+            if clap_call! { note_name=>get(plugin, index, &mut result) } {
+                span.finish(record!(result: result));
+                Ok(result)
+            } else {
+                span.finish(record!(result: false));
+                anyhow::bail!(
+                    "'clap_plugin_note_name::get()' returned false for note name {index} ({} total note names).",
+                    self.get_raw_count()
+                );
+            }
+        }
+    }
+}
+
+impl Recordable for clap_note_name {
+    fn record(&self, record: &mut dyn Recorder) {
+        record.record(
+            "name",
+            c_char_slice_to_string(&self.name).unwrap_or_else(|_| String::from("<invalid>")),
+        );
+        record.record("port", self.port);
+        record.record("key", self.key);
+        record.record("channel", self.channel);
+    }
+}
@@ -0,0 +1,116 @@
+//! Abstractions for interacting with the `param-indication` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::color::clap_color;
+use clap_sys::ext::param_indication::*;
+use clap_sys::id::clap_id;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `param-indication` extension covering the main thread functionality.
+pub struct ParamIndication<'a> {
+    plugin: &'a Plugin<'a>,
+    param_indication: NonNull<clap_plugin_param_indication>,
+}
+
+impl<'a> Extension for ParamIndication<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_PARAM_INDICATION, CLAP_EXT_PARAM_INDICATION_COMPAT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_param_indication;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            param_indication: extension_struct,
+        }
+    }
+}
+
+impl<'a> ParamIndication<'a> {
+    /// Indicate whether the parameter is mapped to a physical controller. The color, label, and
+    /// description are optional and are passed to the plugin as null pointers if they are `None`.
+    pub fn set_mapping(
+        &self,
+        param_id: clap_id,
+        has_mapping: bool,
+        color: Option<&clap_color>,
+        label: Option<&CStr>,
+        description: Option<&CStr>,
+    ) {
+        let param_indication = self.param_indication.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin(
+            "clap_plugin_param_indication::set_mapping",
+            record! {
+                param_id: param_id,
+                has_mapping: has_mapping,
+                color: format_color(color),
+                label: label.map(CStr::to_string_lossy),
+                description: description.map(CStr::to_string_lossy)
+            },
+        );
+
+        unsafe {
+            clap_call! {
+                param_indication=>set_mapping(
+                    plugin,
+                    param_id,
+                    has_mapping,
+                    color.map_or(std::ptr::null(), |color| color as *const _),
+                    label.map_or(std::ptr::null(), CStr::as_ptr),
+                    description.map_or(std::ptr::null(), CStr::as_ptr)
+                )
+            }
+        }
+    }
+
+    /// Indicate the parameter's automation state, using one of the
+    /// `CLAP_PARAM_INDICATION_AUTOMATION_*` constants. The color is passed to the plugin as a null
+    /// pointer if it is `None`.
+    pub fn set_automation(&self, param_id: clap_id, automation_state: u32, color: Option<&clap_color>) {
+        let param_indication = self.param_indication.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin(
+            "clap_plugin_param_indication::set_automation",
+            record! {
+                param_id: param_id,
+                automation_state: match automation_state {
+                    CLAP_PARAM_INDICATION_AUTOMATION_NONE => "CLAP_PARAM_INDICATION_AUTOMATION_NONE",
+                    CLAP_PARAM_INDICATION_AUTOMATION_PRESENT => "CLAP_PARAM_INDICATION_AUTOMATION_PRESENT",
+                    CLAP_PARAM_INDICATION_AUTOMATION_PLAYING => "CLAP_PARAM_INDICATION_AUTOMATION_PLAYING",
+                    CLAP_PARAM_INDICATION_AUTOMATION_RECORDING => "CLAP_PARAM_INDICATION_AUTOMATION_RECORDING",
+                    CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING => "CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING",
+                    _ => "<unknown>",
+                },
+                color: format_color(color)
+            },
+        );
+
+        unsafe {
+            clap_call! {
+                param_indication=>set_automation(
+                    plugin,
+                    param_id,
+                    automation_state,
+                    color.map_or(std::ptr::null(), |color| color as *const _)
+                )
+            }
+        }
+    }
+}
+
+fn format_color(color: Option<&clap_color>) -> String {
+    match color {
+        Some(color) => format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            color.red, color.green, color.blue, color.alpha
+        ),
+        None => String::from("<null>"),
+    }
+}
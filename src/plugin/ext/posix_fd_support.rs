@@ -0,0 +1,47 @@
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::posix_fd_support::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+pub struct PosixFdSupport<'a> {
+    plugin: &'a Plugin<'a>,
+    posix_fd_support: NonNull<clap_plugin_posix_fd_support>,
+}
+
+impl<'a> Extension for PosixFdSupport<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_POSIX_FD_SUPPORT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_posix_fd_support;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            posix_fd_support: extension_struct,
+        }
+    }
+}
+
+impl<'a> PosixFdSupport<'a> {
+    pub fn on_fd(&self, fd: i32, flags: clap_posix_fd_flags) {
+        let posix_fd_support = self.posix_fd_support.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin(
+            "clap_plugin_posix_fd_support::on_fd",
+            record! {
+                fd: fd,
+                read: flags & CLAP_POSIX_FD_READ != 0,
+                write: flags & CLAP_POSIX_FD_WRITE != 0,
+                error: flags & CLAP_POSIX_FD_ERROR != 0
+            },
+        );
+
+        unsafe {
+            clap_call! { posix_fd_support=>on_fd(plugin, fd, flags) }
+        }
+    }
+}
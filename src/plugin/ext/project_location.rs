@@ -0,0 +1,71 @@
+//! Abstractions for interacting with the draft `location` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::ffi::project_location::*;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::color::clap_color;
+use std::ffi::{CStr, CString};
+use std::ptr::{NonNull, null};
+
+/// Abstraction for the `location` extension covering the main thread functionality.
+pub struct ProjectLocation<'a> {
+    plugin: &'a Plugin<'a>,
+    project_location: NonNull<clap_plugin_location>,
+}
+
+impl<'a> Extension for ProjectLocation<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_LOCATION];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_location;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            project_location: extension_struct,
+        }
+    }
+}
+
+/// A single element in the path to the plugin instance's location within the project.
+#[derive(Debug, Clone)]
+pub struct LocationElement {
+    /// The element's internal ID, or a null pointer if this is `None`.
+    pub id: Option<CString>,
+    /// The element's name, or a null pointer if this is `None`.
+    pub name: Option<CString>,
+    /// One of the `CLAP_PLUGIN_LOCATION_*` constants.
+    pub kind: u32,
+    pub index_in_parent: u32,
+    pub color: clap_color,
+}
+
+impl<'a> ProjectLocation<'a> {
+    /// Tell the plugin where it is located in the project. The last element should refer to the
+    /// plugin instance itself.
+    pub fn set(&self, path: &[LocationElement]) {
+        let project_location = self.project_location.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let elements: Vec<clap_plugin_location_element> = path
+            .iter()
+            .map(|element| clap_plugin_location_element {
+                id: element.id.as_ref().map_or(null(), |id| id.as_ptr()),
+                name: element.name.as_ref().map_or(null(), |name| name.as_ptr()),
+                kind: element.kind,
+                index_in_parent: element.index_in_parent,
+                color: element.color,
+            })
+            .collect();
+
+        let _span = Span::begin(
+            "clap_plugin_location::set",
+            record!(num_elements: elements.len() as u32),
+        );
+        unsafe {
+            clap_call! { project_location=>set(plugin, elements.as_ptr(), elements.len() as u32) }
+        }
+    }
+}
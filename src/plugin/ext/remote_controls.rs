@@ -0,0 +1,146 @@
+//! Abstractions for interacting with the `remote-controls` extension.
+
+use super::Extension;
+use crate::cli::tracing::{Recordable, Recorder, Span, record};
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::{c_char_slice_to_string, clap_call};
+use anyhow::{Context, Result};
+use clap_sys::ext::remote_controls::*;
+use clap_sys::id::clap_id;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// The byte pattern written after the page struct to detect plugins writing past its end.
+const GUARD_BYTE: u8 = 0xcd;
+const GUARD_SIZE: usize = 64;
+
+/// Abstraction for the `remote-controls` extension covering the main thread functionality.
+pub struct RemoteControls<'a> {
+    plugin: &'a Plugin<'a>,
+    remote_controls: NonNull<clap_plugin_remote_controls>,
+}
+
+/// A single remote controls page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteControlsPage {
+    pub section_name: String,
+    pub page_id: clap_id,
+    pub page_name: String,
+    /// The parameters mapped to the page's controls. Unmapped controls are set to
+    /// `CLAP_INVALID_ID`.
+    pub param_ids: [clap_id; CLAP_REMOTE_CONTROLS_COUNT],
+    pub is_for_preset: bool,
+}
+
+impl<'a> Extension for RemoteControls<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_REMOTE_CONTROLS, CLAP_EXT_REMOTE_CONTROLS_COMPAT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_remote_controls;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            remote_controls: extension_struct,
+        }
+    }
+}
+
+impl RemoteControls<'_> {
+    /// Get all of the plugin's remote controls pages. Returns an error if the plugin fails to
+    /// return one of the pages it says it has, if it writes past the end of the page struct, or if
+    /// the page's names are not valid UTF-8.
+    pub fn pages(&self) -> Result<Vec<RemoteControlsPage>> {
+        let num_pages = self.get_raw_count();
+
+        let mut pages = Vec::with_capacity(num_pages as usize);
+        for index in 0..num_pages {
+            let page = self.get_raw_page(index)?;
+
+            let section_name = c_char_slice_to_string(&page.section_name)
+                .with_context(|| format!("Could not read the section name for remote controls page {index}"))?;
+            let page_name = c_char_slice_to_string(&page.page_name)
+                .with_context(|| format!("Could not read the page name for remote controls page {index}"))?;
+
+            pages.push(RemoteControlsPage {
+                section_name,
+                page_id: page.page_id,
+                page_name,
+                param_ids: page.param_ids,
+                is_for_preset: page.is_for_preset,
+            });
+        }
+
+        Ok(pages)
+    }
+
+    fn get_raw_count(&self) -> u32 {
+        let remote_controls = self.remote_controls.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_remote_controls::count", ());
+        let result = unsafe {
+            clap_call! { remote_controls=>count(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    fn get_raw_page(&self, page_index: u32) -> Result<clap_remote_controls_page> {
+        /// The page struct followed by some guard bytes. If the plugin tries to write more than
+        /// `CLAP_REMOTE_CONTROLS_COUNT` parameter IDs to the page, then this will catch that.
+        #[repr(C)]
+        struct GuardedPage {
+            page: clap_remote_controls_page,
+            guard: [u8; GUARD_SIZE],
+        }
+
+        let remote_controls = self.remote_controls.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_remote_controls::get", record! { page_index: page_index });
+
+        unsafe {
+            let mut guarded = GuardedPage {
+                page: std::mem::zeroed(),
+                guard: [GUARD_BYTE; GUARD_SIZE],
+            };
+
+            if !clap_call! { remote_controls=>get(plugin, page_index, &mut guarded.page) } {
+                span.finish(record!(result: false));
+                anyhow::bail!(
+                    "'clap_plugin_remote_controls::get()' returned false for page {page_index} ({} total pages).",
+                    self.get_raw_count()
+                );
+            }
+
+            span.finish(record!(result: guarded.page));
+
+            if guarded.guard.iter().any(|&byte| byte != GUARD_BYTE) {
+                anyhow::bail!(
+                    "The plugin wrote past the end of the 'clap_remote_controls_page' struct for page {page_index}. \
+                     Pages may not contain more than {CLAP_REMOTE_CONTROLS_COUNT} parameters."
+                );
+            }
+
+            Ok(guarded.page)
+        }
+    }
+}
+
+impl Recordable for clap_remote_controls_page {
+    fn record(&self, record: &mut dyn Recorder) {
+        record.record(
+            "section_name",
+            c_char_slice_to_string(&self.section_name).unwrap_or_else(|_| String::from("<invalid>")),
+        );
+        record.record("page_id", self.page_id);
+        record.record(
+            "page_name",
+            c_char_slice_to_string(&self.page_name).unwrap_or_else(|_| String::from("<invalid>")),
+        );
+        record.record("param_ids", format!("{:?}", self.param_ids));
+        record.record("is_for_preset", self.is_for_preset);
+    }
+}
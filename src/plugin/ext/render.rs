@@ -0,0 +1,71 @@
+//! Abstractions for interacting with the `render` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::render::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `render` extension covering the main thread functionality.
+pub struct Render<'a> {
+    plugin: &'a Plugin<'a>,
+    render: NonNull<clap_plugin_render>,
+}
+
+impl<'a> Extension for Render<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_RENDER];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_render;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            render: extension_struct,
+        }
+    }
+}
+
+impl<'a> Render<'a> {
+    /// Whether the plugin needs to be processed in real time, for instance because it deals with
+    /// live audio or MIDI IO. The host should not try to render such plugins offline.
+    pub fn has_hard_realtime_requirement(&self) -> bool {
+        let render = self.render.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_render::has_hard_realtime_requirement", ());
+        let result = unsafe {
+            clap_call! { render=>has_hard_realtime_requirement(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Switch the plugin to realtime or offline rendering. Returns whether the plugin accepted the
+    /// new rendering mode.
+    pub fn set(&self, mode: clap_plugin_render_mode) -> bool {
+        let render = self.render.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_render::set",
+            record! {
+                mode: match mode {
+                    CLAP_RENDER_REALTIME => "CLAP_RENDER_REALTIME",
+                    CLAP_RENDER_OFFLINE => "CLAP_RENDER_OFFLINE",
+                    _ => "<unknown>",
+                }
+            },
+        );
+
+        let result = unsafe {
+            clap_call! { render=>set(plugin, mode) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+}
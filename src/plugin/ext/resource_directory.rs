@@ -0,0 +1,132 @@
+//! Abstractions for interacting with the draft `resource-directory` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use anyhow::{Context, Result};
+use clap_sys::ext::draft::resource_directory::*;
+use std::ffi::{CStr, CString, c_char};
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+
+/// The size of the buffer passed to `clap_plugin_resource_directory::get_file_path()`.
+const FILE_PATH_BUFFER_SIZE: usize = 4096;
+
+/// Abstraction for the `resource-directory` extension covering the main thread functionality.
+pub struct ResourceDirectory<'a> {
+    plugin: &'a Plugin<'a>,
+    resource_directory: NonNull<clap_plugin_resource_directory>,
+}
+
+impl<'a> Extension for ResourceDirectory<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_RESOURCE_DIRECTORY];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_resource_directory;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            resource_directory: extension_struct,
+        }
+    }
+}
+
+impl<'a> ResourceDirectory<'a> {
+    /// Set the directory the plugin can store its resources in, or clear it if `path` is `None`.
+    /// Returns an error if the path cannot be passed to the plugin as a UTF-8 C-string.
+    pub fn set_directory(&self, path: Option<&Path>, is_shared: bool) -> Result<()> {
+        let resource_directory = self.resource_directory.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let path_cstring = path
+            .map(|path| {
+                let path_str = path
+                    .to_str()
+                    .with_context(|| format!("'{}' is not a valid UTF-8 path", path.display()))?;
+                CString::new(path_str).context("The path contains internal null bytes")
+            })
+            .transpose()?;
+
+        let _span = Span::begin(
+            "clap_plugin_resource_directory::set_directory",
+            record! {
+                path: path.map(|path| path.display().to_string()).unwrap_or_default(),
+                is_shared: is_shared
+            },
+        );
+        unsafe {
+            clap_call! {
+                resource_directory=>set_directory(
+                    plugin,
+                    path_cstring.as_ref().map_or(std::ptr::null(), |path| path.as_ptr()),
+                    is_shared
+                )
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ask the plugin to copy its resources into the resource directory. If `all` is set, the
+    /// plugin should also collect the files that belong to its factory content.
+    pub fn collect(&self, all: bool) {
+        let resource_directory = self.resource_directory.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_resource_directory::collect", record!(all: all));
+        unsafe {
+            clap_call! { resource_directory=>collect(plugin, all) }
+        }
+    }
+
+    /// Get the paths of all files the plugin uses in the resource directory. Returns an error if
+    /// the plugin fails to return a path, or if a path is not a valid UTF-8 string.
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        let resource_directory = self.resource_directory.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_resource_directory::get_files_count", ());
+        let num_files = unsafe {
+            clap_call! { resource_directory=>get_files_count(plugin) }
+        };
+        span.finish(record!(result: num_files));
+
+        let mut files = Vec::with_capacity(num_files as usize);
+        for index in 0..num_files {
+            let mut path = [0 as c_char; FILE_PATH_BUFFER_SIZE];
+
+            let span = Span::begin("clap_plugin_resource_directory::get_file_path", record!(index: index));
+            let result = unsafe {
+                clap_call! {
+                    resource_directory=>get_file_path(plugin, index, path.as_mut_ptr(), FILE_PATH_BUFFER_SIZE as u32)
+                }
+            };
+            span.finish(record!(result: result));
+
+            if result < 0 {
+                anyhow::bail!(
+                    "'clap_plugin_resource_directory::get_file_path()' returned {result} for file index {index}, \
+                     even though the plugin reported {num_files} files."
+                );
+            }
+            if result as usize >= FILE_PATH_BUFFER_SIZE {
+                anyhow::bail!(
+                    "'clap_plugin_resource_directory::get_file_path()' returned a length of {result} for file index \
+                     {index}, but the buffer only has room for {FILE_PATH_BUFFER_SIZE} bytes."
+                );
+            }
+
+            let path = CStr::from_bytes_until_nul(unsafe {
+                std::slice::from_raw_parts(path.as_ptr() as *const u8, FILE_PATH_BUFFER_SIZE)
+            })
+            .with_context(|| format!("The path for file index {index} is not null-terminated"))?
+            .to_str()
+            .with_context(|| format!("The path for file index {index} contains invalid UTF-8"))?;
+            files.push(PathBuf::from(path));
+        }
+
+        Ok(files)
+    }
+}
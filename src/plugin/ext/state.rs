@@ -22,7 +22,7 @@ pub struct State<'a> {
 
 /// An input stream backed by a slice.
 #[derive(Debug)]
-struct InputStream<'a> {
+pub(crate) struct InputStream<'a> {
     /// The thread ID that created this stream. Used to verify that the plugin is calling the stream
     /// methods from the same thread.
     expected_thread_id: ThreadId,
@@ -41,7 +41,7 @@ struct InputStream<'a> {
 
 /// An output stream backed by a vector.
 #[derive(Debug)]
-struct OutputStream {
+pub(crate) struct OutputStream {
     /// The thread ID that created this stream. Used to verify that the plugin is calling the stream
     /// methods from the same thread.
     expected_thread_id: ThreadId,
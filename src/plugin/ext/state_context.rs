@@ -0,0 +1,97 @@
+//! Abstractions for interacting with the `state-context` extension.
+
+use super::Extension;
+use super::state::{InputStream, OutputStream};
+use crate::cli::tracing::{Span, record};
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::{Proxy, clap_call};
+use anyhow::Result;
+use clap_sys::ext::state_context::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `state-context` extension covering the main thread functionality.
+pub struct StateContext<'a> {
+    plugin: &'a Plugin<'a>,
+    state_context: NonNull<clap_plugin_state_context>,
+}
+
+impl<'a> Extension for StateContext<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_STATE_CONTEXT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_state_context;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            state_context: extension_struct,
+        }
+    }
+}
+
+impl StateContext<'_> {
+    /// Retrieve the plugin's state for the given context. Returns an error if the plugin returned
+    /// `false`.
+    pub fn save(&self, context_type: clap_plugin_state_context_type) -> Result<Vec<u8>> {
+        let stream = OutputStream::new(None);
+        let state_context = self.state_context.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_state_context::save",
+            record! { context_type: context_type_name(context_type) },
+        );
+        let result = unsafe {
+            clap_call! { state_context=>save(plugin, Proxy::vtable(&stream), context_type) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(stream.take())
+        } else {
+            anyhow::bail!(
+                "'clap_plugin_state_context::save()' returned false for '{}'",
+                context_type_name(context_type)
+            );
+        }
+    }
+
+    /// Restore previously stored state for the given context. Returns an error if the plugin
+    /// returned `false`.
+    pub fn load(&self, state: &[u8], context_type: clap_plugin_state_context_type) -> Result<()> {
+        let stream = InputStream::new(state, None);
+        let state_context = self.state_context.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_state_context::load",
+            record! { context_type: context_type_name(context_type) },
+        );
+        let result = unsafe {
+            clap_call! { state_context=>load(plugin, Proxy::vtable(&stream), context_type) }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "'clap_plugin_state_context::load()' returned false for '{}'",
+                context_type_name(context_type)
+            );
+        }
+    }
+}
+
+/// The name of the `CLAP_STATE_CONTEXT_*` constant corresponding to a context type.
+pub fn context_type_name(context_type: clap_plugin_state_context_type) -> &'static str {
+    match context_type {
+        CLAP_STATE_CONTEXT_FOR_PRESET => "CLAP_STATE_CONTEXT_FOR_PRESET",
+        CLAP_STATE_CONTEXT_FOR_DUPLICATE => "CLAP_STATE_CONTEXT_FOR_DUPLICATE",
+        CLAP_STATE_CONTEXT_FOR_PROJECT => "CLAP_STATE_CONTEXT_FOR_PROJECT",
+        _ => "<unknown>",
+    }
+}
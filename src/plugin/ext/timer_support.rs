@@ -0,0 +1,39 @@
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::timer_support::{CLAP_EXT_TIMER_SUPPORT, clap_plugin_timer_support};
+use clap_sys::id::clap_id;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+pub struct TimerSupport<'a> {
+    plugin: &'a Plugin<'a>,
+    timer_support: NonNull<clap_plugin_timer_support>,
+}
+
+impl<'a> Extension for TimerSupport<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_TIMER_SUPPORT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_timer_support;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            timer_support: extension_struct,
+        }
+    }
+}
+
+impl<'a> TimerSupport<'a> {
+    pub fn on_timer(&self, timer_id: clap_id) {
+        let timer_support = self.timer_support.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_timer_support::on_timer", record! { timer_id: timer_id });
+        unsafe {
+            clap_call! { timer_support=>on_timer(plugin, timer_id) }
+        }
+    }
+}
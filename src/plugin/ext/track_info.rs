@@ -0,0 +1,43 @@
+//! Abstractions for interacting with the `track-info` extension.
+
+use crate::cli::tracing::Span;
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::track_info::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `track-info` extension covering the main thread functionality.
+pub struct TrackInfo<'a> {
+    plugin: &'a Plugin<'a>,
+    track_info: NonNull<clap_plugin_track_info>,
+}
+
+impl<'a> Extension for TrackInfo<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_TRACK_INFO, CLAP_EXT_TRACK_INFO_COMPAT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_track_info;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            track_info: extension_struct,
+        }
+    }
+}
+
+impl<'a> TrackInfo<'a> {
+    /// Inform the plugin that the host's track information has changed. The plugin is expected to
+    /// query it again through `clap_host_track_info::get()`.
+    pub fn changed(&self) {
+        let track_info = self.track_info.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_track_info::changed", ());
+        unsafe {
+            clap_call! { track_info=>changed(plugin) }
+        }
+    }
+}
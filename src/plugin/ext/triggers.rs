@@ -0,0 +1,146 @@
+//! Abstractions for interacting with the draft `triggers` extension.
+
+use super::Extension;
+use crate::cli::tracing::{Span, record};
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::{self, clap_call};
+use anyhow::{Context, Result};
+use clap_sys::ext::draft::triggers::*;
+use clap_sys::id::{CLAP_INVALID_ID, clap_id};
+use std::collections::BTreeMap;
+use std::ffi::{CStr, c_void};
+use std::ptr::NonNull;
+
+pub type TriggerInfo = BTreeMap<clap_id, Trigger>;
+
+/// All trigger info flags defined by the extension. Any other bit being set is an error.
+const ALL_TRIGGER_FLAGS: clap_trigger_info_flags = CLAP_TRIGGER_IS_AUTOMATABLE_PER_NOTE_ID
+    | CLAP_TRIGGER_IS_AUTOMATABLE_PER_KEY
+    | CLAP_TRIGGER_IS_AUTOMATABLE_PER_CHANNEL
+    | CLAP_TRIGGER_IS_AUTOMATABLE_PER_PORT;
+
+/// Abstraction for the `triggers` extension covering the main thread functionality.
+pub struct Triggers<'a> {
+    plugin: &'a Plugin<'a>,
+    triggers: NonNull<clap_plugin_triggers>,
+}
+
+impl<'a> Extension for Triggers<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_TRIGGERS];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_triggers;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            triggers: extension_struct,
+        }
+    }
+}
+
+/// Information about a trigger.
+#[derive(Debug, Clone)]
+pub struct Trigger {
+    /// This should be provided to the plugin when sending trigger events for this trigger.
+    pub cookie: *mut c_void,
+    /// The raw trigger flags bit field.
+    pub flags: clap_trigger_info_flags,
+}
+
+unsafe impl Send for Trigger {}
+unsafe impl Sync for Trigger {}
+
+impl Triggers<'_> {
+    /// Get information about all of the plugin's triggers. Returns an error if the plugin's
+    /// triggers are inconsistent. For instance, if there are multiple triggers with the same ID,
+    /// if a trigger has no name, or if it sets flags that don't exist. This uses a BTreeMap to
+    /// ensure the order is consistent between runs.
+    pub fn info(&self) -> Result<TriggerInfo> {
+        let mut result = BTreeMap::new();
+        let num_triggers = self.get_raw_trigger_count();
+
+        for i in 0..num_triggers {
+            let info = self.get_raw_trigger_info(i)?;
+
+            if info.id == CLAP_INVALID_ID {
+                anyhow::bail!("The stable ID for trigger {i} is `CLAP_INVALID_ID`.");
+            }
+
+            let name = util::c_char_slice_to_string(&info.name)
+                .with_context(|| format!("Could not read the name for trigger with stable ID {}", info.id))?;
+            if name.is_empty() {
+                anyhow::bail!("The name for trigger with stable ID {} is empty.", info.id);
+            }
+
+            // Same as with parameters, the module string isn't used but it should still be
+            // consistent
+            let module = util::c_char_slice_to_string(&info.module).with_context(|| {
+                format!(
+                    "Could not read the module name for trigger '{}' (stable ID {})",
+                    &name, info.id
+                )
+            })?;
+
+            if module.starts_with('/') || module.ends_with('/') || module.contains("//") {
+                anyhow::bail!(
+                    "The module name for trigger '{}' (stable ID {}) contains leading, trailing, or multiple \
+                     subsequent slashes: '{}'.",
+                    &name,
+                    info.id,
+                    module
+                )
+            }
+
+            if (info.flags & !ALL_TRIGGER_FLAGS) != 0 {
+                anyhow::bail!(
+                    "Trigger '{}' (stable ID {}) has unknown flags set: {:#x}.",
+                    &name,
+                    info.id,
+                    info.flags & !ALL_TRIGGER_FLAGS
+                )
+            }
+
+            let processed_info = Trigger {
+                cookie: info.cookie,
+                flags: info.flags,
+            };
+
+            if result.insert(info.id, processed_info).is_some() {
+                anyhow::bail!("The plugin contains multiple triggers with stable ID {}.", info.id);
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn get_raw_trigger_count(&self) -> u32 {
+        let triggers = self.triggers.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_triggers::count", ());
+        let result = unsafe {
+            clap_call! { triggers=>count(plugin) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    fn get_raw_trigger_info(&self, index: u32) -> Result<clap_trigger_info> {
+        let triggers = self.triggers.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin("clap_plugin_triggers::get_info", record! { index: index });
+        unsafe {
+            let mut result = clap_trigger_info { ..std::mem::zeroed() };
+            if !clap_call! { triggers=>get_info(plugin, index, &mut result) } {
+                let num_triggers = self.get_raw_trigger_count();
+                anyhow::bail!("Plugin returned false when querying trigger {index} ({num_triggers} total triggers).");
+            }
+
+            span.finish(record!(result: result.id));
+            Ok(result)
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Abstractions for interacting with the draft `tuning` extension.
+
+use crate::cli::tracing::Span;
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::draft::tuning::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `tuning` extension covering the main thread functionality.
+pub struct Tuning<'a> {
+    plugin: &'a Plugin<'a>,
+    tuning: NonNull<clap_plugin_tuning_t>,
+}
+
+impl<'a> Extension for Tuning<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_TUNING];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_tuning_t;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            tuning: extension_struct,
+        }
+    }
+}
+
+impl<'a> Tuning<'a> {
+    /// Inform the plugin that a tuning was added to or removed from the host's tuning pool. The
+    /// plugin is expected to query the pool again through `clap_host_tuning`.
+    pub fn changed(&self) {
+        let tuning = self.tuning.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_tuning::changed", ());
+        unsafe {
+            clap_call! { tuning=>changed(plugin) }
+        }
+    }
+}
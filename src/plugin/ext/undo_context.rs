@@ -0,0 +1,81 @@
+//! Abstractions for interacting with the draft `undo-context` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use clap_sys::ext::draft::undo::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Abstraction for the `undo-context` extension covering the main thread functionality.
+pub struct UndoContext<'a> {
+    plugin: &'a Plugin<'a>,
+    undo_context: NonNull<clap_plugin_undo_context>,
+}
+
+impl<'a> Extension for UndoContext<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_UNDO_CONTEXT];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_undo_context;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            undo_context: extension_struct,
+        }
+    }
+}
+
+impl<'a> UndoContext<'a> {
+    /// Tell the plugin whether the host can currently undo a change.
+    pub fn set_can_undo(&self, can_undo: bool) {
+        let undo_context = self.undo_context.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_undo_context::set_can_undo", record!(can_undo: can_undo));
+        unsafe {
+            clap_call! { undo_context=>set_can_undo(plugin, can_undo) };
+        }
+    }
+
+    /// Tell the plugin whether the host can currently redo a change.
+    pub fn set_can_redo(&self, can_redo: bool) {
+        let undo_context = self.undo_context.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin("clap_plugin_undo_context::set_can_redo", record!(can_redo: can_redo));
+        unsafe {
+            clap_call! { undo_context=>set_can_redo(plugin, can_redo) };
+        }
+    }
+
+    /// Tell the plugin the name of the change that would be undone next.
+    pub fn set_undo_name(&self, name: &CStr) {
+        let undo_context = self.undo_context.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin(
+            "clap_plugin_undo_context::set_undo_name",
+            record!(name: name.to_string_lossy()),
+        );
+        unsafe {
+            clap_call! { undo_context=>set_undo_name(plugin, name.as_ptr()) };
+        }
+    }
+
+    /// Tell the plugin the name of the change that would be redone next.
+    pub fn set_redo_name(&self, name: &CStr) {
+        let undo_context = self.undo_context.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let _span = Span::begin(
+            "clap_plugin_undo_context::set_redo_name",
+            record!(name: name.to_string_lossy()),
+        );
+        unsafe {
+            clap_call! { undo_context=>set_redo_name(plugin, name.as_ptr()) };
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Abstractions for interacting with the draft `undo-delta` extension.
+
+use crate::cli::tracing::{Span, record};
+use crate::plugin::ext::Extension;
+use crate::plugin::instance::Plugin;
+use crate::plugin::util::clap_call;
+use anyhow::Result;
+use clap_sys::ext::draft::undo::*;
+use clap_sys::id::{CLAP_INVALID_ID, clap_id};
+use std::ffi::{CStr, c_void};
+use std::ptr::NonNull;
+
+/// Abstraction for the `undo-delta` extension covering the main thread functionality.
+pub struct UndoDelta<'a> {
+    plugin: &'a Plugin<'a>,
+    undo_delta: NonNull<clap_plugin_undo_delta>,
+}
+
+impl<'a> Extension for UndoDelta<'a> {
+    const IDS: &'static [&'static CStr] = &[CLAP_EXT_UNDO_DELTA];
+
+    type Plugin = &'a Plugin<'a>;
+    type Struct = clap_plugin_undo_delta;
+
+    unsafe fn new(plugin: &'a Plugin<'a>, extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            plugin,
+            undo_delta: extension_struct,
+        }
+    }
+}
+
+impl<'a> UndoDelta<'a> {
+    /// Query the plugin's delta properties. The struct is initialized with no deltas so a plugin
+    /// that does not write anything does not end up with garbage values.
+    pub fn get_delta_properties(&self) -> clap_undo_delta_properties {
+        let undo_delta = self.undo_delta.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let mut properties = clap_undo_delta_properties {
+            has_delta: false,
+            are_deltas_persistent: false,
+            format_version: CLAP_INVALID_ID,
+        };
+
+        let span = Span::begin("clap_plugin_undo_delta::get_delta_properties", ());
+        unsafe {
+            clap_call! { undo_delta=>get_delta_properties(plugin, &mut properties) };
+        }
+
+        span.finish(record!(
+            has_delta: properties.has_delta,
+            are_deltas_persistent: properties.are_deltas_persistent,
+            format_version: properties.format_version
+        ));
+        properties
+    }
+
+    /// Whether the plugin can apply deltas created with this format version.
+    pub fn can_use_delta_format_version(&self, format_version: clap_id) -> bool {
+        let undo_delta = self.undo_delta.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_undo_delta::can_use_delta_format_version",
+            record!(format_version: format_version),
+        );
+        let result = unsafe {
+            clap_call! { undo_delta=>can_use_delta_format_version(plugin, format_version) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    /// Undo a change using the delta the plugin provided through `clap_host_undo::change_made()`.
+    /// Returns an error if the plugin could not apply the delta.
+    pub fn undo(&self, format_version: clap_id, delta: &[u8]) -> Result<()> {
+        let undo_delta = self.undo_delta.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_undo_delta::undo",
+            record!(format_version: format_version, delta_size: delta.len()),
+        );
+        let result = unsafe {
+            clap_call! { undo_delta=>undo(plugin, format_version, delta.as_ptr() as *const c_void, delta.len()) }
+        };
+
+        span.finish(record!(result: result));
+        if !result {
+            anyhow::bail!(
+                "'clap_plugin_undo_delta::undo()' returned false for a {} byte delta with format version \
+                 {format_version}.",
+                delta.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Redo a change using the delta the plugin provided through `clap_host_undo::change_made()`.
+    /// Returns an error if the plugin could not apply the delta.
+    pub fn redo(&self, format_version: clap_id, delta: &[u8]) -> Result<()> {
+        let undo_delta = self.undo_delta.as_ptr();
+        let plugin = self.plugin.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_undo_delta::redo",
+            record!(format_version: format_version, delta_size: delta.len()),
+        );
+        let result = unsafe {
+            clap_call! { undo_delta=>redo(plugin, format_version, delta.as_ptr() as *const c_void, delta.len()) }
+        };
+
+        span.finish(record!(result: result));
+        if !result {
+            anyhow::bail!(
+                "'clap_plugin_undo_delta::redo()' returned false for a {} byte delta with format version \
+                 {format_version}.",
+                delta.len()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -7,7 +7,6 @@ use std::ffi::CStr;
 use std::mem::zeroed;
 use std::ptr::NonNull;
 
-#[allow(unused)]
 pub struct VoiceInfo<'a> {
     plugin: &'a Plugin<'a>,
     voice_info: NonNull<clap_plugin_voice_info>,
@@ -28,7 +27,6 @@ impl<'a> Extension for VoiceInfo<'a> {
 }
 
 impl<'a> VoiceInfo<'a> {
-    #[allow(unused)]
     pub fn get(&self) -> Option<clap_voice_info> {
         let voice_info = self.voice_info.as_ptr();
         let plugin = self.plugin.as_ptr();
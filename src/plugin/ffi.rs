@@ -0,0 +1,9 @@
+//! FFI definitions for draft extensions that are not yet part of `clap-sys`. These follow the
+//! layout of the corresponding CLAP headers, and should be removed once `clap-sys` catches up.
+
+#![allow(non_camel_case_types)]
+
+pub mod gain_adjustment_metering;
+pub mod mini_curve_display;
+pub mod project_location;
+pub mod scratch_memory;
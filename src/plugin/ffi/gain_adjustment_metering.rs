@@ -0,0 +1,12 @@
+//! `ext/draft/gain-adjustment-metering.h`
+
+use clap_sys::plugin::clap_plugin;
+use std::ffi::CStr;
+
+pub const CLAP_EXT_GAIN_ADJUSTMENT_METERING: &CStr = c"clap.gain-adjustment-metering/0";
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct clap_plugin_gain_adjustment_metering {
+    pub get_adjustment_metering: Option<unsafe extern "C" fn(plugin: *const clap_plugin) -> f64>,
+}
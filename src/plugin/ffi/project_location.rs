@@ -0,0 +1,31 @@
+//! `ext/draft/location.h`
+
+use clap_sys::color::clap_color;
+use clap_sys::plugin::clap_plugin;
+use std::ffi::{CStr, c_char};
+
+pub const CLAP_EXT_LOCATION: &CStr = c"clap.location/2";
+
+pub const CLAP_PLUGIN_LOCATION_PROJECT: u32 = 1;
+pub const CLAP_PLUGIN_LOCATION_TRACK_GROUP: u32 = 2;
+pub const CLAP_PLUGIN_LOCATION_TRACK: u32 = 3;
+pub const CLAP_PLUGIN_LOCATION_DEVICE: u32 = 4;
+pub const CLAP_PLUGIN_LOCATION_NESTED_DEVICE_CHAIN: u32 = 5;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct clap_plugin_location_element {
+    pub id: *const c_char,
+    pub name: *const c_char,
+    pub kind: u32,
+    pub index_in_parent: u32,
+    pub color: clap_color,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct clap_plugin_location {
+    pub set: Option<
+        unsafe extern "C" fn(plugin: *const clap_plugin, path: *const clap_plugin_location_element, num_elements: u32),
+    >,
+}
@@ -0,0 +1,15 @@
+//! `ext/draft/scratch-memory.h`
+
+use clap_sys::host::clap_host;
+use std::ffi::{CStr, c_void};
+
+pub const CLAP_EXT_SCRATCH_MEMORY: &CStr = c"clap.scratch-memory/1";
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct clap_host_scratch_memory {
+    pub reserve: Option<
+        unsafe extern "C" fn(host: *const clap_host, scratch_size_bytes: u32, max_concurrency_hint: u32) -> bool,
+    >,
+    pub access: Option<unsafe extern "C" fn(host: *const clap_host) -> *mut c_void>,
+}
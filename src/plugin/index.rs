@@ -1,6 +1,7 @@
 //! Utilities and data structures for indexing plugins and presets.
 
 use crate::cli::sandbox::SandboxOperation;
+use crate::plugin::invalidation::InvalidationSource;
 use crate::plugin::library::PluginMetadata;
 use crate::plugin::preset_discovery::{LocationValue, PresetFile, Soundpack};
 use anyhow::{Context, Result};
@@ -23,6 +24,9 @@ pub struct ScannedLibrary {
     pub version: (u32, u32, u32),
     pub plugins: Vec<PluginMetadata>,
     pub preset_providers: Vec<ScannedPresets>,
+    /// The files the host should monitor to know when the library needs to be scanned again, if
+    /// the library exposes a plugin invalidation factory.
+    pub invalidation_sources: Vec<InvalidationSource>,
 }
 
 /// Preset information declared by a preset provider.
@@ -91,6 +95,19 @@ impl SandboxOperation for SandboxedScanLibrary {
 pub fn scan_library(plugin_path: &Path, scan_presets: bool) -> Result<ScannedLibrary> {
     let library = crate::plugin::library::PluginLibrary::load(plugin_path)?;
     let metadata = library.metadata()?;
+    // Malformed invalidation sources should not prevent the rest of the library from being
+    // indexed. The invalidation tests report these errors in more detail.
+    let invalidation_sources = match library.plugin_invalidation_factory().map(|factory| factory.sources()) {
+        Ok(Ok(sources)) => sources,
+        Ok(Err(err)) => {
+            log::error!(
+                "Could not get the plugin invalidation factory's sources for '{}': {err:#}",
+                plugin_path.display()
+            );
+            vec![]
+        }
+        Err(_) => vec![],
+    };
 
     let presets = if scan_presets && let Ok(preset_discovery_factory) = library.preset_discovery_factory() {
         let metadata = preset_discovery_factory
@@ -134,6 +151,7 @@ pub fn scan_library(plugin_path: &Path, scan_presets: bool) -> Result<ScannedLib
         version: metadata.version,
         plugins: metadata.plugins,
         preset_providers: presets,
+        invalidation_sources,
     })
 }
 
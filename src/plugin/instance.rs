@@ -1,12 +1,32 @@
 //! Abstractions for single CLAP plugin instances for main thread interactions.
 
+mod allocations;
 mod audio_thread;
+mod event_registry;
 mod main_thread;
+mod posix_fds;
+mod scratch_memory;
 mod shared;
+mod timers;
+mod track_info;
+mod transport_control;
+mod tuning;
+mod undo;
 
+pub use allocations::*;
 pub use audio_thread::*;
+pub use event_registry::*;
 pub use main_thread::*;
+pub use posix_fds::*;
+pub use scratch_memory::*;
 pub use shared::*;
+pub use timers::*;
+pub use track_info::*;
+pub use transport_control::*;
+pub use tuning::*;
+pub use undo::*;
+
+use clap_sys::id::clap_id;
 
 /// An event generated by plugin->host callbacks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -40,8 +60,39 @@ pub enum CallbackEvent {
     /// clap_plugin_voice_info::changed()
     VoiceInfoChanged,
 
+    /// clap_host_note_name::changed()
+    NoteNameChanged,
+
+    /// clap_host_remote_controls::changed()
+    RemoteControlsChanged,
+    /// clap_host_remote_controls::suggest_page()
+    RemoteControlsSuggestPage(clap_id),
+
     /// clap_plugin_state::mark_dirty()
     StateMarkDirty,
+
+    /// clap_host_triggers::rescan() with `CLAP_TRIGGER_RESCAN_INFO`
+    TriggersRescanInfo,
+    /// clap_host_triggers::rescan() with `CLAP_TRIGGER_RESCAN_ALL`
+    TriggersRescanAll,
+    /// clap_host_triggers::clear()
+    TriggersClear(clap_id),
+
+    /// clap_host_mini_curve_display::changed() with `CLAP_MINI_CURVE_DISPLAY_CURVE_CHANGED`
+    MiniCurveDisplayCurveChanged,
+    /// clap_host_mini_curve_display::changed() with `CLAP_MINI_CURVE_DISPLAY_AXIS_NAME_CHANGED`
+    MiniCurveDisplayAxisNameChanged,
+
+    /// clap_host_gui::resize_hints_changed()
+    GuiResizeHintsChanged,
+    /// clap_host_gui::request_resize()
+    GuiRequestResize(u32, u32),
+    /// clap_host_gui::request_show()
+    GuiRequestShow,
+    /// clap_host_gui::request_hide()
+    GuiRequestHide,
+    /// clap_host_gui::closed(). Contains the `was_destroyed` flag.
+    GuiClosed(bool),
 }
 
 /// The plugin's current lifecycle state. This is checked extensively to ensure that the plugin is
@@ -0,0 +1,188 @@
+//! Tracking for memory allocations made by the plugin. This is used to check that the plugin does not
+//! allocate memory from within `clap_plugin::process()`.
+//!
+//! On Linux with glibc the validator exports its own `malloc()` family of functions. Those take
+//! precedence over glibc's versions when the plugin's library is loaded, so every allocation made
+//! by the plugin, including those made through C++'s `operator new`, goes through the validator
+//! first. The validator's own Rust allocations bypass these functions through its global allocator
+//! so host callbacks made from within the process call don't count towards the plugin's
+//! allocations. On other platforms allocations are not tracked.
+
+use std::cell::Cell;
+
+/// Whether allocations made by the plugin can be tracked on this platform. If this is `false`, then
+/// [`count_allocations()`] always reports zero allocations.
+pub const ALLOCATION_TRACKING_SUPPORTED: bool = cfg!(all(target_os = "linux", target_env = "gnu"));
+
+thread_local! {
+    /// Whether allocations made on this thread are currently being counted.
+    static IS_COUNTING: Cell<bool> = const { Cell::new(false) };
+    /// The number of allocations made on this thread while [`IS_COUNTING`] was set.
+    static NUM_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Run `f` and count the number of memory allocations made on the current thread while it runs,
+/// excluding the validator's own allocations. Always returns zero allocations if
+/// [`ALLOCATION_TRACKING_SUPPORTED`] is `false`.
+pub fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let was_counting = IS_COUNTING.replace(true);
+    let previous_allocations = NUM_ALLOCATIONS.replace(0);
+
+    let result = f();
+
+    IS_COUNTING.set(was_counting);
+    let num_allocations = NUM_ALLOCATIONS.replace(previous_allocations);
+
+    (result, num_allocations)
+}
+
+/// Called from the exported allocation functions.
+#[cfg_attr(not(all(target_os = "linux", target_env = "gnu")), allow(unused))]
+fn record_allocation() {
+    // These thread locals are const-initialized and don't need to be dropped, so accessing them
+    // never allocates and is safe during thread teardown
+    if IS_COUNTING.get() {
+        NUM_ALLOCATIONS.set(NUM_ALLOCATIONS.get() + 1);
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+mod glibc {
+    use super::record_allocation;
+    use libc::{EINVAL, ENOMEM, c_int, c_void, size_t};
+    use std::alloc::{GlobalAlloc, Layout};
+
+    /// The alignment glibc's `malloc()` guarantees for every allocation.
+    const MIN_ALIGN: usize = 2 * std::mem::size_of::<usize>();
+
+    unsafe extern "C" {
+        fn __libc_malloc(size: size_t) -> *mut c_void;
+        fn __libc_calloc(count: size_t, size: size_t) -> *mut c_void;
+        fn __libc_realloc(ptr: *mut c_void, size: size_t) -> *mut c_void;
+        fn __libc_memalign(alignment: size_t, size: size_t) -> *mut c_void;
+        fn __libc_valloc(size: size_t) -> *mut c_void;
+        fn __libc_pvalloc(size: size_t) -> *mut c_void;
+    }
+
+    /// The validator's global allocator. This is glibc's allocator, but it calls glibc's internal
+    /// functions directly so the validator's own allocations are not counted as the plugin's.
+    pub struct ValidatorAllocator;
+
+    #[global_allocator]
+    static GLOBAL: ValidatorAllocator = ValidatorAllocator;
+
+    unsafe impl GlobalAlloc for ValidatorAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            unsafe {
+                if layout.align() <= MIN_ALIGN {
+                    __libc_malloc(layout.size()) as *mut u8
+                } else {
+                    __libc_memalign(layout.align(), layout.size()) as *mut u8
+                }
+            }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            unsafe {
+                if layout.align() <= MIN_ALIGN {
+                    __libc_calloc(layout.size(), 1) as *mut u8
+                } else {
+                    let ptr = self.alloc(layout);
+                    if !ptr.is_null() {
+                        ptr.write_bytes(0, layout.size());
+                    }
+
+                    ptr
+                }
+            }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+            unsafe { libc::free(ptr as *mut c_void) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            unsafe {
+                if layout.align() <= MIN_ALIGN {
+                    __libc_realloc(ptr as *mut c_void, new_size) as *mut u8
+                } else {
+                    let new_ptr = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+                    if !new_ptr.is_null() {
+                        std::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                        self.dealloc(ptr, layout);
+                    }
+
+                    new_ptr
+                }
+            }
+        }
+    }
+
+    /// Record an allocation if the allocation function returned a non-null pointer. Failed
+    /// allocations don't allocate anything, so they don't count towards the plugin's allocations.
+    fn record_if_allocated(ptr: *mut c_void) -> *mut c_void {
+        if !ptr.is_null() {
+            record_allocation();
+        }
+
+        ptr
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn malloc(size: size_t) -> *mut c_void {
+        record_if_allocated(unsafe { __libc_malloc(size) })
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn calloc(count: size_t, size: size_t) -> *mut c_void {
+        record_if_allocated(unsafe { __libc_calloc(count, size) })
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
+        let new_ptr = unsafe { __libc_realloc(ptr, size) };
+
+        // glibc's `realloc()` frees the pointer when the new size is zero
+        if ptr.is_null() || size != 0 {
+            record_if_allocated(new_ptr)
+        } else {
+            new_ptr
+        }
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn memalign(alignment: size_t, size: size_t) -> *mut c_void {
+        record_if_allocated(unsafe { __libc_memalign(alignment, size) })
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn aligned_alloc(alignment: size_t, size: size_t) -> *mut c_void {
+        record_if_allocated(unsafe { __libc_memalign(alignment, size) })
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn posix_memalign(ptr: *mut *mut c_void, alignment: size_t, size: size_t) -> c_int {
+        if !alignment.is_power_of_two() || !alignment.is_multiple_of(std::mem::size_of::<*mut c_void>()) {
+            return EINVAL;
+        }
+
+        let new_ptr = unsafe { __libc_memalign(alignment, size) };
+        if new_ptr.is_null() {
+            return ENOMEM;
+        }
+
+        record_allocation();
+        unsafe { *ptr = new_ptr };
+        0
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn valloc(size: size_t) -> *mut c_void {
+        record_if_allocated(unsafe { __libc_valloc(size) })
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn pvalloc(size: size_t) -> *mut c_void {
+        record_if_allocated(unsafe { __libc_pvalloc(size) })
+    }
+}
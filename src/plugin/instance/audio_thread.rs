@@ -3,7 +3,7 @@
 use super::{Plugin, PluginStatus};
 use crate::cli::tracing::{Recordable, Recorder, Span, record};
 use crate::plugin::ext::Extension;
-use crate::plugin::instance::{CallbackEvent, MainThreadTask, PluginShared};
+use crate::plugin::instance::{CallbackEvent, MainThreadTask, PluginShared, count_allocations};
 use crate::plugin::process::{InputEventQueue, OutputEventQueue};
 use crate::plugin::util::{Proxy, clap_call};
 use anyhow::Result;
@@ -116,7 +116,6 @@ impl<'a> PluginAudioThread<'a> {
     }
 
     /// Same as [`Self::on_main_thread`], but does not wait for the result and does not block.
-    #[allow(unused)]
     pub fn send_main_thread<F: FnOnce(&Plugin) + Send + 'static>(&self, callback: F) {
         self.shared
             .task_sender
@@ -160,7 +159,7 @@ impl<'a> PluginAudioThread<'a> {
 
         let span = Span::begin("clap_plugin::process", &process);
 
-        let result = unsafe {
+        let (result, num_allocations) = count_allocations(|| unsafe {
             clap_call! { self.as_ptr()=>process(self.as_ptr(), &clap_process {
                 frames_count: process.frames_count,
                 steady_time: process.steady_time.map(|t| t as i64).unwrap_or(-1),
@@ -172,7 +171,8 @@ impl<'a> PluginAudioThread<'a> {
                 in_events: Proxy::vtable(process.input_events),
                 out_events: Proxy::vtable(process.output_events),
             }) }
-        };
+        });
+        self.shared.process_allocations.fetch_add(num_allocations);
 
         span.finish(record!(
             result: match result {
@@ -0,0 +1,61 @@
+//! The event spaces the host exposes to the plugin through the `event-registry` extension.
+
+use clap_sys::events::CLAP_CORE_EVENT_SPACE_ID;
+use std::collections::BTreeSet;
+use std::ffi::{CStr, CString};
+
+/// The space ID assigned to the first event space registered with the host. Subsequent spaces get
+/// consecutive IDs. This is deliberately far away from the core event space.
+const FIRST_EVENT_SPACE_ID: u16 = 0x4000;
+
+/// The event spaces known to the host. Plugins can look up the IDs of these spaces by name through
+/// `clap_host_event_registry::query()`. By default the host does not know about any spaces other
+/// than the core event space.
+#[derive(Debug, Default)]
+pub struct HostEventRegistry {
+    /// The registered space names and their IDs, in registration order.
+    spaces: Vec<(CString, u16)>,
+    /// The space IDs that have been handed out to the plugin through
+    /// `clap_host_event_registry::query()`.
+    queried_space_ids: BTreeSet<u16>,
+}
+
+impl HostEventRegistry {
+    /// Register an event space with the host, returning the space's ID. Registering the same name
+    /// twice returns the existing ID.
+    pub fn register(&mut self, name: &CStr) -> u16 {
+        if let Some((_, space_id)) = self.spaces.iter().find(|(space_name, _)| space_name.as_c_str() == name) {
+            return *space_id;
+        }
+
+        let space_id = FIRST_EVENT_SPACE_ID + self.spaces.len() as u16;
+        self.spaces.push((name.to_owned(), space_id));
+
+        space_id
+    }
+
+    /// Look up a space by name on behalf of the plugin. Returns `None` if the space has not been
+    /// registered. Successful lookups are remembered so [`is_known_to_plugin()`][Self::is_known_to_plugin()]
+    /// can tell which spaces the plugin may use.
+    pub fn query(&mut self, name: &CStr) -> Option<u16> {
+        let space_id = self
+            .spaces
+            .iter()
+            .find(|(space_name, _)| space_name.as_c_str() == name)
+            .map(|(_, space_id)| *space_id)?;
+        self.queried_space_ids.insert(space_id);
+
+        Some(space_id)
+    }
+
+    /// All registered space IDs, in registration order.
+    pub fn space_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        self.spaces.iter().map(|(_, space_id)| *space_id)
+    }
+
+    /// Whether the plugin knows about events in this space. This is true for the core event space,
+    /// and for any space the plugin has successfully queried.
+    pub fn is_known_to_plugin(&self, space_id: u16) -> bool {
+        space_id == CLAP_CORE_EVENT_SPACE_ID || self.queried_space_ids.contains(&space_id)
+    }
+}
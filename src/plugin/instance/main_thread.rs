@@ -1,17 +1,24 @@
+use crate::cli::fail_test;
 use crate::cli::tracing::{Span, record};
 use crate::plugin::ext::Extension;
-use crate::plugin::instance::{CallbackEvent, PluginAudioThread, PluginShared, PluginStatus};
+use crate::plugin::ext::posix_fd_support::PosixFdSupport;
+use crate::plugin::ext::timer_support::TimerSupport;
+use crate::plugin::instance::{CallbackEvent, PluginAudioThread, PluginShared, PluginStatus, PosixFdReadiness};
 use crate::plugin::library::PluginMetadata;
 use crate::plugin::util::{Proxy, clap_call};
 use anyhow::Result;
 use clap_sys::plugin::clap_plugin;
 use std::marker::PhantomData;
 use std::panic::resume_unwind;
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Instant;
 
 pub enum MainThreadTask {
     Dispatch(Box<dyn FnOnce(&Plugin) + Send>),
     CallbackRequest,
+    /// Sent by the [`PosixFdWatcher`][super::PosixFdWatcher] when one of the plugin's file
+    /// descriptors is ready.
+    PosixFdsReady,
     StopAudioThread,
 }
 
@@ -64,6 +71,24 @@ impl Drop for Plugin<'_> {
             let _span = Span::begin("clap_plugin::destroy", ());
             clap_call! { plugin=>destroy(plugin) }
         }
+
+        // The plugin is supposed to clean up its timers and file descriptors by the time it has
+        // been destroyed. There's no way to return an error from here, so this fails the test
+        // outright.
+        let leftover_timers = self.shared.timers.registered();
+        if !leftover_timers.is_empty() && !std::thread::panicking() {
+            fail_test!(
+                "The plugin did not unregister the following timers before being destroyed: {leftover_timers:?}"
+            );
+        }
+
+        let leftover_fds = self.shared.posix_fds.registered();
+        if !leftover_fds.is_empty() && !std::thread::panicking() {
+            fail_test!(
+                "The plugin did not unregister the following file descriptors before being destroyed: \
+                 {leftover_fds:?}"
+            );
+        }
     }
 }
 
@@ -85,6 +110,11 @@ impl<'lib> Plugin<'lib> {
         PluginMetadata::from_descriptor(unsafe { &*descriptor })
     }
 
+    /// Get a reference to the plugin's shared state.
+    pub fn shared(&self) -> &PluginShared {
+        &self.shared
+    }
+
     /// The plugin's current initialization status.
     pub fn status(&self) -> PluginStatus {
         self.shared.status()
@@ -132,14 +162,50 @@ impl<'lib> Plugin<'lib> {
                 .spawn(|_| f(PluginAudioThread::new(shared)))
                 .unwrap();
 
-            while let Ok(task) = self.task_receiver.recv() {
+            // The plugin's file descriptors are watched on a separate thread, which sends a task to
+            // this thread when one of them becomes ready
+            #[cfg(unix)]
+            {
+                let shared = &*self.shared;
+                let fd_watcher = shared.posix_fds.start_watching();
+                s.builder()
+                    .name("posix-fds".into())
+                    .spawn(move |_| fd_watcher.run(&shared.posix_fds, &shared.task_sender))
+                    .unwrap();
+            }
+
+            loop {
+                // While waiting for tasks from the audio thread we'll also need to wake up in time
+                // for any timers the plugin has registered
+                let timer_timeout = self
+                    .shared
+                    .timers
+                    .next_tick()
+                    .map(|next_tick| next_tick.saturating_duration_since(Instant::now()));
+
+                let task = match timer_timeout {
+                    Some(timeout) => self.task_receiver.recv_timeout(timeout),
+                    None => self.task_receiver.recv().map_err(RecvTimeoutError::from),
+                };
+
                 match task {
-                    MainThreadTask::Dispatch(callback) => callback(self),
-                    MainThreadTask::CallbackRequest => self.poll_callback_unchecked(),
-                    MainThreadTask::StopAudioThread => break,
+                    Ok(MainThreadTask::Dispatch(callback)) => callback(self),
+                    Ok(MainThreadTask::CallbackRequest) => self.poll_callback_unchecked(),
+                    Ok(MainThreadTask::PosixFdsReady) => {
+                        self.poll_posix_fds();
+                        self.shared.posix_fds.wake_watcher();
+                    }
+                    Ok(MainThreadTask::StopAudioThread) | Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => (),
                 }
+
+                // Timers that became due while handling a task would otherwise be starved when
+                // the audio thread keeps sending new tasks
+                self.poll_timers();
             }
 
+            self.shared.posix_fds.stop_watching();
+
             audio_thread.join().unwrap_or_else(|e| resume_unwind(e))
         });
 
@@ -182,10 +248,13 @@ impl<'lib> Plugin<'lib> {
         assert!(min_buffer_size >= 1);
         assert!(max_buffer_size >= min_buffer_size);
 
-        // we need to track the `Activating` state to validate that we call clap_host_latency::changed only within the activation call.
-        self.shared.set_status(PluginStatus::Activating);
-
         for _ in 0..10 {
+            // we need to track the `Activating` state to validate that we call clap_host_latency::changed only within the activation call.
+            self.shared.set_status(PluginStatus::Activating);
+
+            // Scratch memory reservations only last for a single activation
+            self.shared.scratch_memory.clear();
+
             let result = unsafe {
                 let span = Span::begin(
                     "clap_plugin::activate",
@@ -238,6 +307,9 @@ impl<'lib> Plugin<'lib> {
     }
 
     fn poll_callback_unchecked(&self) {
+        self.poll_timers();
+        self.poll_posix_fds();
+
         // 10 iterations, then bail
         for _ in 0..10 {
             if !self.shared.requested_callback.swap(false) {
@@ -252,4 +324,84 @@ impl<'lib> Plugin<'lib> {
 
         log::warn!("The plugin seems to be stuck in an 'on_main_thread' callback loop");
     }
+
+    /// Fire all timers registered through `clap_host_timer_support` that are currently due.
+    fn poll_timers(&self) {
+        // Timers registered during `init()` can only fire after the plugin has been initialized
+        if self.status() == PluginStatus::Uninitialized {
+            return;
+        }
+
+        let due = self.shared.timers.take_due(Instant::now());
+        if due.is_empty() {
+            return;
+        }
+
+        let Some(timer_support) = self.get_extension::<TimerSupport>() else {
+            let mut guard = self.shared.callback_error.lock().unwrap();
+            if guard.is_none() {
+                *guard = Some(anyhow::anyhow!(
+                    "The plugin registered timers through 'clap_host_timer_support', but it does not implement the \
+                     'timer-support' extension."
+                ));
+            }
+
+            return;
+        };
+
+        for timer_id in due {
+            // An earlier timer callback may have unregistered this timer
+            if self.shared.timers.contains(timer_id) {
+                timer_support.on_timer(timer_id);
+            }
+        }
+    }
+
+    /// Call `clap_plugin_posix_fd_support::on_fd()` for all registered file descriptors that are
+    /// currently ready.
+    fn poll_posix_fds(&self) {
+        if self.status() == PluginStatus::Uninitialized {
+            return;
+        }
+
+        let ready = self.shared.posix_fds.poll();
+        if ready.is_empty() {
+            return;
+        }
+
+        let set_error = |error: anyhow::Error| {
+            let mut guard = self.shared.callback_error.lock().unwrap();
+            if guard.is_none() {
+                *guard = Some(error);
+            }
+        };
+
+        let Some(posix_fd_support) = self.get_extension::<PosixFdSupport>() else {
+            set_error(anyhow::anyhow!(
+                "The plugin registered file descriptors through 'clap_host_posix_fd_support', but it does not \
+                 implement the 'posix-fd-support' extension."
+            ));
+
+            return;
+        };
+
+        for (fd, readiness) in ready {
+            // An earlier callback may have unregistered this file descriptor
+            if !self.shared.posix_fds.contains(fd) {
+                continue;
+            }
+
+            match readiness {
+                PosixFdReadiness::Ready(flags) => posix_fd_support.on_fd(fd, flags),
+                PosixFdReadiness::Invalid => {
+                    // Unregister it so we don't keep on reporting the same error
+                    let _ = self.shared.posix_fds.unregister(fd);
+                    set_error(anyhow::anyhow!(
+                        "File descriptor {fd} was closed while it was still registered through \
+                         'clap_host_posix_fd_support'."
+                    ));
+                }
+            }
+        }
+    }
 }
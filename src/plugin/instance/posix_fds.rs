@@ -0,0 +1,299 @@
+//! Bookkeeping for the file descriptors registered through the host's `posix-fd-support`
+//! extension, and a tiny `poll()`-based reactor for checking them for readiness.
+
+#[cfg(unix)]
+use super::MainThreadTask;
+use clap_sys::ext::posix_fd_support::*;
+use std::collections::BTreeMap;
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::Mutex;
+#[cfg(unix)]
+use std::sync::mpsc::Sender;
+
+/// All flags a plugin may register a file descriptor with.
+const ALL_FLAGS: clap_posix_fd_flags = CLAP_POSIX_FD_READ | CLAP_POSIX_FD_WRITE | CLAP_POSIX_FD_ERROR;
+
+/// The file descriptors a plugin instance has registered with the host, along with the flags they
+/// were registered with. These are polled from the main thread by [`Plugin`][super::Plugin].
+#[derive(Default)]
+pub struct PosixFds {
+    fds: Mutex<BTreeMap<i32, clap_posix_fd_flags>>,
+    /// The write end of the pipe used to wake up the [`PosixFdWatcher`], if one is running. Dropping
+    /// this stops the watcher.
+    #[cfg(unix)]
+    waker: Mutex<Option<OwnedFd>>,
+}
+
+/// Blocks on the registered file descriptors from a separate thread, and wakes up the main thread
+/// with [`MainThreadTask::PosixFdsReady`] when one of them becomes ready. This lets the main thread
+/// block until there's something to do instead of periodically checking the file descriptors.
+/// Created using [`PosixFds::start_watching()`].
+#[cfg(unix)]
+pub struct PosixFdWatcher {
+    /// The read end of the pipe used to wake up the watcher when the registered file descriptors
+    /// change, when the main thread has handled the ready file descriptors, or when the watcher
+    /// should stop.
+    wake_fd: OwnedFd,
+}
+
+/// A file descriptor that was ready according to [`PosixFds::poll()`].
+pub enum PosixFdReadiness {
+    /// The file descriptor is ready for the operations in these flags.
+    Ready(clap_posix_fd_flags),
+    /// The file descriptor is not a valid open file descriptor anymore. The plugin likely closed it
+    /// without unregistering it first.
+    Invalid,
+}
+
+impl PosixFds {
+    /// Register a file descriptor. Returns an error if the flags are invalid or if the file
+    /// descriptor has already been registered.
+    pub fn register(&self, fd: i32, flags: clap_posix_fd_flags) -> anyhow::Result<()> {
+        check_fd_and_flags(fd, flags)?;
+
+        let mut fds = self.fds.lock().unwrap();
+        anyhow::ensure!(
+            !fds.contains_key(&fd),
+            "File descriptor {fd} has already been registered."
+        );
+
+        fds.insert(fd, flags);
+        drop(fds);
+
+        self.wake_watcher();
+        Ok(())
+    }
+
+    /// Change the flags for a previously registered file descriptor. Returns an error if the flags
+    /// are invalid or if the file descriptor was never registered.
+    pub fn modify(&self, fd: i32, flags: clap_posix_fd_flags) -> anyhow::Result<()> {
+        check_fd_and_flags(fd, flags)?;
+
+        match self.fds.lock().unwrap().get_mut(&fd) {
+            Some(registered_flags) => *registered_flags = flags,
+            None => anyhow::bail!("File descriptor {fd} was never registered or has already been unregistered."),
+        }
+
+        self.wake_watcher();
+        Ok(())
+    }
+
+    /// Unregister a file descriptor. Returns an error if the file descriptor was never registered.
+    pub fn unregister(&self, fd: i32) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.fds.lock().unwrap().remove(&fd).is_some(),
+            "File descriptor {fd} was never registered or has already been unregistered."
+        );
+
+        self.wake_watcher();
+        Ok(())
+    }
+
+    /// Whether the file descriptor is currently registered.
+    pub fn contains(&self, fd: i32) -> bool {
+        self.fds.lock().unwrap().contains_key(&fd)
+    }
+
+    /// All currently registered file descriptors.
+    pub fn registered(&self) -> Vec<i32> {
+        self.fds.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Check all registered file descriptors for readiness without blocking. Only the read and write
+    /// conditions the file descriptor was registered for are reported, but errors and hang-ups are
+    /// always reported.
+    #[cfg(unix)]
+    pub fn poll(&self) -> Vec<(i32, PosixFdReadiness)> {
+        let mut poll_fds = self.poll_fds();
+        if poll_fds.is_empty() {
+            return Vec::new();
+        }
+
+        let result = unsafe { libc::poll(poll_fds.as_mut_ptr(), poll_fds.len() as libc::nfds_t, 0) };
+        if result <= 0 {
+            return Vec::new();
+        }
+
+        let fds = self.fds.lock().unwrap();
+        poll_fds
+            .into_iter()
+            .filter_map(|poll_fd| {
+                // The plugin may have changed the flags in the meantime
+                let registered_flags = *fds.get(&poll_fd.fd)?;
+                if poll_fd.revents & libc::POLLNVAL != 0 {
+                    return Some((poll_fd.fd, PosixFdReadiness::Invalid));
+                }
+
+                let mut flags = 0;
+                if poll_fd.revents & libc::POLLIN != 0 {
+                    flags |= CLAP_POSIX_FD_READ;
+                }
+                if poll_fd.revents & libc::POLLOUT != 0 {
+                    flags |= CLAP_POSIX_FD_WRITE;
+                }
+                flags &= registered_flags;
+
+                // `poll()` reports these conditions even if they were not requested, and the plugin
+                // needs to know about them to stop using the file descriptor
+                if poll_fd.revents & (libc::POLLERR | libc::POLLHUP) != 0 {
+                    flags |= CLAP_POSIX_FD_ERROR;
+                }
+
+                (flags != 0).then_some((poll_fd.fd, PosixFdReadiness::Ready(flags)))
+            })
+            .collect()
+    }
+
+    /// Start watching the registered file descriptors. The returned watcher should be run on a
+    /// separate thread, and it keeps running until [`stop_watching()`][Self::stop_watching()] is
+    /// called.
+    #[cfg(unix)]
+    pub fn start_watching(&self) -> PosixFdWatcher {
+        let mut pipe_fds = [0; 2];
+        assert_eq!(
+            unsafe { libc::pipe(pipe_fds.as_mut_ptr()) },
+            0,
+            "Could not create the file descriptor watcher's pipe: {}",
+            std::io::Error::last_os_error()
+        );
+
+        let [wake_fd, waker] = pipe_fds.map(|fd| {
+            unsafe {
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                libc::fcntl(fd, libc::F_SETFL, libc::fcntl(fd, libc::F_GETFL) | libc::O_NONBLOCK);
+            }
+
+            unsafe { OwnedFd::from_raw_fd(fd) }
+        });
+
+        *self.waker.lock().unwrap() = Some(waker);
+        PosixFdWatcher { wake_fd }
+    }
+
+    /// Stop the watcher created by [`start_watching()`][Self::start_watching()]. This closes the
+    /// write end of the watcher's pipe, which the watcher sees as a hang-up.
+    #[cfg(unix)]
+    pub fn stop_watching(&self) {
+        self.waker.lock().unwrap().take();
+    }
+
+    /// Wake up the watcher so it starts watching the currently registered file descriptors again.
+    /// This needs to be called after the main thread has handled a
+    /// [`MainThreadTask::PosixFdsReady`] task.
+    #[cfg(unix)]
+    pub fn wake_watcher(&self) {
+        if let Some(waker) = &*self.waker.lock().unwrap() {
+            // If the pipe is full then the watcher has already been woken up
+            unsafe { libc::write(waker.as_raw_fd(), [0u8].as_ptr() as *const libc::c_void, 1) };
+        }
+    }
+
+    /// The `pollfd`s for all registered file descriptors, requesting the conditions they were
+    /// registered for.
+    #[cfg(unix)]
+    fn poll_fds(&self) -> Vec<libc::pollfd> {
+        self.fds
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&fd, &flags)| {
+                let mut events = 0;
+                if flags & CLAP_POSIX_FD_READ != 0 {
+                    events |= libc::POLLIN;
+                }
+                if flags & CLAP_POSIX_FD_WRITE != 0 {
+                    events |= libc::POLLOUT;
+                }
+
+                libc::pollfd { fd, events, revents: 0 }
+            })
+            .collect()
+    }
+
+    /// File descriptors are not a thing on non-Unix-like platforms, and the extension is not
+    /// exposed there.
+    #[cfg(not(unix))]
+    pub fn poll(&self) -> Vec<(i32, PosixFdReadiness)> {
+        Vec::new()
+    }
+
+    #[cfg(not(unix))]
+    pub fn stop_watching(&self) {}
+
+    #[cfg(not(unix))]
+    pub fn wake_watcher(&self) {}
+}
+
+#[cfg(unix)]
+impl PosixFdWatcher {
+    /// Block until one of the plugin's registered file descriptors becomes ready, and then send a
+    /// [`MainThreadTask::PosixFdsReady`] task to the main thread. The file descriptors are not
+    /// watched again until the main thread calls [`PosixFds::wake_watcher()`], since they would
+    /// otherwise stay ready until the plugin has handled them. Returns when
+    /// [`PosixFds::stop_watching()`] is called.
+    pub fn run(self, fds: &PosixFds, task_sender: &Sender<MainThreadTask>) {
+        let wake_poll_fd = libc::pollfd {
+            fd: self.wake_fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let mut waiting_for_main_thread = false;
+        loop {
+            let mut poll_fds = vec![wake_poll_fd];
+            if !waiting_for_main_thread {
+                poll_fds.extend(fds.poll_fds());
+            }
+
+            let result = unsafe { libc::poll(poll_fds.as_mut_ptr(), poll_fds.len() as libc::nfds_t, -1) };
+            if result < 0 {
+                if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+
+                log::error!(
+                    "Could not watch the plugin's file descriptors: {}",
+                    std::io::Error::last_os_error()
+                );
+                return;
+            }
+
+            let wake_events = poll_fds[0].revents;
+            if wake_events & libc::POLLHUP != 0 {
+                return;
+            }
+
+            if wake_events & libc::POLLIN != 0 {
+                let mut buffer = [0u8; 64];
+                while unsafe { libc::read(self.wake_fd.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, 64) } > 0
+                {
+                }
+
+                waiting_for_main_thread = false;
+                continue;
+            }
+
+            if poll_fds[1..].iter().any(|poll_fd| poll_fd.revents != 0) {
+                waiting_for_main_thread = true;
+                if task_sender.send(MainThreadTask::PosixFdsReady).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn check_fd_and_flags(fd: i32, flags: clap_posix_fd_flags) -> anyhow::Result<()> {
+    anyhow::ensure!(fd >= 0, "{fd} is not a valid file descriptor.");
+    anyhow::ensure!(
+        flags != 0,
+        "The flags for file descriptor {fd} are empty, this would never cause 'on_fd()' to be called."
+    );
+    anyhow::ensure!(
+        flags & !ALL_FLAGS == 0,
+        "The flags for file descriptor {fd} contain unknown bits (0b{flags:b})."
+    );
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+//! Bookkeeping for the scratch memory handed out through the host's `scratch-memory` extension.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread::ThreadId;
+
+/// The largest scratch memory reservation the host will accept.
+pub const MAX_SCRATCH_MEMORY_SIZE: u32 = 64 * 1024 * 1024;
+/// The size of the guard regions placed before and after every scratch memory region.
+const GUARD_SIZE: usize = 256;
+/// The byte the guard regions are filled with. Any other value in a guard region means the plugin
+/// wrote outside of its scratch memory.
+const GUARD_BYTE: u8 = 0xa5;
+/// The byte the scratch memory itself is filled with after every process call, so the plugin
+/// cannot rely on its contents persisting between process calls.
+const SCRATCH_BYTE: u8 = 0xcd;
+
+/// The scratch memory a plugin instance reserved through `clap_host_scratch_memory::reserve()`.
+/// Every thread that accesses the scratch memory gets its own region, surrounded by guard regions
+/// that are checked after every process call.
+#[derive(Default)]
+pub struct ScratchMemory {
+    inner: Mutex<ScratchMemoryInner>,
+}
+
+#[derive(Default)]
+struct ScratchMemoryInner {
+    /// The reserved size in bytes, if the plugin reserved any scratch memory during the current
+    /// activation.
+    reserved_size: Option<u32>,
+    /// The allocated regions per thread, including guard regions. These are boxed so the pointers
+    /// handed to the plugin stay valid when the map is resized.
+    regions: HashMap<ThreadId, Box<[u8]>>,
+}
+
+impl ScratchMemory {
+    /// Forget about the current reservation. Called before every activation, as reservations only
+    /// last for a single activation.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.reserved_size = None;
+        inner.regions.clear();
+    }
+
+    /// Reserve `size` bytes of scratch memory. Returns `false` if the size exceeds
+    /// [`MAX_SCRATCH_MEMORY_SIZE`]. Reserving again replaces the previous reservation.
+    pub fn reserve(&self, size: u32) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        inner.regions.clear();
+        if size > MAX_SCRATCH_MEMORY_SIZE {
+            inner.reserved_size = None;
+            return false;
+        }
+
+        inner.reserved_size = Some(size);
+        true
+    }
+
+    /// Get the current thread's scratch memory region, allocating it if needed. Returns a null
+    /// pointer if no scratch memory has been reserved.
+    pub fn access(&self) -> *mut u8 {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(size) = inner.reserved_size else {
+            return std::ptr::null_mut();
+        };
+
+        let region = inner.regions.entry(std::thread::current().id()).or_insert_with(|| {
+            let mut region = vec![GUARD_BYTE; GUARD_SIZE + size as usize + GUARD_SIZE].into_boxed_slice();
+            region[GUARD_SIZE..GUARD_SIZE + size as usize].fill(SCRATCH_BYTE);
+            region
+        });
+
+        unsafe { region.as_mut_ptr().add(GUARD_SIZE) }
+    }
+
+    /// Check that the plugin did not write outside of any of the scratch memory regions, and then
+    /// scramble the regions' contents again.
+    pub fn check_guards(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(size) = inner.reserved_size else {
+            return Ok(());
+        };
+        let size = size as usize;
+
+        for region in inner.regions.values_mut() {
+            let (before, rest) = region.split_at_mut(GUARD_SIZE);
+            let (scratch, after) = rest.split_at_mut(size);
+
+            if let Some(offset) = before.iter().position(|&byte| byte != GUARD_BYTE) {
+                anyhow::bail!(
+                    "The plugin wrote {} byte(s) before the start of its {size} byte scratch memory region.",
+                    GUARD_SIZE - offset
+                );
+            }
+            if let Some(offset) = after.iter().rposition(|&byte| byte != GUARD_BYTE) {
+                anyhow::bail!(
+                    "The plugin wrote up to {} byte(s) past the end of its {size} byte scratch memory region.",
+                    offset + 1
+                );
+            }
+
+            scratch.fill(SCRATCH_BYTE);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_without_reservation() {
+        let scratch_memory = ScratchMemory::default();
+        assert!(scratch_memory.access().is_null());
+        assert!(scratch_memory.check_guards().is_ok());
+    }
+
+    #[test]
+    fn reserve_too_large() {
+        let scratch_memory = ScratchMemory::default();
+        assert!(!scratch_memory.reserve(MAX_SCRATCH_MEMORY_SIZE + 1));
+        assert!(scratch_memory.access().is_null());
+    }
+
+    #[test]
+    fn write_within_region() {
+        let scratch_memory = ScratchMemory::default();
+        assert!(scratch_memory.reserve(64));
+
+        let ptr = scratch_memory.access();
+        assert!(!ptr.is_null());
+        assert_eq!(scratch_memory.access(), ptr);
+        unsafe { ptr.write_bytes(0, 64) };
+        scratch_memory.check_guards().unwrap();
+
+        // The contents are scrambled again after every check
+        let scratch = unsafe { std::slice::from_raw_parts(ptr, 64) };
+        assert!(scratch.iter().all(|&byte| byte == SCRATCH_BYTE));
+    }
+
+    #[test]
+    fn write_past_end() {
+        let scratch_memory = ScratchMemory::default();
+        assert!(scratch_memory.reserve(64));
+
+        let ptr = scratch_memory.access();
+        unsafe { ptr.add(64).write(0) };
+        assert_eq!(
+            scratch_memory.check_guards().unwrap_err().to_string(),
+            "The plugin wrote up to 1 byte(s) past the end of its 64 byte scratch memory region."
+        );
+    }
+
+    #[test]
+    fn write_before_start() {
+        let scratch_memory = ScratchMemory::default();
+        assert!(scratch_memory.reserve(64));
+
+        let ptr = scratch_memory.access();
+        unsafe { ptr.sub(2).write(0) };
+        assert_eq!(
+            scratch_memory.check_guards().unwrap_err().to_string(),
+            "The plugin wrote 2 byte(s) before the start of its 64 byte scratch memory region."
+        );
+    }
+
+    #[test]
+    fn clear_forgets_reservation() {
+        let scratch_memory = ScratchMemory::default();
+        assert!(scratch_memory.reserve(64));
+        scratch_memory.clear();
+        assert!(scratch_memory.access().is_null());
+    }
+}
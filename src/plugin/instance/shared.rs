@@ -3,43 +3,79 @@ use crate::cli::tracing::{Span, record};
 use crate::plugin::ext::Extension;
 use crate::plugin::ext::audio_ports::AudioPorts;
 use crate::plugin::ext::audio_ports_config::AudioPortsConfig;
+use crate::plugin::ext::context_menu;
 use crate::plugin::ext::latency::Latency;
+use crate::plugin::ext::mini_curve_display::{MiniCurveDisplay, curve_kind_hints};
+use crate::plugin::ext::note_name::NoteName;
 use crate::plugin::ext::note_ports::NotePorts;
 use crate::plugin::ext::params::Params;
+use crate::plugin::ext::posix_fd_support::PosixFdSupport;
 use crate::plugin::ext::preset_load::PresetLoad;
+use crate::plugin::ext::remote_controls::RemoteControls;
+use crate::plugin::ext::resource_directory::ResourceDirectory;
 use crate::plugin::ext::state::State;
 use crate::plugin::ext::tail::Tail;
 use crate::plugin::ext::thread_pool::ThreadPool;
+use crate::plugin::ext::timer_support::TimerSupport;
+use crate::plugin::ext::track_info::TrackInfo;
+use crate::plugin::ext::triggers::Triggers;
 use crate::plugin::ext::voice_info::VoiceInfo;
-use crate::plugin::instance::{CallbackEvent, MainThreadTask, Plugin, PluginStatus};
+use crate::plugin::ffi::mini_curve_display::*;
+use crate::plugin::ffi::scratch_memory::{CLAP_EXT_SCRATCH_MEMORY, clap_host_scratch_memory};
+use crate::plugin::instance::{
+    CallbackEvent, HostEventRegistry, HostTrackInfo, HostTunings, MainThreadTask, Plugin, PluginStatus, PosixFds,
+    ScratchMemory, Timers, TransportRequest, TransportRequests, UndoChange, UndoHistory,
+};
 use crate::plugin::preset_discovery::LocationValue;
 use crate::plugin::util::{self, CHECK_POINTER, Proxy, Proxyable, clap_call, cstr_ptr_to_string, validator_version};
 use anyhow::{Context, Result};
 use clap_sys::ext::audio_ports::*;
 use clap_sys::ext::audio_ports_config::{CLAP_EXT_AUDIO_PORTS_CONFIG, clap_host_audio_ports_config};
+use clap_sys::ext::context_menu::*;
+use clap_sys::ext::draft::resource_directory::{CLAP_EXT_RESOURCE_DIRECTORY, clap_host_resource_directory};
+use clap_sys::ext::draft::transport_control::{CLAP_EXT_TRANSPORT_CONTROL, clap_host_transport_control};
+use clap_sys::ext::draft::triggers::*;
+use clap_sys::ext::draft::tuning::{CLAP_EXT_TUNING, clap_host_tuning, clap_tuning_info};
+use clap_sys::ext::draft::undo::{CLAP_EXT_UNDO, clap_host_undo};
+use clap_sys::ext::event_registry::{CLAP_EXT_EVENT_REGISTRY, clap_host_event_registry};
+use clap_sys::ext::gui::{CLAP_EXT_GUI, clap_host_gui};
 use clap_sys::ext::latency::*;
 use clap_sys::ext::log::*;
+use clap_sys::ext::note_name::{CLAP_EXT_NOTE_NAME, clap_host_note_name};
 use clap_sys::ext::note_ports::*;
 use clap_sys::ext::params::*;
+use clap_sys::ext::posix_fd_support::*;
 use clap_sys::ext::preset_load::{CLAP_EXT_PRESET_LOAD, clap_host_preset_load};
+use clap_sys::ext::remote_controls::{
+    CLAP_EXT_REMOTE_CONTROLS, CLAP_EXT_REMOTE_CONTROLS_COMPAT, clap_host_remote_controls,
+};
 use clap_sys::ext::state::{CLAP_EXT_STATE, clap_host_state};
 use clap_sys::ext::tail::{CLAP_EXT_TAIL, clap_host_tail};
 use clap_sys::ext::thread_check::{CLAP_EXT_THREAD_CHECK, clap_host_thread_check};
 use clap_sys::ext::thread_pool::{CLAP_EXT_THREAD_POOL, clap_host_thread_pool};
+use clap_sys::ext::timer_support::{CLAP_EXT_TIMER_SUPPORT, clap_host_timer_support};
+use clap_sys::ext::track_info::*;
 use clap_sys::ext::voice_info::{CLAP_EXT_VOICE_INFO, clap_host_voice_info};
 use clap_sys::factory::plugin_factory::clap_plugin_factory;
 use clap_sys::factory::preset_discovery::clap_preset_discovery_location_kind;
+use clap_sys::fixedpoint::clap_beattime;
 use clap_sys::host::clap_host;
-use clap_sys::id::clap_id;
+use clap_sys::id::{CLAP_INVALID_ID, clap_id};
 use clap_sys::plugin::clap_plugin;
 use clap_sys::version::CLAP_VERSION;
 use crossbeam::atomic::AtomicCell;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::HashSet;
 use std::ffi::{CStr, c_char, c_void};
 use std::ptr::NonNull;
 use std::sync::Mutex;
 use std::sync::mpsc::{Sender, channel};
 use std::thread::ThreadId;
+use std::time::Duration;
+
+/// The action ID for the single entry the host adds to context menus populated through
+/// `clap_host_context_menu::populate()`.
+const HOST_CONTEXT_MENU_ACTION_ID: clap_id = 0;
 
 /// Plugin instance state that is shared between the main thread, audio thread and any external unmanaged threads.
 /// This struct also acts as the `clap_host` implementation for the plugin instance.
@@ -68,6 +104,49 @@ pub struct PluginShared {
     /// Whether the plugin is currently being called from within a process call. This is used to
     /// check that certain functions (like thread_pool::request_exec()) are called from the process function.
     pub is_currently_in_process_call: AtomicCell<bool>,
+    /// The number of allocations the plugin made on the audio thread from within
+    /// `clap_plugin::process()`. This is always zero on platforms where allocations cannot be tracked.
+    pub process_allocations: AtomicCell<usize>,
+
+    /// The timers registered by the plugin through `clap_host_timer_support`.
+    pub timers: Timers,
+
+    /// The file descriptors registered by the plugin through `clap_host_posix_fd_support`.
+    pub posix_fds: PosixFds,
+
+    /// The track information returned from `clap_host_track_info::get()`. If this is `None`, then
+    /// the host reports that there is no track information available.
+    pub track_info: Mutex<Option<HostTrackInfo>>,
+
+    /// The event spaces the plugin can look up through `clap_host_event_registry::query()`.
+    pub event_registry: Mutex<HostEventRegistry>,
+
+    /// The undoable changes reported by the plugin through `clap_host_undo`.
+    pub undo_history: UndoHistory,
+
+    /// The tuning pool the plugin can query through `clap_host_tuning`.
+    pub tunings: HostTunings,
+
+    /// The scratch memory reserved by the plugin through `clap_host_scratch_memory`.
+    pub scratch_memory: ScratchMemory,
+
+    /// Transport requests made by the plugin through `clap_host_transport_control`. These are
+    /// applied to the transport right before the next process call.
+    pub transport_requests: TransportRequests,
+
+    /// Whether the plugin's curves are currently observed through
+    /// `clap_plugin_mini_curve_display::set_observed()`.
+    pub mini_curve_display_observed: AtomicCell<bool>,
+    /// Whether the plugin marked its curves as dynamic through
+    /// `clap_host_mini_curve_display::set_dynamic()`.
+    pub mini_curve_display_dynamic: AtomicCell<bool>,
+    /// The curve kinds the plugin requested hints for through
+    /// `clap_host_mini_curve_display::get_hints()`.
+    pub mini_curve_display_hint_kinds: Mutex<HashSet<u32>>,
+
+    /// Whether the plugin's GUI currently exists, and if it does, whether it's floating. Set by
+    /// `clap_plugin_gui::create()` and cleared by `clap_plugin_gui::destroy()`.
+    pub gui: AtomicCell<Option<bool>>,
 
     pub clap_plugin: *const clap_plugin,
 }
@@ -117,6 +196,19 @@ impl PluginShared {
             requested_callback: AtomicCell::new(false),
             requested_restart: AtomicCell::new(false),
             is_currently_in_process_call: AtomicCell::new(false),
+            process_allocations: AtomicCell::new(0),
+            timers: Timers::default(),
+            posix_fds: PosixFds::default(),
+            track_info: Mutex::new(Some(HostTrackInfo::default())),
+            event_registry: Mutex::new(HostEventRegistry::default()),
+            undo_history: UndoHistory::default(),
+            tunings: HostTunings::default(),
+            scratch_memory: ScratchMemory::default(),
+            transport_requests: TransportRequests::default(),
+            mini_curve_display_observed: AtomicCell::new(false),
+            mini_curve_display_dynamic: AtomicCell::new(false),
+            mini_curve_display_hint_kinds: Mutex::new(HashSet::new()),
+            gui: AtomicCell::new(None),
 
             clap_plugin: std::ptr::null(),
         });
@@ -286,6 +378,16 @@ impl PluginShared {
 
         Ok(())
     }
+
+    /// Checks whether the plugin's GUI currently exists, and whether it's embedded if
+    /// `embedded_only` is set.
+    fn assert_has_gui(&self, embedded_only: bool) -> Result<()> {
+        match self.gui.load() {
+            Some(true) if embedded_only => anyhow::bail!("Called while the plugin's GUI is floating"),
+            Some(_) => Ok(()),
+            None => anyhow::bail!("Called while the plugin's GUI does not exist"),
+        }
+    }
 }
 
 // Extensions
@@ -344,6 +446,100 @@ impl PluginShared {
         rescan: Some(Self::ext_audio_ports_config_rescan),
     };
 
+    const EXT_NOTE_NAME: clap_host_note_name = clap_host_note_name {
+        changed: Some(Self::ext_note_name_changed),
+    };
+
+    const EXT_REMOTE_CONTROLS: clap_host_remote_controls = clap_host_remote_controls {
+        changed: Some(Self::ext_remote_controls_changed),
+        suggest_page: Some(Self::ext_remote_controls_suggest_page),
+    };
+
+    const EXT_TIMER_SUPPORT: clap_host_timer_support = clap_host_timer_support {
+        register_timer: Some(Self::ext_timer_support_register_timer),
+        unregister_timer: Some(Self::ext_timer_support_unregister_timer),
+    };
+
+    const EXT_TRACK_INFO: clap_host_track_info = clap_host_track_info {
+        get: Some(Self::ext_track_info_get),
+    };
+
+    const EXT_CONTEXT_MENU: clap_host_context_menu = clap_host_context_menu {
+        populate: Some(Self::ext_context_menu_populate),
+        perform: Some(Self::ext_context_menu_perform),
+        can_popup: Some(Self::ext_context_menu_can_popup),
+        popup: Some(Self::ext_context_menu_popup),
+    };
+
+    const EXT_EVENT_REGISTRY: clap_host_event_registry = clap_host_event_registry {
+        query: Some(Self::ext_event_registry_query),
+    };
+
+    const EXT_UNDO: clap_host_undo = clap_host_undo {
+        begin_change: Some(Self::ext_undo_begin_change),
+        cancel_change: Some(Self::ext_undo_cancel_change),
+        change_made: Some(Self::ext_undo_change_made),
+        request_undo: Some(Self::ext_undo_request_undo),
+        request_redo: Some(Self::ext_undo_request_redo),
+        set_wants_context_updates: Some(Self::ext_undo_set_wants_context_updates),
+    };
+
+    const EXT_TUNING: clap_host_tuning = clap_host_tuning {
+        get_relative: Some(Self::ext_tuning_get_relative),
+        should_play: Some(Self::ext_tuning_should_play),
+        get_tuning_count: Some(Self::ext_tuning_get_tuning_count),
+        get_info: Some(Self::ext_tuning_get_info),
+    };
+
+    const EXT_RESOURCE_DIRECTORY: clap_host_resource_directory = clap_host_resource_directory {
+        request_directory: Some(Self::ext_resource_directory_request_directory),
+        release_directory: Some(Self::ext_resource_directory_release_directory),
+    };
+
+    const EXT_SCRATCH_MEMORY: clap_host_scratch_memory = clap_host_scratch_memory {
+        reserve: Some(Self::ext_scratch_memory_reserve),
+        access: Some(Self::ext_scratch_memory_access),
+    };
+
+    const EXT_TRIGGERS: clap_host_triggers = clap_host_triggers {
+        rescan: Some(Self::ext_triggers_rescan),
+        clear: Some(Self::ext_triggers_clear),
+    };
+
+    const EXT_TRANSPORT_CONTROL: clap_host_transport_control = clap_host_transport_control {
+        request_start: Some(Self::ext_transport_control_request_start),
+        request_stop: Some(Self::ext_transport_control_request_stop),
+        request_continue: Some(Self::ext_transport_control_request_continue),
+        request_pause: Some(Self::ext_transport_control_request_pause),
+        request_toggle_play: Some(Self::ext_transport_control_request_toggle_play),
+        request_jump: Some(Self::ext_transport_control_request_jump),
+        request_loop_region: Some(Self::ext_transport_control_request_loop_region),
+        request_toggle_loop: Some(Self::ext_transport_control_request_toggle_loop),
+        request_enable_loop: Some(Self::ext_transport_control_request_enable_loop),
+        request_record: Some(Self::ext_transport_control_request_record),
+        request_toggle_record: Some(Self::ext_transport_control_request_toggle_record),
+    };
+
+    const EXT_MINI_CURVE_DISPLAY: clap_host_mini_curve_display = clap_host_mini_curve_display {
+        get_hints: Some(Self::ext_mini_curve_display_get_hints),
+        set_dynamic: Some(Self::ext_mini_curve_display_set_dynamic),
+        changed: Some(Self::ext_mini_curve_display_changed),
+    };
+
+    const EXT_GUI: clap_host_gui = clap_host_gui {
+        resize_hints_changed: Some(Self::ext_gui_resize_hints_changed),
+        request_resize: Some(Self::ext_gui_request_resize),
+        request_show: Some(Self::ext_gui_request_show),
+        request_hide: Some(Self::ext_gui_request_hide),
+        closed: Some(Self::ext_gui_closed),
+    };
+
+    const EXT_POSIX_FD_SUPPORT: clap_host_posix_fd_support = clap_host_posix_fd_support {
+        register_fd: Some(Self::ext_posix_fd_support_register_fd),
+        modify_fd: Some(Self::ext_posix_fd_support_modify_fd),
+        unregister_fd: Some(Self::ext_posix_fd_support_unregister_fd),
+    };
+
     unsafe extern "C" fn clap_get_extension(host: *const clap_host, extension_id: *const c_char) -> *const c_void {
         let extension_id_cstr = if extension_id.is_null() {
             None
@@ -392,6 +588,38 @@ impl PluginShared {
                 &Self::EXT_VOICE_INFO as *const _ as *const c_void
             } else if extension_id_cstr == CLAP_EXT_AUDIO_PORTS_CONFIG {
                 &Self::EXT_AUDIO_PORTS_CONFIG as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_NOTE_NAME {
+                &Self::EXT_NOTE_NAME as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_REMOTE_CONTROLS
+                || extension_id_cstr == CLAP_EXT_REMOTE_CONTROLS_COMPAT
+            {
+                &Self::EXT_REMOTE_CONTROLS as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_TIMER_SUPPORT {
+                &Self::EXT_TIMER_SUPPORT as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_TRACK_INFO || extension_id_cstr == CLAP_EXT_TRACK_INFO_COMPAT {
+                &Self::EXT_TRACK_INFO as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_CONTEXT_MENU || extension_id_cstr == CLAP_EXT_CONTEXT_MENU_COMPAT {
+                &Self::EXT_CONTEXT_MENU as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_EVENT_REGISTRY {
+                &Self::EXT_EVENT_REGISTRY as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_UNDO {
+                &Self::EXT_UNDO as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_TUNING {
+                &Self::EXT_TUNING as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_RESOURCE_DIRECTORY {
+                &Self::EXT_RESOURCE_DIRECTORY as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_SCRATCH_MEMORY {
+                &Self::EXT_SCRATCH_MEMORY as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_TRIGGERS {
+                &Self::EXT_TRIGGERS as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_TRANSPORT_CONTROL {
+                &Self::EXT_TRANSPORT_CONTROL as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_MINI_CURVE_DISPLAY {
+                &Self::EXT_MINI_CURVE_DISPLAY as *const _ as *const c_void
+            } else if extension_id_cstr == CLAP_EXT_GUI {
+                &Self::EXT_GUI as *const _ as *const c_void
+            } else if cfg!(unix) && extension_id_cstr == CLAP_EXT_POSIX_FD_SUPPORT {
+                &Self::EXT_POSIX_FD_SUPPORT as *const _ as *const c_void
             } else {
                 std::ptr::null()
             };
@@ -738,6 +966,41 @@ impl PluginShared {
         });
     }
 
+    unsafe extern "C" fn ext_note_name_changed(host: *const clap_host) {
+        let span = Span::begin("clap_host_note_name::changed", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<NoteName>()?;
+            this.callback_sender.send(CallbackEvent::NoteNameChanged).unwrap();
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_remote_controls_changed(host: *const clap_host) {
+        let span = Span::begin("clap_host_remote_controls::changed", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<RemoteControls>()?;
+            this.callback_sender.send(CallbackEvent::RemoteControlsChanged).unwrap();
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_remote_controls_suggest_page(host: *const clap_host, page_id: clap_id) {
+        let span = Span::begin("clap_host_remote_controls::suggest_page", record! { page_id: page_id });
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<RemoteControls>()?;
+            this.callback_sender
+                .send(CallbackEvent::RemoteControlsSuggestPage(page_id))
+                .unwrap();
+            Ok(())
+        });
+    }
+
     unsafe extern "C" fn ext_audio_ports_config_rescan(host: *const clap_host) {
         let span = Span::begin("clap_host_audio_ports_config::rescan", ());
 
@@ -776,4 +1039,978 @@ impl PluginShared {
         })
         .unwrap_or(false)
     }
+
+    unsafe extern "C" fn ext_timer_support_register_timer(
+        host: *const clap_host,
+        period_ms: u32,
+        timer_id: *mut clap_id,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_timer_support::register_timer",
+            record! {
+                period_ms: period_ms
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+
+            // Plugins commonly register their timers from within `clap_plugin::init()`, at which
+            // point we cannot yet query the plugin's extensions
+            if this.status() != PluginStatus::Uninitialized {
+                this.assert_has_extension::<TimerSupport>()?;
+            }
+
+            anyhow::ensure!(!timer_id.is_null(), "'timer_id' is a null pointer");
+
+            // A zero period would have us spinning on the main thread, so that gets rounded up
+            let id = this.timers.register(Duration::from_millis(period_ms.max(1) as u64));
+            unsafe { timer_id.write(id) };
+
+            span.finish(record!(result: true, timer_id: id));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_timer_support_unregister_timer(host: *const clap_host, timer_id: clap_id) -> bool {
+        let span = Span::begin(
+            "clap_host_timer_support::unregister_timer",
+            record! {
+                timer_id: timer_id
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+
+            anyhow::ensure!(
+                this.timers.unregister(timer_id),
+                "Called with timer ID {timer_id}, which was never registered or has already been unregistered."
+            );
+
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_posix_fd_support_register_fd(
+        host: *const clap_host,
+        fd: i32,
+        flags: clap_posix_fd_flags,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_posix_fd_support::register_fd",
+            record! {
+                fd: fd,
+                read: flags & CLAP_POSIX_FD_READ != 0,
+                write: flags & CLAP_POSIX_FD_WRITE != 0,
+                error: flags & CLAP_POSIX_FD_ERROR != 0
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+
+            // Like with timers, file descriptors may already be registered during `init()`
+            if this.status() != PluginStatus::Uninitialized {
+                this.assert_has_extension::<PosixFdSupport>()?;
+            }
+
+            this.posix_fds.register(fd, flags)?;
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_posix_fd_support_modify_fd(
+        host: *const clap_host,
+        fd: i32,
+        flags: clap_posix_fd_flags,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_posix_fd_support::modify_fd",
+            record! {
+                fd: fd,
+                read: flags & CLAP_POSIX_FD_READ != 0,
+                write: flags & CLAP_POSIX_FD_WRITE != 0,
+                error: flags & CLAP_POSIX_FD_ERROR != 0
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.posix_fds.modify(fd, flags)?;
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_posix_fd_support_unregister_fd(host: *const clap_host, fd: i32) -> bool {
+        let span = Span::begin("clap_host_posix_fd_support::unregister_fd", record! { fd: fd });
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.posix_fds.unregister(fd)?;
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_track_info_get(host: *const clap_host, info: *mut clap_track_info) -> bool {
+        let span = Span::begin("clap_host_track_info::get", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<TrackInfo>()?;
+            anyhow::ensure!(!info.is_null(), "'info' is a null pointer");
+
+            match &*this.track_info.lock().unwrap() {
+                Some(track_info) => {
+                    unsafe { info.write(track_info.to_clap()) };
+
+                    span.finish(record!(
+                        result: true,
+                        flags: format_args!("{:#b}", track_info.flags),
+                        name: track_info.name.as_str(),
+                        audio_channel_count: track_info.audio_channel_count
+                    ));
+                    Ok(true)
+                }
+                None => {
+                    span.finish(record!(result: false));
+                    Ok(false)
+                }
+            }
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_context_menu_populate(
+        host: *const clap_host,
+        target: *const clap_context_menu_target,
+        builder: *const clap_context_menu_builder,
+    ) -> bool {
+        let span = Span::begin("clap_host_context_menu::populate", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            unsafe { context_menu::read_target(target) }?;
+            anyhow::ensure!(!builder.is_null(), "'builder' is a null pointer");
+
+            // The host adds a single entry to the plugin's menu. The plugin's builder may not
+            // support entries, in which case we'll leave the menu alone.
+            let entry = clap_context_menu_entry {
+                label: c"Validator Action".as_ptr(),
+                is_enabled: true,
+                action_id: HOST_CONTEXT_MENU_ACTION_ID,
+            };
+            unsafe {
+                if clap_call! { builder=>supports(builder, CLAP_CONTEXT_MENU_ITEM_ENTRY) } {
+                    clap_call! { builder=>add_item(builder, CLAP_CONTEXT_MENU_ITEM_ENTRY, &entry as *const _ as *const c_void) };
+                }
+            }
+
+            span.finish(record!(result: true));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_context_menu_perform(
+        host: *const clap_host,
+        target: *const clap_context_menu_target,
+        action_id: clap_id,
+    ) -> bool {
+        let span = Span::begin("clap_host_context_menu::perform", record! { action_id: action_id });
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            unsafe { context_menu::read_target(target) }?;
+            anyhow::ensure!(
+                action_id == HOST_CONTEXT_MENU_ACTION_ID,
+                "Called with action ID {action_id}, which the host never added to a context menu."
+            );
+
+            span.finish(record!(result: true));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_context_menu_can_popup(host: *const clap_host) -> bool {
+        let span = Span::begin("clap_host_context_menu::can_popup", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+
+            // The validator does not have a GUI to show menus in
+            span.finish(record!(result: false));
+            Ok(false)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_context_menu_popup(
+        host: *const clap_host,
+        target: *const clap_context_menu_target,
+        screen_index: i32,
+        x: i32,
+        y: i32,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_context_menu::popup",
+            record! { screen_index: screen_index, x: x, y: y },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            unsafe { context_menu::read_target(target) }?;
+
+            anyhow::bail!("Called even though 'clap_host_context_menu::can_popup()' returns false.");
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_event_registry_query(
+        host: *const clap_host,
+        space_name: *const c_char,
+        space_id: *mut u16,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_event_registry::query",
+            record! {
+                space_name: unsafe { cstr_ptr_to_string(space_name) }.ok().flatten().unwrap_or_default()
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            anyhow::ensure!(!space_name.is_null(), "'space_name' is a null pointer");
+            anyhow::ensure!(!space_id.is_null(), "'space_id' is a null pointer");
+
+            let name = unsafe { CStr::from_ptr(space_name) };
+            match this.event_registry.lock().unwrap().query(name) {
+                Some(id) => {
+                    unsafe { space_id.write(id) };
+
+                    span.finish(record!(result: true, space_id: id));
+                    Ok(true)
+                }
+                None => {
+                    // The spec requires the host to set the ID to `UINT16_MAX` for unknown spaces
+                    unsafe { space_id.write(u16::MAX) };
+
+                    span.finish(record!(result: false));
+                    Ok(false)
+                }
+            }
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_undo_begin_change(host: *const clap_host) {
+        let span = Span::begin("clap_host_undo::begin_change", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.undo_history.begin_change()
+        });
+    }
+
+    unsafe extern "C" fn ext_undo_cancel_change(host: *const clap_host) {
+        let span = Span::begin("clap_host_undo::cancel_change", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.undo_history.cancel_change()
+        });
+    }
+
+    unsafe extern "C" fn ext_undo_change_made(
+        host: *const clap_host,
+        name: *const c_char,
+        delta: *const c_void,
+        delta_size: usize,
+        delta_can_undo: bool,
+    ) {
+        let span = Span::begin(
+            "clap_host_undo::change_made",
+            record! {
+                delta_size: delta_size,
+                delta_can_undo: delta_can_undo
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+
+            let name = unsafe { cstr_ptr_to_string(name) }?.context("'name' is a null pointer")?;
+            anyhow::ensure!(
+                delta.is_null() == (delta_size == 0),
+                "The delta pointer is {} but the delta size is {delta_size}",
+                if delta.is_null() { "null" } else { "not null" }
+            );
+
+            let delta = if delta.is_null() {
+                None
+            } else {
+                Some(unsafe { std::slice::from_raw_parts(delta as *const u8, delta_size) }.to_vec())
+            };
+
+            this.undo_history.change_made(UndoChange {
+                name,
+                delta,
+                delta_can_undo,
+            });
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_undo_request_undo(host: *const clap_host) {
+        let span = Span::begin("clap_host_undo::request_undo", ());
+
+        // The host is free to ignore these requests
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_undo_request_redo(host: *const clap_host) {
+        let span = Span::begin("clap_host_undo::request_redo", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_undo_set_wants_context_updates(host: *const clap_host, is_subscribed: bool) {
+        let span = Span::begin(
+            "clap_host_undo::set_wants_context_updates",
+            record! { is_subscribed: is_subscribed },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.undo_history.set_wants_context_updates(is_subscribed);
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_tuning_get_relative(
+        host: *const clap_host,
+        tuning_id: clap_id,
+        channel: i32,
+        key: i32,
+        sample_offset: u32,
+    ) -> f64 {
+        let span = Span::begin(
+            "clap_host_tuning::get_relative",
+            record! {
+                tuning_id: tuning_id,
+                channel: channel,
+                key: key,
+                sample_offset: sample_offset
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_audio_thread()?;
+
+            let result = this.tunings.get_relative(tuning_id, key);
+            span.finish(record!(result: result));
+            Ok(result)
+        })
+        .unwrap_or(0.0)
+    }
+
+    unsafe extern "C" fn ext_tuning_should_play(
+        host: *const clap_host,
+        tuning_id: clap_id,
+        channel: i32,
+        key: i32,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_tuning::should_play",
+            record! {
+                tuning_id: tuning_id,
+                channel: channel,
+                key: key
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_audio_thread()?;
+
+            let result = this.tunings.should_play(tuning_id, key);
+            span.finish(record!(result: result));
+            Ok(result)
+        })
+        .unwrap_or(true)
+    }
+
+    unsafe extern "C" fn ext_tuning_get_tuning_count(host: *const clap_host) -> u32 {
+        let span = Span::begin("clap_host_tuning::get_tuning_count", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+
+            let result = this.tunings.count() as u32;
+            span.finish(record!(result: result));
+            Ok(result)
+        })
+        .unwrap_or(0)
+    }
+
+    unsafe extern "C" fn ext_tuning_get_info(
+        host: *const clap_host,
+        tuning_index: u32,
+        info: *mut clap_tuning_info,
+    ) -> bool {
+        let span = Span::begin("clap_host_tuning::get_info", record! { tuning_index: tuning_index });
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            anyhow::ensure!(!info.is_null(), "'info' is a null pointer");
+
+            let tuning_info = this.tunings.info(tuning_index as usize).with_context(|| {
+                format!(
+                    "Called with tuning index {tuning_index}, but the host only has {} tunings",
+                    this.tunings.count()
+                )
+            })?;
+            unsafe { info.write(tuning_info) };
+
+            span.finish(record!(result: true, tuning_id: tuning_info.tuning_id));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_resource_directory_request_directory(host: *const clap_host, is_shared: bool) -> bool {
+        let span = Span::begin(
+            "clap_host_resource_directory::request_directory",
+            record! { is_shared: is_shared },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<ResourceDirectory>()?;
+
+            // The validator assigns resource directories on its own terms through
+            // `clap_plugin_resource_directory::set_directory()`, so requests are always declined
+            span.finish(record!(result: false));
+            Ok(false)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_resource_directory_release_directory(host: *const clap_host, is_shared: bool) {
+        let span = Span::begin(
+            "clap_host_resource_directory::release_directory",
+            record! { is_shared: is_shared },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<ResourceDirectory>()?;
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_scratch_memory_reserve(
+        host: *const clap_host,
+        scratch_size_bytes: u32,
+        max_concurrency_hint: u32,
+    ) -> bool {
+        let span = Span::begin(
+            "clap_host_scratch_memory::reserve",
+            record! {
+                scratch_size_bytes: scratch_size_bytes,
+                max_concurrency_hint: max_concurrency_hint
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            anyhow::ensure!(
+                this.status() == PluginStatus::Activating,
+                "Scratch memory may only be reserved during 'clap_plugin::activate()', but the plugin's status is \
+                 {:?}.",
+                this.status()
+            );
+
+            let result = this.scratch_memory.reserve(scratch_size_bytes);
+            span.finish(record!(result: result));
+            Ok(result)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_scratch_memory_access(host: *const clap_host) -> *mut c_void {
+        let span = Span::begin("clap_host_scratch_memory::access", ());
+
+        Self::wrap(host, span.name(), |this| {
+            // This may also be called from the thread pool's worker threads, as long as it happens
+            // during a process call
+            anyhow::ensure!(
+                this.is_currently_in_process_call.load(),
+                "Scratch memory may only be accessed from within 'clap_plugin::process()'."
+            );
+            anyhow::ensure!(
+                std::thread::current().id() != this.main_thread_id,
+                "Scratch memory may only be accessed from the audio thread or from the thread pool, but it was \
+                 accessed from the main thread."
+            );
+
+            let result = this.scratch_memory.access() as *mut c_void;
+            span.finish(record!(result: format_args!("{:p}", result)));
+            Ok(result)
+        })
+        .unwrap_or(std::ptr::null_mut())
+    }
+
+    unsafe extern "C" fn ext_triggers_rescan(host: *const clap_host, flags: clap_trigger_rescan_flags) {
+        let span = Span::begin(
+            "clap_host_triggers::rescan",
+            record! {
+                rescan_info: flags & CLAP_TRIGGER_RESCAN_INFO != 0,
+                rescan_all: flags & CLAP_TRIGGER_RESCAN_ALL != 0
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<Triggers>()?;
+
+            if flags & CLAP_TRIGGER_RESCAN_INFO != 0 {
+                this.callback_sender.send(CallbackEvent::TriggersRescanInfo).unwrap();
+            }
+
+            if flags & CLAP_TRIGGER_RESCAN_ALL != 0 {
+                anyhow::ensure!(
+                    this.status() <= PluginStatus::Deactivated,
+                    "Called while the plugin is active"
+                );
+
+                this.callback_sender.send(CallbackEvent::TriggersRescanAll).unwrap();
+            }
+
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_triggers_clear(
+        host: *const clap_host,
+        trigger_id: clap_id,
+        flags: clap_trigger_clear_flags,
+    ) {
+        let span = Span::begin(
+            "clap_host_triggers::clear",
+            record! {
+                trigger_id: trigger_id,
+                clear_all: flags & CLAP_TRIGGER_CLEAR_ALL != 0,
+                clear_automations: flags & CLAP_TRIGGER_CLEAR_AUTOMATIONS != 0
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<Triggers>()?;
+
+            anyhow::ensure!(trigger_id != CLAP_INVALID_ID, "Called with `CLAP_INVALID_ID`");
+
+            // Whether the trigger actually exists is checked by the tests that handle this event
+            this.callback_sender
+                .send(CallbackEvent::TriggersClear(trigger_id))
+                .unwrap();
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_start(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_start", ());
+        Self::request_transport(host, span.name(), TransportRequest::Start);
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_stop(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_stop", ());
+        Self::request_transport(host, span.name(), TransportRequest::Stop);
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_continue(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_continue", ());
+        Self::request_transport(host, span.name(), TransportRequest::Continue);
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_pause(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_pause", ());
+        Self::request_transport(host, span.name(), TransportRequest::Pause);
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_toggle_play(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_toggle_play", ());
+        Self::request_transport(host, span.name(), TransportRequest::TogglePlay);
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_jump(host: *const clap_host, position: clap_beattime) {
+        let span = Span::begin("clap_host_transport_control::request_jump", record!(position: position));
+        Self::request_transport(host, span.name(), TransportRequest::Jump(position));
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_loop_region(
+        host: *const clap_host,
+        start: clap_beattime,
+        duration: clap_beattime,
+    ) {
+        let span = Span::begin(
+            "clap_host_transport_control::request_loop_region",
+            record! { start: start, duration: duration },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            anyhow::ensure!(
+                duration > 0,
+                "Called with a loop region duration of {duration}, which is not positive."
+            );
+
+            this.transport_requests
+                .push(TransportRequest::LoopRegion(start, duration));
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_toggle_loop(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_toggle_loop", ());
+        Self::request_transport(host, span.name(), TransportRequest::ToggleLoop);
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_enable_loop(host: *const clap_host, is_enabled: bool) {
+        let span = Span::begin(
+            "clap_host_transport_control::request_enable_loop",
+            record!(is_enabled: is_enabled),
+        );
+        Self::request_transport(host, span.name(), TransportRequest::EnableLoop(is_enabled));
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_record(host: *const clap_host, is_recording: bool) {
+        let span = Span::begin(
+            "clap_host_transport_control::request_record",
+            record!(is_recording: is_recording),
+        );
+        Self::request_transport(host, span.name(), TransportRequest::Record(is_recording));
+    }
+
+    unsafe extern "C" fn ext_transport_control_request_toggle_record(host: *const clap_host) {
+        let span = Span::begin("clap_host_transport_control::request_toggle_record", ());
+        Self::request_transport(host, span.name(), TransportRequest::ToggleRecord);
+    }
+
+    /// Record a transport request made through one of the `clap_host_transport_control` functions.
+    /// These may only be made from the main thread.
+    fn request_transport(host: *const clap_host, function_name: &'static str, request: TransportRequest) {
+        Self::wrap(host, function_name, |this| {
+            this.assert_main_thread()?;
+            this.transport_requests.push(request);
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_mini_curve_display_get_hints(
+        host: *const clap_host,
+        kind: u32,
+        hints: *mut clap_mini_curve_display_curve_hints,
+    ) -> bool {
+        let span = Span::begin("clap_host_mini_curve_display::get_hints", record!(kind: kind));
+
+        let result = Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<MiniCurveDisplay>()?;
+
+            if hints.is_null() {
+                anyhow::bail!("'hints' is a null pointer");
+            }
+
+            let Some(kind_hints) = curve_kind_hints(kind) else {
+                return Ok(false);
+            };
+
+            unsafe { *hints = kind_hints };
+            this.mini_curve_display_hint_kinds.lock().unwrap().insert(kind);
+
+            Ok(true)
+        })
+        .unwrap_or(false);
+
+        span.finish(record!(result: result));
+        result
+    }
+
+    unsafe extern "C" fn ext_mini_curve_display_set_dynamic(host: *const clap_host, is_dynamic: bool) {
+        let span = Span::begin(
+            "clap_host_mini_curve_display::set_dynamic",
+            record!(is_dynamic: is_dynamic),
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<MiniCurveDisplay>()?;
+
+            this.mini_curve_display_dynamic.store(is_dynamic);
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_mini_curve_display_changed(host: *const clap_host, flags: u32) {
+        let span = Span::begin(
+            "clap_host_mini_curve_display::changed",
+            record! {
+                curve_changed: flags & CLAP_MINI_CURVE_DISPLAY_CURVE_CHANGED != 0,
+                axis_name_changed: flags & CLAP_MINI_CURVE_DISPLAY_AXIS_NAME_CHANGED != 0
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_extension::<MiniCurveDisplay>()?;
+
+            anyhow::ensure!(
+                this.mini_curve_display_observed.load(),
+                "Called while the plugin's curves are not being observed"
+            );
+
+            if flags & CLAP_MINI_CURVE_DISPLAY_CURVE_CHANGED != 0 {
+                anyhow::ensure!(
+                    !this.mini_curve_display_dynamic.load(),
+                    "Called with 'CLAP_MINI_CURVE_DISPLAY_CURVE_CHANGED' while the plugin's curves are dynamic"
+                );
+
+                this.callback_sender
+                    .send(CallbackEvent::MiniCurveDisplayCurveChanged)
+                    .unwrap();
+            }
+
+            if flags & CLAP_MINI_CURVE_DISPLAY_AXIS_NAME_CHANGED != 0 {
+                this.callback_sender
+                    .send(CallbackEvent::MiniCurveDisplayAxisNameChanged)
+                    .unwrap();
+            }
+
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_gui_resize_hints_changed(host: *const clap_host) {
+        let span = Span::begin("clap_host_gui::resize_hints_changed", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_has_gui(true)?;
+            this.callback_sender.send(CallbackEvent::GuiResizeHintsChanged).unwrap();
+            Ok(())
+        });
+    }
+
+    unsafe extern "C" fn ext_gui_request_resize(host: *const clap_host, width: u32, height: u32) -> bool {
+        let span = Span::begin(
+            "clap_host_gui::request_resize",
+            record! { width: width, height: height },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_has_gui(true)?;
+            anyhow::ensure!(width > 0 && height > 0, "Requested a resize to {width}x{height} pixels");
+
+            this.callback_sender
+                .send(CallbackEvent::GuiRequestResize(width, height))
+                .unwrap();
+
+            span.finish(record!(result: true));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_gui_request_show(host: *const clap_host) -> bool {
+        let span = Span::begin("clap_host_gui::request_show", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_has_gui(false)?;
+            this.callback_sender.send(CallbackEvent::GuiRequestShow).unwrap();
+
+            span.finish(record!(result: true));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_gui_request_hide(host: *const clap_host) -> bool {
+        let span = Span::begin("clap_host_gui::request_hide", ());
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_has_gui(false)?;
+            this.callback_sender.send(CallbackEvent::GuiRequestHide).unwrap();
+
+            span.finish(record!(result: true));
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn ext_gui_closed(host: *const clap_host, was_destroyed: bool) {
+        let span = Span::begin(
+            "clap_host_gui::closed",
+            record! {
+                was_destroyed: was_destroyed
+            },
+        );
+
+        Self::wrap(host, span.name(), |this| {
+            this.assert_main_thread()?;
+            this.assert_has_gui(false)?;
+            this.callback_sender
+                .send(CallbackEvent::GuiClosed(was_destroyed))
+                .unwrap();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_sys::plugin::clap_plugin;
+
+    static TRIGGERS: clap_plugin_triggers = clap_plugin_triggers {
+        count: Some(triggers_count),
+        get_info: Some(triggers_get_info),
+    };
+
+    static FACTORY: clap_plugin_factory = clap_plugin_factory {
+        get_plugin_count: None,
+        get_plugin_descriptor: None,
+        create_plugin: Some(create_plugin),
+    };
+
+    /// A minimal plugin that implements the triggers extension and asks the host to rescan all of
+    /// its triggers from `on_main_thread()`. The host pointer is stored in `plugin_data` so tests
+    /// can also call the host's functions directly.
+    unsafe extern "C" fn create_plugin(
+        _factory: *const clap_plugin_factory,
+        host: *const clap_host,
+        _plugin_id: *const c_char,
+    ) -> *const clap_plugin {
+        Box::into_raw(Box::new(clap_plugin {
+            desc: std::ptr::null(),
+            plugin_data: host as *mut c_void,
+            init: Some(init),
+            destroy: Some(destroy),
+            activate: Some(activate),
+            deactivate: Some(deactivate),
+            start_processing: None,
+            stop_processing: None,
+            reset: None,
+            process: None,
+            get_extension: Some(get_extension),
+            on_main_thread: Some(on_main_thread),
+        }))
+    }
+
+    unsafe extern "C" fn init(_plugin: *const clap_plugin) -> bool {
+        true
+    }
+
+    unsafe extern "C" fn destroy(plugin: *const clap_plugin) {
+        drop(unsafe { Box::from_raw(plugin.cast_mut()) });
+    }
+
+    unsafe extern "C" fn activate(_plugin: *const clap_plugin, _sample_rate: f64, _min: u32, _max: u32) -> bool {
+        true
+    }
+
+    unsafe extern "C" fn deactivate(_plugin: *const clap_plugin) {}
+
+    unsafe extern "C" fn get_extension(_plugin: *const clap_plugin, id: *const c_char) -> *const c_void {
+        if unsafe { CStr::from_ptr(id) } == CLAP_EXT_TRIGGERS {
+            &TRIGGERS as *const _ as *const c_void
+        } else {
+            std::ptr::null()
+        }
+    }
+
+    unsafe extern "C" fn on_main_thread(plugin: *const clap_plugin) {
+        unsafe {
+            let host = (*plugin).plugin_data as *const clap_host;
+            let host_triggers =
+                clap_call! { host=>get_extension(host, CLAP_EXT_TRIGGERS.as_ptr()) } as *const clap_host_triggers;
+            clap_call! { host_triggers=>rescan(host, CLAP_TRIGGER_RESCAN_ALL) };
+        }
+    }
+
+    unsafe extern "C" fn triggers_count(_plugin: *const clap_plugin) -> u32 {
+        0
+    }
+
+    unsafe extern "C" fn triggers_get_info(
+        _plugin: *const clap_plugin,
+        _index: u32,
+        _info: *mut clap_trigger_info,
+    ) -> bool {
+        false
+    }
+
+    fn create_test_plugin<'a>() -> Plugin<'a> {
+        let plugin = unsafe { PluginShared::create_plugin(&FACTORY, c"test-plugin") }.unwrap();
+        plugin.init().unwrap();
+
+        plugin
+    }
+
+    #[test]
+    fn triggers_rescan_all_while_deactivated() {
+        let plugin = create_test_plugin();
+        plugin.shared().requested_callback.store(true);
+
+        let mut events = Vec::new();
+        plugin
+            .poll_callback(|event| {
+                events.push(event);
+                Ok(())
+            })
+            .unwrap();
+        assert!(matches!(events[..], [CallbackEvent::TriggersRescanAll]));
+    }
+
+    #[test]
+    fn scratch_memory_access_outside_process() {
+        let plugin = create_test_plugin();
+        plugin.activate(44100.0, 1, 512).unwrap();
+
+        let host = unsafe { (*plugin.as_ptr()).plugin_data } as *const clap_host;
+        let result = unsafe { PluginShared::ext_scratch_memory_access(host) };
+        assert!(result.is_null());
+
+        let error = format!("{:#}", plugin.poll_callback(|_| Ok(())).unwrap_err());
+        plugin.deactivate();
+        assert_eq!(
+            error,
+            "clap_host_scratch_memory::access: Scratch memory may only be accessed from within \
+             'clap_plugin::process()'."
+        );
+    }
+
+    #[test]
+    fn triggers_rescan_all_while_activated() {
+        let plugin = create_test_plugin();
+        plugin.activate(44100.0, 1, 512).unwrap();
+        plugin.shared().requested_callback.store(true);
+
+        let result = plugin.poll_callback(|_| Ok(()));
+        plugin.deactivate();
+
+        let error = format!("{:#}", result.unwrap_err());
+        assert!(error.contains("Called while the plugin is active"), "{error}");
+    }
 }
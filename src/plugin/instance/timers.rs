@@ -0,0 +1,77 @@
+//! Bookkeeping for the timers registered through the host's `timer-support` extension.
+
+use clap_sys::id::clap_id;
+use crossbeam::atomic::AtomicCell;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The timers a plugin instance has registered with the host. These are fired from the main thread
+/// by [`Plugin`][super::Plugin] whenever it polls for callbacks or idles while an audio thread is
+/// running.
+#[derive(Default)]
+pub struct Timers {
+    timers: Mutex<BTreeMap<clap_id, Timer>>,
+    next_id: AtomicCell<clap_id>,
+    num_ticks: AtomicCell<u64>,
+}
+
+struct Timer {
+    period: Duration,
+    next_tick: Instant,
+}
+
+impl Timers {
+    /// Register a new timer with the given period and return its ID.
+    pub fn register(&self, period: Duration) -> clap_id {
+        let id = self.next_id.fetch_add(1);
+        self.timers.lock().unwrap().insert(
+            id,
+            Timer {
+                period,
+                next_tick: Instant::now() + period,
+            },
+        );
+
+        id
+    }
+
+    /// Unregister a timer. Returns `false` if no timer with that ID was registered.
+    pub fn unregister(&self, id: clap_id) -> bool {
+        self.timers.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Whether the timer with this ID is currently registered.
+    pub fn contains(&self, id: clap_id) -> bool {
+        self.timers.lock().unwrap().contains_key(&id)
+    }
+
+    /// The IDs of all currently registered timers.
+    pub fn registered(&self) -> Vec<clap_id> {
+        self.timers.lock().unwrap().keys().copied().collect()
+    }
+
+    /// The point in time when the next timer is due, if there are any timers.
+    pub fn next_tick(&self) -> Option<Instant> {
+        self.timers.lock().unwrap().values().map(|timer| timer.next_tick).min()
+    }
+
+    /// Return the IDs of all timers that are due at `now`, and schedule their next tick.
+    pub fn take_due(&self, now: Instant) -> Vec<clap_id> {
+        let mut due = Vec::new();
+        for (id, timer) in self.timers.lock().unwrap().iter_mut() {
+            if timer.next_tick <= now {
+                timer.next_tick = now + timer.period;
+                due.push(*id);
+            }
+        }
+
+        self.num_ticks.fetch_add(due.len() as u64);
+        due
+    }
+
+    /// The total number of timer ticks that have been handed out by [`take_due()`][Self::take_due()].
+    pub fn num_ticks(&self) -> u64 {
+        self.num_ticks.load()
+    }
+}
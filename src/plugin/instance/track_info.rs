@@ -0,0 +1,65 @@
+//! The track information the host exposes to the plugin through the `track-info` extension.
+
+use clap_sys::color::clap_color;
+use clap_sys::ext::audio_ports::CLAP_PORT_STEREO;
+use clap_sys::ext::track_info::*;
+use clap_sys::string_sizes::CLAP_NAME_SIZE;
+use std::ffi::{CStr, c_char};
+
+/// The track information returned from `clap_host_track_info::get()`. This can be changed freely by
+/// the tests, after which the plugin should be notified through `clap_plugin_track_info::changed()`.
+#[derive(Debug, Clone)]
+pub struct HostTrackInfo {
+    /// A combination of the `CLAP_TRACK_INFO_*` flags. These are passed to the plugin as is, so they
+    /// don't need to be consistent with the other fields.
+    pub flags: u64,
+    /// The track's name. This is truncated at a character boundary to fit in `CLAP_NAME_SIZE` bytes.
+    pub name: String,
+    pub color: clap_color,
+    pub audio_channel_count: i32,
+    /// The track's audio port type, or a null pointer if this is `None`.
+    pub audio_port_type: Option<&'static CStr>,
+}
+
+impl Default for HostTrackInfo {
+    fn default() -> Self {
+        Self {
+            flags: CLAP_TRACK_INFO_HAS_TRACK_NAME | CLAP_TRACK_INFO_HAS_TRACK_COLOR | CLAP_TRACK_INFO_HAS_AUDIO_CHANNEL,
+            name: String::from("Track 1"),
+            color: clap_color {
+                alpha: 255,
+                red: 87,
+                green: 138,
+                blue: 201,
+            },
+            audio_channel_count: 2,
+            audio_port_type: Some(CLAP_PORT_STEREO),
+        }
+    }
+}
+
+impl HostTrackInfo {
+    /// Convert this to the C-struct passed to the plugin.
+    pub fn to_clap(&self) -> clap_track_info {
+        let mut name_len = self.name.len().min(CLAP_NAME_SIZE - 1);
+        while !self.name.is_char_boundary(name_len) {
+            name_len -= 1;
+        }
+
+        let mut name = [0 as c_char; CLAP_NAME_SIZE];
+        for (dest, &byte) in name.iter_mut().zip(&self.name.as_bytes()[..name_len]) {
+            *dest = byte as c_char;
+        }
+
+        clap_track_info {
+            flags: self.flags,
+            name,
+            color: self.color,
+            audio_channel_count: self.audio_channel_count,
+            audio_port_type: self
+                .audio_port_type
+                .map(|port_type| port_type.as_ptr())
+                .unwrap_or(std::ptr::null()),
+        }
+    }
+}
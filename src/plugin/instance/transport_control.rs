@@ -0,0 +1,76 @@
+//! Transport requests made by the plugin through the host's draft `transport-control` extension.
+
+use crate::plugin::process::TransportState;
+use clap_sys::fixedpoint::{CLAP_BEATTIME_FACTOR, clap_beattime};
+use std::sync::Mutex;
+
+/// The transport requests a plugin instance made through `clap_host_transport_control` that have
+/// not yet been applied to a [`ProcessScope`][crate::plugin::process::ProcessScope]'s transport.
+#[derive(Default)]
+pub struct TransportRequests {
+    pending: Mutex<Vec<TransportRequest>>,
+}
+
+/// A single `clap_host_transport_control::request_*()` call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransportRequest {
+    Start,
+    Stop,
+    Continue,
+    Pause,
+    TogglePlay,
+    /// Jump to a position in beats.
+    Jump(clap_beattime),
+    /// Set the loop region's start position and duration in beats.
+    LoopRegion(clap_beattime, clap_beattime),
+    ToggleLoop,
+    EnableLoop(bool),
+    Record(bool),
+    ToggleRecord,
+}
+
+impl TransportRequests {
+    /// Record a new request.
+    pub fn push(&self, request: TransportRequest) {
+        self.pending.lock().unwrap().push(request);
+    }
+
+    /// Take all requests that have been made since the last call, in the order they were made in.
+    pub fn take(&self) -> Vec<TransportRequest> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+}
+
+impl TransportRequest {
+    /// Apply this request to a transport state, like a host's transport would. Starting and
+    /// stopping jump back to the start of the timeline, while continuing and pausing keep the
+    /// current position.
+    pub fn apply(self, transport: &mut TransportState) {
+        match self {
+            TransportRequest::Start => {
+                transport.jump_to_beats(0.0);
+                transport.is_playing = true;
+            }
+            TransportRequest::Stop => {
+                transport.jump_to_beats(0.0);
+                transport.is_playing = false;
+                transport.is_recording = false;
+            }
+            TransportRequest::Continue => transport.is_playing = true,
+            TransportRequest::Pause => transport.is_playing = false,
+            TransportRequest::TogglePlay => transport.is_playing = !transport.is_playing,
+            TransportRequest::Jump(position) => {
+                transport.jump_to_beats(position as f64 / CLAP_BEATTIME_FACTOR as f64);
+            }
+            TransportRequest::LoopRegion(start, duration) => {
+                let start = start as f64 / CLAP_BEATTIME_FACTOR as f64;
+                let duration = duration as f64 / CLAP_BEATTIME_FACTOR as f64;
+                transport.loop_region = Some((start, start + duration));
+            }
+            TransportRequest::ToggleLoop => transport.is_loop_active = !transport.is_loop_active,
+            TransportRequest::EnableLoop(is_enabled) => transport.is_loop_active = is_enabled,
+            TransportRequest::Record(is_recording) => transport.is_recording = is_recording,
+            TransportRequest::ToggleRecord => transport.is_recording = !transport.is_recording,
+        }
+    }
+}
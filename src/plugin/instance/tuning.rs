@@ -0,0 +1,121 @@
+//! The tuning pool the host exposes to the plugin through the draft `tuning` extension.
+
+use clap_sys::ext::draft::tuning::clap_tuning_info;
+use clap_sys::id::clap_id;
+use clap_sys::string_sizes::CLAP_NAME_SIZE;
+use crossbeam::atomic::AtomicCell;
+use std::ffi::c_char;
+use std::sync::Mutex;
+
+/// The number of MIDI keys a tuning defines offsets for.
+pub const NUM_TUNING_KEYS: usize = 128;
+
+/// The tunings a plugin instance can query through `clap_host_tuning`. This is empty by default.
+/// Tests can change the pool freely, after which the plugin should be notified through
+/// `clap_plugin_tuning::changed()`.
+#[derive(Default)]
+pub struct HostTunings {
+    tunings: Mutex<Vec<HostTuning>>,
+    num_get_relative_calls: AtomicCell<u64>,
+}
+
+/// A single tuning in the host's tuning pool.
+#[derive(Debug, Clone)]
+pub struct HostTuning {
+    pub tuning_id: clap_id,
+    /// The tuning's name. This is truncated at a character boundary to fit in `CLAP_NAME_SIZE` bytes.
+    pub name: String,
+    pub is_dynamic: bool,
+    /// The offset for every key in semitones, relative to equal temperament with A4 = 440 Hz.
+    pub relative: [f64; NUM_TUNING_KEYS],
+    /// Whether the plugin should play a key at all.
+    pub should_play: [bool; NUM_TUNING_KEYS],
+}
+
+impl HostTunings {
+    /// Replace the entire tuning pool.
+    pub fn set(&self, tunings: Vec<HostTuning>) {
+        *self.tunings.lock().unwrap() = tunings;
+    }
+
+    /// The number of tunings in the pool.
+    pub fn count(&self) -> usize {
+        self.tunings.lock().unwrap().len()
+    }
+
+    /// Get information about the tuning at `index`, or `None` if the index is out of bounds.
+    pub fn info(&self, index: usize) -> Option<clap_tuning_info> {
+        self.tunings.lock().unwrap().get(index).map(HostTuning::to_clap)
+    }
+
+    /// The relative tuning in semitones for a key. Unknown tunings and out of range keys result in
+    /// no offset at all, as the extension requires the host to gracefully handle those.
+    pub fn get_relative(&self, tuning_id: clap_id, key: i32) -> f64 {
+        self.num_get_relative_calls.fetch_add(1);
+
+        let tunings = self.tunings.lock().unwrap();
+        match (
+            tunings.iter().find(|tuning| tuning.tuning_id == tuning_id),
+            usize::try_from(key),
+        ) {
+            (Some(tuning), Ok(key)) if key < NUM_TUNING_KEYS => tuning.relative[key],
+            _ => 0.0,
+        }
+    }
+
+    /// Whether a key should be played with this tuning. Unknown tunings and out of range keys are
+    /// always played.
+    pub fn should_play(&self, tuning_id: clap_id, key: i32) -> bool {
+        let tunings = self.tunings.lock().unwrap();
+        match (
+            tunings.iter().find(|tuning| tuning.tuning_id == tuning_id),
+            usize::try_from(key),
+        ) {
+            (Some(tuning), Ok(key)) if key < NUM_TUNING_KEYS => tuning.should_play[key],
+            _ => true,
+        }
+    }
+
+    /// The total number of `clap_host_tuning::get_relative()` calls made by the plugin.
+    pub fn num_get_relative_calls(&self) -> u64 {
+        self.num_get_relative_calls.load()
+    }
+}
+
+impl HostTuning {
+    /// An equal temperament tuning with `divisions` steps per octave, anchored at A4 = 440 Hz.
+    pub fn equal_temperament(tuning_id: clap_id, divisions: u32) -> Self {
+        let mut relative = [0.0; NUM_TUNING_KEYS];
+        for (key, offset) in relative.iter_mut().enumerate() {
+            let steps_from_a4 = key as f64 - 69.0;
+            *offset = steps_from_a4 * (12.0 / divisions as f64) - steps_from_a4;
+        }
+
+        Self {
+            tuning_id,
+            name: format!("{divisions}-EDO"),
+            is_dynamic: false,
+            relative,
+            should_play: [true; NUM_TUNING_KEYS],
+        }
+    }
+
+    /// Convert this to the C-struct passed to the plugin.
+    pub fn to_clap(&self) -> clap_tuning_info {
+        let mut name_len = self.name.len().min(CLAP_NAME_SIZE - 1);
+        while !self.name.is_char_boundary(name_len) {
+            name_len -= 1;
+        }
+
+        let mut name = [0 as c_char; CLAP_NAME_SIZE];
+        for (dest, &byte) in name.iter_mut().zip(&self.name.as_bytes()[..name_len]) {
+            *dest = byte as c_char;
+        }
+
+        clap_tuning_info {
+            tuning_id: self.tuning_id,
+            name,
+            is_dynamic: self.is_dynamic,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Bookkeeping for the changes reported through the host's `undo` extension.
+
+use anyhow::Result;
+use crossbeam::atomic::AtomicCell;
+use std::sync::Mutex;
+
+/// The undoable changes a plugin instance has reported through `clap_host_undo::change_made()`,
+/// in the order they were made.
+#[derive(Default)]
+pub struct UndoHistory {
+    changes: Mutex<Vec<UndoChange>>,
+    /// Whether the plugin is currently in a long running change started with
+    /// `clap_host_undo::begin_change()`.
+    in_change: AtomicCell<bool>,
+    /// Whether the plugin wants to receive updates through `clap_plugin_undo_context`.
+    wants_context_updates: AtomicCell<bool>,
+}
+
+/// A single change reported through `clap_host_undo::change_made()`.
+#[derive(Debug, Clone)]
+pub struct UndoChange {
+    pub name: String,
+    /// The plugin's delta for this change, if it provided one.
+    pub delta: Option<Vec<u8>>,
+    /// Whether the delta can be used to undo the change. It can always be used to redo the change.
+    pub delta_can_undo: bool,
+}
+
+impl UndoHistory {
+    /// Start a long running change. Returns an error if another change is already in progress.
+    pub fn begin_change(&self) -> Result<()> {
+        anyhow::ensure!(
+            !self.in_change.swap(true),
+            "Called while a change started by a previous 'clap_host_undo::begin_change()' call was still in progress."
+        );
+
+        Ok(())
+    }
+
+    /// Cancel the current long running change. Returns an error if no change was in progress.
+    pub fn cancel_change(&self) -> Result<()> {
+        anyhow::ensure!(
+            self.in_change.swap(false),
+            "Called without a matching 'clap_host_undo::begin_change()' call."
+        );
+
+        Ok(())
+    }
+
+    /// Record a completed change. This also ends the current long running change, if any.
+    pub fn change_made(&self, change: UndoChange) {
+        self.in_change.store(false);
+        self.changes.lock().unwrap().push(change);
+    }
+
+    pub fn set_wants_context_updates(&self, is_subscribed: bool) {
+        self.wants_context_updates.store(is_subscribed);
+    }
+
+    /// Whether the plugin has subscribed to `clap_plugin_undo_context` updates.
+    pub fn wants_context_updates(&self) -> bool {
+        self.wants_context_updates.load()
+    }
+
+    /// The number of changes recorded so far.
+    pub fn num_changes(&self) -> usize {
+        self.changes.lock().unwrap().len()
+    }
+
+    /// All changes recorded after the first `start` changes.
+    pub fn changes_since(&self, start: usize) -> Vec<UndoChange> {
+        self.changes.lock().unwrap().get(start..).unwrap_or_default().to_vec()
+    }
+}
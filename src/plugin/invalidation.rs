@@ -0,0 +1,127 @@
+//! An abstraction for the draft plugin invalidation factory.
+
+use super::library::PluginLibrary;
+use super::util::{self, clap_call};
+use crate::cli::tracing::{Span, record};
+use anyhow::{Context, Result};
+use clap_sys::factory::draft::plugin_invalidation::{
+    clap_plugin_invalidation_factory, clap_plugin_invalidation_source,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::ptr::NonNull;
+
+/// A wrapper around a CLAP plugin invalidation factory. Plugin libraries that expose their plugins
+/// based on other files on disk, like shell plugins, use this to tell the host which files it
+/// should monitor to know when the library needs to be scanned again.
+///
+/// See <https://github.com/free-audio/clap/blob/main/include/clap/factory/draft/plugin-invalidation.h>
+/// for more information.
+#[derive(Debug)]
+pub struct PluginInvalidationFactory<'lib> {
+    factory: NonNull<clap_plugin_invalidation_factory>,
+
+    /// The CLAP plugin library this factory was created from. This field is not used directly, but
+    /// keeping a reference to the library here prevents the factory from outliving the library.
+    _library: &'lib PluginLibrary,
+}
+
+/// A set of files the host should monitor for changes. See
+/// [`PluginInvalidationFactory::sources()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct InvalidationSource {
+    /// The absolute path to the directory containing the files.
+    pub directory: String,
+    /// A glob pattern for the file names, like `*.dll`.
+    pub filename_glob: String,
+    /// Whether the directory's subdirectories should also be monitored.
+    pub recursive_scan: bool,
+}
+
+impl InvalidationSource {
+    /// Parse the source from a `clap_plugin_invalidation_source`.
+    pub unsafe fn from_raw(source: *const clap_plugin_invalidation_source) -> Result<Self> {
+        anyhow::ensure!(!source.is_null(), "The invalidation source is a null pointer.");
+
+        let source = unsafe { &*source };
+        let directory = unsafe { util::cstr_ptr_to_mandatory_string(source.directory) }
+            .context("Error parsing the invalidation source's 'directory' field")?;
+        let filename_glob = unsafe { util::cstr_ptr_to_mandatory_string(source.filename_glob) }
+            .context("Error parsing the invalidation source's 'filename_glob' field")?;
+
+        if !Path::new(&directory).is_absolute() {
+            anyhow::bail!("The invalidation source's directory '{directory}' is not an absolute path.");
+        }
+
+        Ok(InvalidationSource {
+            directory,
+            filename_glob,
+            recursive_scan: source.recursive_scan,
+        })
+    }
+}
+
+impl<'lib> PluginInvalidationFactory<'lib> {
+    /// Create a wrapper around a plugin invalidation factory instance returned from a CLAP
+    /// plugin's entry point.
+    pub fn new(library: &'lib PluginLibrary, factory: NonNull<clap_plugin_invalidation_factory>) -> Self {
+        PluginInvalidationFactory {
+            factory,
+            _library: library,
+        }
+    }
+
+    /// Get the raw pointer to the `clap_plugin_invalidation_factory` instance.
+    pub fn as_ptr(&self) -> *const clap_plugin_invalidation_factory {
+        self.factory.as_ptr()
+    }
+
+    /// Return all of the factory's invalidation sources. Returns an error if any of the sources
+    /// are malformed, for instance if their directory is not an absolute path.
+    pub fn sources(&self) -> Result<Vec<InvalidationSource>> {
+        let factory = self.as_ptr();
+        let span = Span::begin("clap_plugin_invalidation_factory::count", ());
+        let num_sources = unsafe {
+            clap_call! { factory=>count(factory) }
+        };
+        span.finish(record!(result: num_sources));
+
+        let mut sources = Vec::with_capacity(num_sources as usize);
+        for i in 0..num_sources {
+            let span = Span::begin("clap_plugin_invalidation_factory::get", record!(index: i));
+            let source = unsafe {
+                clap_call! { factory=>get(factory, i) }
+            };
+            span.finish(record!(result: format_args!("{:p}", source)));
+
+            if source.is_null() {
+                anyhow::bail!(
+                    "The plugin invalidation factory returned a null pointer for the source at index {i} (expected \
+                     {num_sources} total sources)."
+                );
+            }
+
+            sources.push(
+                unsafe { InvalidationSource::from_raw(source) }
+                    .with_context(|| format!("Error parsing invalidation source {i}"))?,
+            );
+        }
+
+        Ok(sources)
+    }
+
+    /// Ask the plugin library to update its set of available plugins after one of the
+    /// invalidation sources changed. If this returns `false`, then the library needs to be
+    /// reloaded instead.
+    pub fn refresh(&self) -> bool {
+        let factory = self.as_ptr();
+        let span = Span::begin("clap_plugin_invalidation_factory::refresh", ());
+        let result = unsafe {
+            clap_call! { factory=>refresh(factory) }
+        };
+
+        span.finish(record!(result: result));
+        result
+    }
+}
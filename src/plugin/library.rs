@@ -1,12 +1,20 @@
 //! Interactions with CLAP plugin libraries, which may contain multiple plugins.
 
 use super::instance::Plugin;
+use super::invalidation::PluginInvalidationFactory;
 use super::preset_discovery::PresetDiscoveryFactory;
+use super::state_converter::PluginStateConverterFactory;
 use super::util::{self, clap_call};
 use crate::cli::tracing::{Span, record};
 use crate::plugin::instance::PluginShared;
 use anyhow::{Context, Result};
 use clap_sys::entry::clap_plugin_entry;
+use clap_sys::factory::draft::plugin_invalidation::{
+    CLAP_PLUGIN_INVALIDATION_FACTORY_ID, clap_plugin_invalidation_factory,
+};
+use clap_sys::factory::draft::plugin_state_converter::{
+    CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID, clap_plugin_state_converter_factory,
+};
 use clap_sys::factory::plugin_factory::{CLAP_PLUGIN_FACTORY_ID, clap_plugin_factory};
 use clap_sys::factory::preset_discovery::{CLAP_PRESET_DISCOVERY_FACTORY_ID, clap_preset_discovery_factory};
 use clap_sys::plugin::clap_plugin_descriptor;
@@ -304,6 +312,50 @@ impl PluginLibrary {
             }
         }
     }
+
+    /// Returns the plugin's plugin invalidation factory, if it has one.
+    pub fn plugin_invalidation_factory(&self) -> Result<PluginInvalidationFactory<'_>> {
+        let entry_point =
+            get_clap_entry_point(&self.library).expect("A Plugin was constructed for a plugin with no entry point");
+        let plugin_invalidation_factory = unsafe {
+            clap_call! {
+                entry_point=>get_factory(CLAP_PLUGIN_INVALIDATION_FACTORY_ID.as_ptr())
+            }
+        } as *mut clap_plugin_invalidation_factory;
+
+        match NonNull::new(plugin_invalidation_factory) {
+            Some(plugin_invalidation_factory) => Ok(PluginInvalidationFactory::new(self, plugin_invalidation_factory)),
+            None => {
+                anyhow::bail!(
+                    "The plugin does not support the '{}' factory.",
+                    CLAP_PLUGIN_INVALIDATION_FACTORY_ID.to_str().unwrap()
+                )
+            }
+        }
+    }
+
+    /// Returns the plugin's plugin state converter factory, if it has one.
+    pub fn plugin_state_converter_factory(&self) -> Result<PluginStateConverterFactory<'_>> {
+        let entry_point =
+            get_clap_entry_point(&self.library).expect("A Plugin was constructed for a plugin with no entry point");
+        let plugin_state_converter_factory = unsafe {
+            clap_call! {
+                entry_point=>get_factory(CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID.as_ptr())
+            }
+        } as *mut clap_plugin_state_converter_factory;
+
+        match NonNull::new(plugin_state_converter_factory) {
+            Some(plugin_state_converter_factory) => {
+                Ok(PluginStateConverterFactory::new(self, plugin_state_converter_factory))
+            }
+            None => {
+                anyhow::bail!(
+                    "The plugin does not support the '{}' factory.",
+                    CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID.to_str().unwrap()
+                )
+            }
+        }
+    }
 }
 
 impl PluginLibraryMetadata {
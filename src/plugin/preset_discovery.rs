@@ -15,7 +15,7 @@ mod metadata_receiver;
 mod provider;
 
 pub use self::indexer::{Flags, Location, LocationValue, Soundpack};
-pub use self::metadata_receiver::{PluginAbi, Preset, PresetFile};
+pub use self::metadata_receiver::{PluginAbi, PluginId, Preset, PresetFile};
 pub use self::provider::Provider;
 
 /// A `Send+Sync` wrapper around `*const clap_preset_discovery_factory`.
@@ -1,7 +1,10 @@
 //! Data structures and functions surrounding audio processing.
-use crate::plugin::instance::{PluginAudioThread, PluginStatus, ProcessInfo, ProcessStatus};
+use crate::plugin::ext::render::Render;
+use crate::plugin::instance::{PluginAudioThread, PluginStatus, ProcessInfo, ProcessStatus, TransportRequest};
 use crate::plugin::util::Proxy;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap_sys::events::clap_event_transport;
+use clap_sys::ext::render::{CLAP_RENDER_OFFLINE, clap_plugin_render_mode};
 
 mod buffer;
 mod events;
@@ -19,7 +22,15 @@ pub struct ProcessScope<'a> {
     events_output: Proxy<OutputEventQueue>,
 
     transport: TransportState,
+    /// The transport requests made by the plugin that were applied to the transport before the
+    /// last process call.
+    transport_requests: Vec<TransportRequest>,
+    /// The transport passed to the plugin during the last process call, if any.
+    last_transport: Option<clap_event_transport>,
     sample_rate: f64,
+    /// The rendering mode set through the `render` extension before the plugin gets activated, if
+    /// any.
+    render_mode: Option<clap_plugin_render_mode>,
 }
 
 #[derive(Debug)]
@@ -52,10 +63,20 @@ impl<'a> ProcessScope<'a> {
             events_input: InputEventQueue::new(),
             events_output: OutputEventQueue::new(),
             transport: TransportState::dummy(),
+            transport_requests: Vec::new(),
+            last_transport: None,
             sample_rate,
+            render_mode: None,
         })
     }
 
+    /// Switch the plugin to this rendering mode using the `render` extension right before it gets
+    /// activated. Processing fails if the plugin does not implement the extension or if it refuses
+    /// the rendering mode.
+    pub fn set_render_mode(&mut self, mode: clap_plugin_render_mode) {
+        self.render_mode = Some(mode);
+    }
+
     pub fn sample_rate(&self) -> f64 {
         self.sample_rate
     }
@@ -68,7 +89,6 @@ impl<'a> ProcessScope<'a> {
         self.events_input.add_events(events);
     }
 
-    #[allow(unused)]
     pub fn read_events(&self) -> Vec<Event> {
         self.events_output.read()
     }
@@ -77,6 +97,18 @@ impl<'a> ProcessScope<'a> {
         &mut self.transport
     }
 
+    /// The transport requests the plugin made through `clap_host_transport_control` that were
+    /// applied to the transport right before the last process call, in the order they were made in.
+    pub fn transport_requests(&self) -> &[TransportRequest] {
+        &self.transport_requests
+    }
+
+    /// The transport passed to the plugin during the last process call, or `None` if the transport
+    /// was free running or if nothing has been processed yet.
+    pub fn last_transport(&self) -> Option<&clap_event_transport> {
+        self.last_transport.as_ref()
+    }
+
     pub fn audio_buffers(&mut self) -> &mut AudioBuffers {
         self.buffer
     }
@@ -110,9 +142,26 @@ impl<'a> ProcessScope<'a> {
 
             let sample_rate = self.sample_rate;
             let buffer_size = self.buffer.samples();
+            let render_mode = self.render_mode;
+
+            self.plugin.on_main_thread(move |plugin| {
+                if let Some(render_mode) = render_mode {
+                    let render = plugin
+                        .get_extension::<Render>()
+                        .context("Cannot set the rendering mode, the plugin does not implement 'render'")?;
+                    anyhow::ensure!(
+                        render.set(render_mode),
+                        "The plugin refused to switch to {} rendering.",
+                        if render_mode == CLAP_RENDER_OFFLINE {
+                            "offline"
+                        } else {
+                            "realtime"
+                        }
+                    );
+                }
 
-            self.plugin
-                .on_main_thread(move |plugin| plugin.activate(sample_rate, 1, buffer_size))?;
+                plugin.activate(sample_rate, 1, buffer_size)
+            })?;
         }
 
         // start processing if needed
@@ -137,12 +186,18 @@ impl<'a> ProcessScope<'a> {
             }
         }
 
+        // the plugin may have asked the host to change the transport since the last process call
+        self.transport_requests = self.plugin.shared().transport_requests.take();
+        for request in &self.transport_requests {
+            request.apply(&mut self.transport);
+        }
+        self.last_transport = (!self.transport.is_freerun).then(|| self.transport.as_clap_transport(0));
+
         // save original buffers for consistency check
         let original_buffers = self.buffer[..].to_owned();
 
         // run processing
         let status = self.buffer.process(|inputs, outputs| {
-            let transport = self.transport.as_clap_transport(0);
             self.plugin.process(ProcessInfo {
                 frames_count: run.block_size,
                 steady_time: self.transport.sample_pos,
@@ -150,10 +205,17 @@ impl<'a> ProcessScope<'a> {
                 audio_outputs: outputs,
                 input_events: &self.events_input,
                 output_events: &self.events_output,
-                transport: (!self.transport.is_freerun).then_some(&transport),
+                transport: self.last_transport.as_ref(),
             })
         })?;
 
+        // the plugin may not have written outside of its scratch memory during the process call
+        self.plugin
+            .shared()
+            .scratch_memory
+            .check_guards()
+            .context("Scratch memory corruption detected after 'clap_plugin::process()'")?;
+
         // clear input event queue and advance transport
         self.events_input.clear();
         self.transport.advance(run.block_size as i64, self.sample_rate());
@@ -2,6 +2,8 @@ use crate::cli::fail_test;
 use crate::cli::tracing::{Recordable, Recorder, Span, record};
 use crate::plugin::util::{CHECK_POINTER, Proxy, Proxyable};
 use clap_sys::events::*;
+use clap_sys::ext::draft::triggers::clap_event_trigger;
+use clap_sys::ext::draft::tuning::clap_event_tuning;
 use std::fmt::Debug;
 use std::sync::Mutex;
 
@@ -34,8 +36,15 @@ pub enum Event {
     ParamGesture(clap_event_param_gesture),
     /// `CLAP_EVENT_TRANSPORT`.
     Transport(clap_event_transport),
+    /// `clap_event_tuning` from the draft `tuning` extension. This event's space ID is assigned
+    /// through the host's event registry, so it is only ever sent to the plugin.
+    Tuning(clap_event_tuning),
+    /// `CLAP_EVENT_TRIGGER` from the draft `triggers` extension. Like [`Event::Tuning`], this uses
+    /// an event space assigned through the host's event registry.
+    Trigger(clap_event_trigger),
     /// An unhandled event type. This is only used when the plugin outputs an event we don't handle
-    /// or recognize.
+    /// or recognize. All output events outside of the core event space are parsed as this, including
+    /// events in event spaces the plugin never obtained through the host's event registry.
     Unknown(clap_event_header),
 }
 
@@ -221,6 +230,8 @@ impl Event {
             Event::Midi2(event) => &event.header,
             Event::Sysex(event) => &event.header,
             Event::Transport(event) => &event.header,
+            Event::Tuning(event) => &event.header,
+            Event::Trigger(event) => &event.header,
             Event::Unknown(header) => header,
         }
     }
@@ -330,7 +341,21 @@ impl Recordable for Event {
             Event::Transport(event) => {
                 record.record("info.transport", event);
             }
-            Event::Unknown(..) => {}
+            Event::Tuning(event) => {
+                record.record("info.port_index", event.port_index);
+                record.record("info.channel", event.channel);
+                record.record("info.tuning_id", event.tunning_id);
+            }
+            Event::Trigger(event) => {
+                record.record("info.trigger_id", event.trigger_id);
+                record.record("info.note_id", event.note_id);
+                record.record("info.port_index", event.port_index);
+                record.record("info.key", event.key);
+                record.record("info.channel", event.channel);
+            }
+            Event::Unknown(header) => {
+                record.record("info.size", header.size);
+            }
         }
     }
 }
@@ -33,6 +33,12 @@ pub struct TransportState {
 
     /// Current position in seconds. Sets `CLAP_TRANSPORT_HAS_SECONDS_TIMELINE` flag.
     pub position_seconds: Option<f64>,
+
+    /// The loop region's start and end positions in beats.
+    pub loop_region: Option<(f64, f64)>,
+
+    /// Whether looping is enabled. Sets `CLAP_TRANSPORT_IS_LOOP_ACTIVE` flag if there's also a loop region.
+    pub is_loop_active: bool,
 }
 
 impl TransportState {
@@ -51,9 +57,32 @@ impl TransportState {
             time_signature: Some((4, 4)),
             position_beats: Some(0.0),
             position_seconds: Some(0.0),
+            loop_region: None,
+            is_loop_active: false,
+        }
+    }
+
+    /// Jump to a position in beats. This does nothing if the transport doesn't have a beats
+    /// timeline. The position in seconds is updated to match if the transport also has a seconds
+    /// timeline and a tempo.
+    pub fn jump_to_beats(&mut self, position: f64) {
+        let Some(position_beats) = &mut self.position_beats else {
+            return;
+        };
+        let delta = position - *position_beats;
+        *position_beats = position;
+
+        if let (Some(position_seconds), Some((tempo, _))) = (&mut self.position_seconds, self.tempo) {
+            *position_seconds += delta * 60.0 / tempo;
         }
     }
 
+    /// The active loop region in beats, if looping is enabled and the region is not empty.
+    fn active_loop_region(&self) -> Option<(f64, f64)> {
+        self.loop_region
+            .filter(|(start, end)| self.is_loop_active && end > start)
+    }
+
     /// Advance the transport state by the given number of samples at the specified sample rate.
     pub fn advance(&mut self, samples: i64, sample_rate: f64) {
         if let Some(sample_pos) = &mut self.sample_pos {
@@ -78,6 +107,15 @@ impl TransportState {
                 *position_beats += (samples as f64 * (tempo_end + tempo_start) / 60.0 * 0.5) / sample_rate;
             }
         }
+
+        // Playback jumps back to the start of the loop region when it moves past its end
+        if self.is_playing
+            && let Some((loop_start, loop_end)) = self.active_loop_region()
+            && let Some(position_beats) = self.position_beats
+            && position_beats >= loop_end
+        {
+            self.jump_to_beats(loop_start + (position_beats - loop_end) % (loop_end - loop_start));
+        }
     }
 
     /// Convert the transport state to a CLAP transport event.
@@ -90,6 +128,12 @@ impl TransportState {
         flags |= self.position_seconds.is_some() as u32 * CLAP_TRANSPORT_HAS_SECONDS_TIMELINE;
         flags |= self.tempo.is_some() as u32 * CLAP_TRANSPORT_HAS_TEMPO;
         flags |= self.time_signature.is_some() as u32 * CLAP_TRANSPORT_HAS_TIME_SIGNATURE;
+        flags |= self.active_loop_region().is_some() as u32 * CLAP_TRANSPORT_IS_LOOP_ACTIVE;
+
+        let loop_seconds = self
+            .active_loop_region()
+            .zip(self.tempo)
+            .map(|((start, end), (tempo, _))| (start * 60.0 / tempo, end * 60.0 / tempo));
 
         clap_event_transport {
             flags,
@@ -113,10 +157,20 @@ impl TransportState {
                 .unwrap_or(i64::MIN),
             tempo: self.tempo.map(|(t, _)| t).unwrap_or(f64::NAN),
             tempo_inc: self.tempo.map(|(_, ti)| ti).unwrap_or(f64::NAN),
-            loop_start_beats: i64::MAX,
-            loop_end_beats: i64::MIN,
-            loop_start_seconds: i64::MAX,
-            loop_end_seconds: i64::MIN,
+            loop_start_beats: self
+                .active_loop_region()
+                .map(|(start, _)| (start * CLAP_BEATTIME_FACTOR as f64).round() as i64)
+                .unwrap_or(i64::MAX),
+            loop_end_beats: self
+                .active_loop_region()
+                .map(|(_, end)| (end * CLAP_BEATTIME_FACTOR as f64).round() as i64)
+                .unwrap_or(i64::MIN),
+            loop_start_seconds: loop_seconds
+                .map(|(start, _)| (start * CLAP_SECTIME_FACTOR as f64).round() as i64)
+                .unwrap_or(i64::MAX),
+            loop_end_seconds: loop_seconds
+                .map(|(_, end)| (end * CLAP_SECTIME_FACTOR as f64).round() as i64)
+                .unwrap_or(i64::MIN),
             bar_start: i64::MAX,
             bar_number: i32::MIN,
             tsig_num: self.time_signature.map(|(n, _)| n).unwrap_or(u16::MAX),
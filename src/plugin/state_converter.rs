@@ -0,0 +1,297 @@
+//! An abstraction for the draft plugin state converter factory.
+
+use super::ext::state::{InputStream, OutputStream};
+use super::library::PluginLibrary;
+use super::preset_discovery::{PluginAbi, PluginId};
+use super::util::{self, Proxy, clap_call};
+use crate::cli::tracing::{Span, record};
+use anyhow::{Context, Result};
+use clap_sys::factory::draft::plugin_state_converter::{
+    clap_plugin_state_converter, clap_plugin_state_converter_descriptor, clap_plugin_state_converter_factory,
+};
+use clap_sys::universal_plugin_id::clap_universal_plugin_id;
+use clap_sys::version::{clap_version, clap_version_is_compatible};
+use std::collections::HashSet;
+use std::ffi::{CString, c_char};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// The size of the error buffer passed to `clap_plugin_state_converter::convert_state()`.
+const ERROR_BUFFER_SIZE: usize = 4096;
+
+/// A wrapper around a CLAP plugin state converter factory. Plugins use this to convert state from
+/// other plugin formats, or from older versions of the plugin, to a state the destination CLAP
+/// plugin can load.
+///
+/// See <https://github.com/free-audio/clap/blob/main/include/clap/factory/draft/plugin-state-converter.h>
+/// for more information.
+#[derive(Debug)]
+pub struct PluginStateConverterFactory<'lib> {
+    factory: NonNull<clap_plugin_state_converter_factory>,
+
+    /// The CLAP plugin library this factory was created from. This field is not used directly, but
+    /// keeping a reference to the library here prevents the factory from outliving the library.
+    _library: &'lib PluginLibrary,
+}
+
+/// Metadata (descriptor) for a state converter. These converters can be instantiated by passing
+/// the metadata to [`PluginStateConverterFactory::create_converter()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateConverterMetadata {
+    pub id: String,
+    pub name: String,
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub clap_version: (u32, u32, u32),
+    /// The plugin whose state is converted.
+    pub src_plugin_id: PluginId,
+    /// The plugin the converted state is meant for.
+    pub dst_plugin_id: PluginId,
+}
+
+/// A state converter created from a state converter factory. The converter is destroyed when this
+/// object is dropped.
+#[derive(Debug)]
+pub struct StateConverter<'a> {
+    handle: NonNull<clap_plugin_state_converter>,
+
+    /// The factory this converter was created form. Only used for the lifetime.
+    _factory: &'a PluginStateConverterFactory<'a>,
+    /// To honor CLAP's thread safety guidelines, this converter cannot be shared with or sent to
+    /// other threads.
+    _send_sync_marker: PhantomData<*const ()>,
+}
+
+/// The result of [`StateConverter::convert_state()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateConversion {
+    /// The plugin converted the state.
+    Success(Vec<u8>),
+    /// The plugin returned false, with the error message it wrote to the error buffer. This may be
+    /// empty.
+    Failed(String),
+}
+
+impl StateConverterMetadata {
+    /// Parse the metadata from a `clap_plugin_state_converter_descriptor`.
+    pub unsafe fn from_descriptor(descriptor: *const clap_plugin_state_converter_descriptor) -> Result<Self> {
+        anyhow::ensure!(
+            !descriptor.is_null(),
+            "The state converter descriptor is a null pointer."
+        );
+
+        let descriptor = unsafe { &*descriptor };
+
+        Ok(StateConverterMetadata {
+            id: unsafe { util::cstr_ptr_to_mandatory_string(descriptor.id) }
+                .context("Error parsing the state converter's 'id' field")?,
+            name: unsafe { util::cstr_ptr_to_mandatory_string(descriptor.name) }
+                .context("Error parsing the state converter's 'name' field")?,
+            vendor: unsafe { util::cstr_ptr_to_optional_string(descriptor.vendor) }
+                .context("Error parsing the state converter's 'vendor' field")?,
+            version: unsafe { util::cstr_ptr_to_optional_string(descriptor.version) }
+                .context("Error parsing the state converter's 'version' field")?,
+            description: unsafe { util::cstr_ptr_to_optional_string(descriptor.description) }
+                .context("Error parsing the state converter's 'description' field")?,
+            clap_version: (
+                descriptor.clap_version.major,
+                descriptor.clap_version.minor,
+                descriptor.clap_version.revision,
+            ),
+            src_plugin_id: unsafe { parse_universal_plugin_id(&descriptor.src_plugin_id) }
+                .context("Error parsing the state converter's 'src_plugin_id' field")?,
+            dst_plugin_id: unsafe { parse_universal_plugin_id(&descriptor.dst_plugin_id) }
+                .context("Error parsing the state converter's 'dst_plugin_id' field")?,
+        })
+    }
+
+    /// Get the CLAP version representation for this converter.
+    pub fn clap_version(&self) -> clap_version {
+        clap_version {
+            major: self.clap_version.0,
+            minor: self.clap_version.1,
+            revision: self.clap_version.2,
+        }
+    }
+}
+
+impl<'lib> PluginStateConverterFactory<'lib> {
+    /// Create a wrapper around a plugin state converter factory instance returned from a CLAP
+    /// plugin's entry point.
+    pub fn new(library: &'lib PluginLibrary, factory: NonNull<clap_plugin_state_converter_factory>) -> Self {
+        PluginStateConverterFactory {
+            factory,
+            _library: library,
+        }
+    }
+
+    /// Get the raw pointer to the `clap_plugin_state_converter_factory` instance.
+    pub fn as_ptr(&self) -> *const clap_plugin_state_converter_factory {
+        self.factory.as_ptr()
+    }
+
+    /// Return metadata for all of the factory's state converters. These converters can be
+    /// instantiated using [`create_converter()`][Self::create_converter()].
+    pub fn metadata(&self) -> Result<Vec<StateConverterMetadata>> {
+        let factory = self.as_ptr();
+        let num_converters = unsafe {
+            clap_call! { factory=>count(factory) }
+        };
+
+        let mut metadata = Vec::with_capacity(num_converters as usize);
+        for i in 0..num_converters {
+            let descriptor = unsafe {
+                clap_call! { factory=>get_descriptor(factory, i) }
+            };
+
+            if descriptor.is_null() {
+                anyhow::bail!(
+                    "The state converter factory returned a null pointer for the descriptor at index {i} (expected \
+                     {num_converters} total converters)."
+                );
+            }
+
+            metadata.push(unsafe { StateConverterMetadata::from_descriptor(descriptor)? });
+        }
+
+        // As a sanity check we'll make sure there are no duplicate IDs in here
+        let unique_ids: HashSet<&str> = metadata
+            .iter()
+            .map(|converter_metadata| converter_metadata.id.as_str())
+            .collect();
+        if unique_ids.len() != metadata.len() {
+            anyhow::bail!("The state converter factory contains multiple entries for the same converter ID.");
+        }
+
+        Ok(metadata)
+    }
+
+    /// Create a state converter based on one of the converters returned by
+    /// [`metadata()`][Self::metadata()].
+    ///
+    /// Returns an error if the converter's CLAP version is not supported.
+    pub fn create_converter(&self, metadata: &StateConverterMetadata) -> Result<StateConverter<'_>> {
+        if !clap_version_is_compatible(metadata.clap_version()) {
+            anyhow::bail!(
+                "The state converter with ID '{}' has an unsupported CLAP version {:?}.",
+                metadata.id,
+                metadata.clap_version()
+            );
+        }
+
+        let converter_id_cstring = CString::new(metadata.id.as_str()).context("Converter ID contained null bytes")?;
+
+        let span = Span::begin(
+            "clap_plugin_state_converter_factory::create",
+            record! {
+                converter_id: metadata.id.as_str()
+            },
+        );
+
+        let factory = self.as_ptr();
+        let converter = unsafe {
+            clap_call! { factory=>create(factory, converter_id_cstring.as_ptr()) }
+        };
+
+        span.finish(record!(result: format_args!("{:p}", converter)));
+
+        match NonNull::new(converter) {
+            Some(converter) => Ok(StateConverter {
+                handle: converter,
+                _factory: self,
+                _send_sync_marker: PhantomData,
+            }),
+            None => anyhow::bail!(
+                "'clap_plugin_state_converter_factory::create()' returned a null pointer for the converter with ID \
+                 '{}'.",
+                metadata.id
+            ),
+        }
+    }
+}
+
+impl Drop for StateConverter<'_> {
+    fn drop(&mut self) {
+        let converter = self.as_ptr();
+
+        let _span = Span::begin("clap_plugin_state_converter::destroy", ());
+        unsafe {
+            clap_call! { converter=>destroy(converter) };
+        }
+    }
+}
+
+impl StateConverter<'_> {
+    /// Get the raw pointer to the `clap_plugin_state_converter` instance.
+    pub fn as_ptr(&self) -> *mut clap_plugin_state_converter {
+        self.handle.as_ptr()
+    }
+
+    /// Get this converter's metadata descriptor. In theory this should be the same as the one
+    /// retrieved from the factory earlier.
+    pub fn descriptor(&self) -> Result<StateConverterMetadata> {
+        let converter = self.as_ptr();
+        let descriptor = unsafe { (*converter).desc };
+
+        if descriptor.is_null() {
+            anyhow::bail!("The 'desc' field on the 'clap_plugin_state_converter' struct is a null pointer.");
+        }
+
+        unsafe { StateConverterMetadata::from_descriptor(descriptor) }
+    }
+
+    /// Convert the source plugin's state to a state the destination plugin can load. Returns an
+    /// error if the plugin returned false without writing a valid null terminated UTF-8 error
+    /// message to the error buffer.
+    pub fn convert_state(&self, state: &[u8]) -> Result<StateConversion> {
+        let input_stream = InputStream::new(state, None);
+        let output_stream = OutputStream::new(None);
+        let mut error_buffer = [0 as c_char; ERROR_BUFFER_SIZE];
+        let converter = self.as_ptr();
+
+        let span = Span::begin(
+            "clap_plugin_state_converter::convert_state",
+            record! { num_bytes: state.len() },
+        );
+        let result = unsafe {
+            clap_call! {
+                converter=>convert_state(
+                    converter,
+                    Proxy::vtable(&input_stream),
+                    Proxy::vtable(&output_stream),
+                    error_buffer.as_mut_ptr(),
+                    error_buffer.len()
+                )
+            }
+        };
+
+        span.finish(record!(result: result));
+
+        if result {
+            Ok(StateConversion::Success(output_stream.take()))
+        } else {
+            let error = util::c_char_slice_to_string(&error_buffer).context(
+                "'clap_plugin_state_converter::convert_state()' returned false, and the error message it wrote is \
+                 malformed",
+            )?;
+
+            Ok(StateConversion::Failed(error))
+        }
+    }
+}
+
+/// Parse a `clap_universal_plugin_id`. Both the ABI and the ID are mandatory.
+unsafe fn parse_universal_plugin_id(plugin_id: &clap_universal_plugin_id) -> Result<PluginId> {
+    let abi = unsafe { util::cstr_ptr_to_mandatory_string(plugin_id.abi) }.context("Error parsing the 'abi' field")?;
+    let id = unsafe { util::cstr_ptr_to_mandatory_string(plugin_id.id) }.context("Error parsing the 'id' field")?;
+
+    Ok(PluginId {
+        abi: if abi == "clap" {
+            PluginAbi::Clap
+        } else {
+            PluginAbi::Other(abi)
+        },
+        id,
+    })
+}
@@ -18,6 +18,7 @@ use std::str::FromStr;
 use std::time::Duration;
 use strum::IntoEnumIterator;
 
+mod display;
 mod plugin;
 mod plugin_library;
 mod rng;
@@ -119,6 +120,27 @@ pub trait TestCase<'a>: Into<&'static str> + Display + FromStr + Sized + 'static
 
         Ok((path, file))
     }
+
+    /// Get an empty temporary directory for this test case. The directory will be located at
+    /// `$TMP_DIR/clap-validator/$plugin_id/$test_name/$dir_name`, and it is cleared together with
+    /// the temporary files on a new validator run.
+    fn temporary_directory(&self, plugin_id: &str, name: &str) -> Result<PathBuf> {
+        let path = crate::cli::validator_temp_dir()
+            .join(plugin_id)
+            .join(self.to_string())
+            .join(name);
+
+        if path.exists() {
+            panic!(
+                "Tried to create a temporary directory at '{}', but this directory already exists",
+                path.display()
+            )
+        }
+
+        fs::create_dir_all(&path).expect("Could not create a temporary directory for the test");
+
+        Ok(path)
+    }
 }
 
 impl TestStatus {
@@ -0,0 +1,249 @@
+//! A display the `gui-*` tests can create plugin GUIs on. This connects to the X server from the
+//! `DISPLAY` environment variable, or starts the virtual X server from the config when that
+//! variable is not set. Xlib is loaded at runtime so the validator does not need to link against
+//! it on systems without X11.
+
+use crate::cli::GuiConfig;
+use anyhow::{Context, Result};
+use clap_sys::ext::gui::{CLAP_WINDOW_API_X11, clap_window, clap_window_handle};
+use std::ffi::{CString, c_char, c_int, c_uint, c_ulong, c_void};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+/// The display numbers tried when starting a virtual X server. Multiple validator processes may be
+/// running GUI tests at the same time, so the first number that's not in use is taken.
+const XVFB_DISPLAY_NUMBERS: RangeInclusive<u32> = 99..=162;
+/// How long to wait for a virtual X server to start accepting connections.
+const XVFB_STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+type XOpenDisplay = unsafe extern "C" fn(display_name: *const c_char) -> *mut c_void;
+type XCloseDisplay = unsafe extern "C" fn(display: *mut c_void) -> c_int;
+type XDefaultRootWindow = unsafe extern "C" fn(display: *mut c_void) -> c_ulong;
+type XCreateSimpleWindow = unsafe extern "C" fn(
+    display: *mut c_void,
+    parent: c_ulong,
+    x: c_int,
+    y: c_int,
+    width: c_uint,
+    height: c_uint,
+    border_width: c_uint,
+    border: c_ulong,
+    background: c_ulong,
+) -> c_ulong;
+type XMapWindow = unsafe extern "C" fn(display: *mut c_void, window: c_ulong) -> c_int;
+type XResizeWindow =
+    unsafe extern "C" fn(display: *mut c_void, window: c_ulong, width: c_uint, height: c_uint) -> c_int;
+type XDestroyWindow = unsafe extern "C" fn(display: *mut c_void, window: c_ulong) -> c_int;
+type XSync = unsafe extern "C" fn(display: *mut c_void, discard: c_int) -> c_int;
+
+/// The Xlib functions used to create parent windows.
+struct Xlib {
+    open_display: XOpenDisplay,
+    close_display: XCloseDisplay,
+    default_root_window: XDefaultRootWindow,
+    create_simple_window: XCreateSimpleWindow,
+    map_window: XMapWindow,
+    resize_window: XResizeWindow,
+    destroy_window: XDestroyWindow,
+    sync: XSync,
+
+    /// The loaded library. The function pointers above are only valid while this is alive.
+    _library: libloading::Library,
+}
+
+/// A connection to an X11 display. If the display is a virtual X server started for this test,
+/// then that server is stopped again when this object is dropped.
+pub struct TestDisplay {
+    xlib: Xlib,
+    display: NonNull<c_void>,
+
+    /// The virtual X server started from the config, if `DISPLAY` was not set.
+    xvfb: Option<Child>,
+}
+
+/// A top level window on a [`TestDisplay`] that plugin GUIs can be embedded in. The window is
+/// destroyed when this object is dropped.
+pub struct ParentWindow<'a> {
+    display: &'a TestDisplay,
+    window: c_ulong,
+}
+
+impl Xlib {
+    fn load() -> Result<Self> {
+        let library = unsafe { libloading::Library::new("libX11.so.6") }
+            .or_else(|_| unsafe { libloading::Library::new("libX11.so") })
+            .context("Could not load libX11")?;
+
+        unsafe {
+            Ok(Xlib {
+                open_display: *library.get(b"XOpenDisplay")?,
+                close_display: *library.get(b"XCloseDisplay")?,
+                default_root_window: *library.get(b"XDefaultRootWindow")?,
+                create_simple_window: *library.get(b"XCreateSimpleWindow")?,
+                map_window: *library.get(b"XMapWindow")?,
+                resize_window: *library.get(b"XResizeWindow")?,
+                destroy_window: *library.get(b"XDestroyWindow")?,
+                sync: *library.get(b"XSync")?,
+
+                _library: library,
+            })
+        }
+    }
+
+    fn connect(&self, display_name: &str) -> Option<NonNull<c_void>> {
+        let display_name = CString::new(display_name).ok()?;
+        NonNull::new(unsafe { (self.open_display)(display_name.as_ptr()) })
+    }
+}
+
+impl Drop for TestDisplay {
+    fn drop(&mut self) {
+        unsafe { (self.xlib.close_display)(self.display.as_ptr()) };
+
+        if let Some(mut xvfb) = self.xvfb.take() {
+            let _ = xvfb.kill();
+            let _ = xvfb.wait();
+
+            // SAFETY: See `start_xvfb()`
+            unsafe { std::env::remove_var("DISPLAY") };
+        }
+    }
+}
+
+impl TestDisplay {
+    /// Connect to the display from the `DISPLAY` environment variable, or start the virtual X
+    /// server from the config if that variable is not set. Returns `None` if no display is
+    /// available.
+    pub fn open(config: &GuiConfig) -> Result<Option<Self>> {
+        if !cfg!(all(unix, not(target_os = "macos"))) {
+            return Ok(None);
+        }
+
+        let display_name = std::env::var("DISPLAY").ok().filter(|name| !name.is_empty());
+        if display_name.is_none() && config.xvfb.is_none() {
+            return Ok(None);
+        }
+
+        let Ok(xlib) = Xlib::load() else {
+            return Ok(None);
+        };
+
+        match (display_name, &config.xvfb) {
+            (Some(display_name), _) => Ok(xlib.connect(&display_name).map(|display| TestDisplay {
+                xlib,
+                display,
+                xvfb: None,
+            })),
+            (None, Some(xvfb_command)) => {
+                let (display, xvfb) = start_xvfb(&xlib, xvfb_command)?;
+                Ok(Some(TestDisplay {
+                    xlib,
+                    display,
+                    xvfb: Some(xvfb),
+                }))
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Create a mapped top level window that plugin GUIs can be embedded in.
+    pub fn create_window(&self, width: u32, height: u32) -> Result<ParentWindow<'_>> {
+        let display = self.display.as_ptr();
+        let window = unsafe {
+            let root = (self.xlib.default_root_window)(display);
+            let window = (self.xlib.create_simple_window)(display, root, 0, 0, width.max(1), height.max(1), 0, 0, 0);
+            if window != 0 {
+                (self.xlib.map_window)(display, window);
+                (self.xlib.sync)(display, 0);
+            }
+
+            window
+        };
+
+        anyhow::ensure!(window != 0, "Could not create a {width}x{height} X11 window.");
+        Ok(ParentWindow { display: self, window })
+    }
+}
+
+impl ParentWindow<'_> {
+    /// The `clap_window` to pass to `clap_plugin_gui::set_parent()`.
+    pub fn clap_window(&self) -> clap_window {
+        clap_window {
+            api: CLAP_WINDOW_API_X11.as_ptr(),
+            specific: clap_window_handle { x11: self.window },
+        }
+    }
+
+    /// Resize the window, for instance after the plugin requested a resize.
+    pub fn resize(&self, width: u32, height: u32) {
+        let display = self.display.display.as_ptr();
+        unsafe {
+            (self.display.xlib.resize_window)(display, self.window, width.max(1), height.max(1));
+            (self.display.xlib.sync)(display, 0);
+        }
+    }
+}
+
+impl Drop for ParentWindow<'_> {
+    fn drop(&mut self) {
+        let display = self.display.display.as_ptr();
+        unsafe {
+            (self.display.xlib.destroy_window)(display, self.window);
+            (self.display.xlib.sync)(display, 0);
+        }
+    }
+}
+
+/// Start a virtual X server using the first free display number, wait for it to accept
+/// connections, and point the `DISPLAY` environment variable at it so the plugin connects to the
+/// same server.
+fn start_xvfb(xlib: &Xlib, xvfb_command: &str) -> Result<(NonNull<c_void>, Child)> {
+    for display_number in XVFB_DISPLAY_NUMBERS {
+        if Path::new(&format!("/tmp/.X{display_number}-lock")).exists() {
+            continue;
+        }
+
+        let display_name = format!(":{display_number}");
+        let mut xvfb = Command::new(xvfb_command)
+            .arg(&display_name)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Could not start the virtual X server '{xvfb_command}'"))?;
+
+        let start = Instant::now();
+        while start.elapsed() < XVFB_STARTUP_TIMEOUT {
+            // The server exits immediately if another process claimed the display number first
+            if xvfb.try_wait()?.is_some() {
+                break;
+            }
+
+            if let Some(display) = xlib.connect(&display_name) {
+                // SAFETY: Tests run one at a time within a validator process, and nothing else
+                //         reads the environment while the GUI tests run
+                unsafe { std::env::set_var("DISPLAY", &display_name) };
+
+                return Ok((display, xvfb));
+            }
+
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        if xvfb.try_wait()?.is_none() {
+            let _ = xvfb.kill();
+            let _ = xvfb.wait();
+
+            anyhow::bail!(
+                "The virtual X server '{xvfb_command}' did not accept connections on display '{display_name}' within \
+                 {} seconds.",
+                XVFB_STARTUP_TIMEOUT.as_secs()
+            );
+        }
+    }
+
+    anyhow::bail!("Could not start the virtual X server '{xvfb_command}' on any display number.")
+}
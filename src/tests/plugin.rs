@@ -5,15 +5,27 @@ use crate::cli::tracing::{Span, record};
 use crate::plugin::library::PluginLibrary;
 use crate::tests::TestStatus;
 use anyhow::{Context, Result};
+use clap_sys::ext::state_context::*;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+mod context_menu;
 mod descriptor;
+mod events;
+mod gui;
+mod latency;
 mod layout;
+mod mini_curve_display;
+mod notes;
 mod params;
 mod processing;
+mod render;
+mod resource_directory;
 mod state;
+mod tail;
 mod transport;
+mod triggers;
+mod undo;
 
 /// The tests for individual CLAP plugins. See the module's heading for more information, and the
 /// `description` function below for a description of each test case.
@@ -40,6 +52,24 @@ pub enum PluginTestCase {
     LayoutAudioPortsConfig,
     #[strum(serialize = "layout-configurable-audio-ports")]
     LayoutConfigurableAudioPorts,
+    #[strum(serialize = "layout-extensible-audio-ports")]
+    LayoutExtensibleAudioPorts,
+    #[strum(serialize = "context-menu-populate")]
+    ContextMenuPopulate,
+    #[strum(serialize = "events-foreign-space")]
+    EventsForeignSpace,
+    #[strum(serialize = "gui-lifecycle")]
+    GuiLifecycle,
+    #[strum(serialize = "gui-size-negotiation")]
+    GuiSizeNegotiation,
+    #[strum(serialize = "gui-create-destroy-processing")]
+    GuiCreateDestroyProcessing,
+    #[strum(serialize = "latency-accuracy")]
+    LatencyAccuracy,
+    #[strum(serialize = "mini-curve-display-render")]
+    MiniCurveDisplayRender,
+    #[strum(serialize = "note-names-consistency")]
+    NoteNamesConsistency,
     #[strum(serialize = "process-audio-basic-out-of-place")]
     ProcessAudioBasicOutOfPlace,
     #[strum(serialize = "process-audio-basic-in-place")]
@@ -58,6 +88,10 @@ pub enum PluginTestCase {
     ProcessNoteOutOfPlaceBasic,
     #[strum(serialize = "process-note-inconsistent")]
     ProcessNoteInconsistent,
+    #[strum(serialize = "process-note-tuning")]
+    ProcessNoteTuning,
+    #[strum(serialize = "process-gain-adjustment-metering")]
+    ProcessGainAdjustmentMetering,
     #[strum(serialize = "process-varying-sample-rates")]
     ProcessVaryingSampleRates,
     #[strum(serialize = "process-varying-block-sizes")]
@@ -66,6 +100,16 @@ pub enum PluginTestCase {
     ProcessRandomBlockSizes,
     #[strum(serialize = "process-reset-reactivate")]
     ProcessResetReactivate,
+    #[strum(serialize = "process-timer-support")]
+    ProcessTimerSupport,
+    #[strum(serialize = "render-offline-basic")]
+    RenderOfflineBasic,
+    #[strum(serialize = "render-offline-deterministic")]
+    RenderOfflineDeterministic,
+    #[strum(serialize = "track-info-changed")]
+    TrackInfoChanged,
+    #[strum(serialize = "project-location-changed")]
+    ProjectLocationChanged,
     #[strum(serialize = "param-conversions")]
     ParamConversions,
     #[strum(serialize = "param-fuzz-basic")]
@@ -84,22 +128,46 @@ pub enum PluginTestCase {
     ParamSetWrongNamespace,
     #[strum(serialize = "param-default-values")]
     ParamDefaultValues,
+    #[strum(serialize = "param-indication-fuzz")]
+    ParamIndicationFuzz,
+    #[strum(serialize = "remote-controls-pages")]
+    RemoteControlsPages,
+    #[strum(serialize = "resource-directory-collect")]
+    ResourceDirectoryCollect,
     #[strum(serialize = "state-invalid-empty")]
     StateInvalidEmpty,
     #[strum(serialize = "state-invalid-random")]
     StateInvalidRandom,
+    #[strum(serialize = "state-context-reproducibility-preset")]
+    StateContextReproducibilityPreset,
+    #[strum(serialize = "state-context-reproducibility-duplicate")]
+    StateContextReproducibilityDuplicate,
+    #[strum(serialize = "state-context-reproducibility-project")]
+    StateContextReproducibilityProject,
+    #[strum(serialize = "state-context-cross-load")]
+    StateContextCrossLoad,
     #[strum(serialize = "state-reproducibility-basic")]
     StateReproducibilityBasic,
     #[strum(serialize = "state-reproducibility-binary")]
     StateReproducibilityBinary,
     #[strum(serialize = "state-reproducibility-buffered")]
     StateReproducibilityBuffered,
+    #[strum(serialize = "tail-accuracy")]
+    TailAccuracy,
     #[strum(serialize = "transport-null")]
     TransportNull,
     #[strum(serialize = "transport-fuzz")]
     TransportFuzz,
     #[strum(serialize = "transport-fuzz-sample-accurate")]
     TransportFuzzSampleAccurate,
+    #[strum(serialize = "transport-control-requests")]
+    TransportControlRequests,
+    #[strum(serialize = "triggers-fuzz")]
+    TriggersFuzz,
+    #[strum(serialize = "undo-delta-roundtrip")]
+    UndoDeltaRoundtrip,
+    #[strum(serialize = "voice-info-consistency")]
+    VoiceInfoConsistency,
 }
 
 impl<'a> TestCase<'a> for PluginTestCase {
@@ -122,6 +190,63 @@ impl<'a> TestCase<'a> for PluginTestCase {
             PluginTestCase::FeaturesStandard => String::from(
                 "Check that every unnamespaced feature that the plugin declares is part of the CLAP standard features.",
             ),
+            PluginTestCase::ContextMenuPopulate => String::from(
+                "Asks the plugin to populate its context menus for the global target and for every parameter through \
+                 the 'context-menu' extension. Asserts that all labels are non-empty and that submenus are properly \
+                 nested, and then performs every action in the menus.",
+            ),
+            PluginTestCase::EventsForeignSpace => String::from(
+                "Registers a couple of event spaces with the host's 'event-registry' extension, and then sends events \
+                 in non-core event spaces the plugin never queried alongside regular note events. Asserts that the \
+                 plugin ignores these events without producing invalid output. Outputting events in event spaces the \
+                 plugin never obtained from the host results in a warning.",
+            ),
+            PluginTestCase::GuiLifecycle => format!(
+                "Creates the plugin's GUI using the X11 API {} times, embedding it in a parent window or suggesting a \
+                 title for a floating window, showing it for {} milliseconds while handling its callbacks, hiding it, \
+                 and then destroying it again. This test is skipped if no X11 display is available.",
+                gui::GUI_NUM_CYCLES,
+                gui::GUI_SHOW_DURATION.as_millis()
+            ),
+            PluginTestCase::GuiSizeNegotiation => format!(
+                "Sets the embedded GUI's scale to {:?} and asserts that it still reports a valid size. If the GUI \
+                 can be resized, then a range of sizes is adjusted using 'clap_plugin_gui::adjust_size()'. The \
+                 adjusted sizes must be valid, adjusting them again may not change them, they must respect the \
+                 GUI's resize hints, and after resizing the GUI to them 'clap_plugin_gui::get_size()' must return the \
+                 same size. This test is skipped if no X11 display is available.",
+                gui::GUI_SCALES
+            ),
+            PluginTestCase::GuiCreateDestroyProcessing => format!(
+                "Processes {} blocks of audio and notes on the audio thread while the plugin's GUI is repeatedly \
+                 created, shown, hidden, and destroyed on the main thread. This test is skipped if no X11 display is \
+                 available.",
+                gui::GUI_PROCESSING_NUM_BLOCKS
+            ),
+            PluginTestCase::LatencyAccuracy => format!(
+                "Sends impulses with amplitudes of {:?} to the plugin's main input after activating it, and locates \
+                 the response on the plugin's main output by cross-correlating the output with the input up to {} \
+                 samples past the latency reported through the 'latency' extension. The measured delay must match \
+                 the reported latency. If the output is not a delayed copy of the input, for instance because the \
+                 plugin is a synthesizer or a nonlinear effect, then the latency cannot be verified and the test \
+                 results in a warning.",
+                latency::LATENCY_IMPULSE_AMPLITUDES,
+                latency::LATENCY_SEARCH_MARGIN
+            ),
+            PluginTestCase::MiniCurveDisplayRender => format!(
+                "Renders the plugin's curves through the 'mini-curve-display' extension with {:?} values per curve. \
+                 Every size is rendered twice with different buffer contents to assert that every value in the \
+                 buffers is written. The curve kinds, which determine the hints used to draw the curves, must be \
+                 valid and may not change between renders or sizes, and static curves may not change between \
+                 renders. The axis names must be valid UTF-8 strings. The hints for every curve kind must be \
+                 consistent, and a warning is emitted if the plugin renders a curve without having requested the \
+                 hints for its kind from the host.",
+                mini_curve_display::MINI_CURVE_DISPLAY_SIZES
+            ),
+            PluginTestCase::NoteNamesConsistency => String::from(
+                "Queries the plugin's note names through the 'note-name' extension and checks that they are valid \
+                 non-empty UTF-8 strings, that their port, key, and channel values are either in range or -1, and \
+                 that the list stays the same between calls unless the plugin signals that it has changed.",
+            ),
             PluginTestCase::ProcessAudioBasicOutOfPlace => String::from(
                 "Processes random audio through the plugin with its default parameter values and tests whether the \
                  output does not contain any non-finite or subnormal values. Uses out-of-place audio processing.",
@@ -155,6 +280,13 @@ impl<'a> TestCase<'a> for PluginTestCase {
                  'configurable-audio-ports' extension.",
                 PluginTestCase::ProcessAudioBasicOutOfPlace,
             ),
+            PluginTestCase::LayoutExtensibleAudioPorts => format!(
+                "Adds random mono, stereo, untyped, surround, and ambisonic audio ports through the \
+                 'extensible-audio-ports' extension {} times, asserts that the new ports are reported correctly by \
+                 the 'audio-ports' extension, processes audio through the enlarged layout, and then removes the \
+                 added ports again.",
+                layout::EXTENSIBLE_AUDIO_PORTS_NUM_ATTEMPTS,
+            ),
             PluginTestCase::LayoutAudioPortsConfig => format!(
                 "Same as '{}', but this time it tries all available port configurations exposed via the \
                  'audio-ports-config' extension.",
@@ -178,6 +310,22 @@ impl<'a> TestCase<'a> for PluginTestCase {
                 "Sends intentionally inconsistent and mismatching note and MIDI events to the plugin with its default \
                  parameter values and tests the output for consistency. Uses out-of-place audio processing.",
             ),
+            PluginTestCase::ProcessNoteTuning => format!(
+                "Sends audio and random note events to the plugin while the host's 'tuning' extension provides \
+                 synthetic tunings, including equal temperaments, random per-key offsets, and extreme offsets of up \
+                 to 48 semitones. The tuning pool is replaced every {} blocks, after which the plugin is notified \
+                 through 'clap_plugin_tuning::changed()'. If the plugin queried the tuning event space, tuning \
+                 events are sent as well. Asserts that the output is finite and that \
+                 'clap_host_tuning::get_relative()' is only called from the audio thread. A warning is emitted if the \
+                 plugin never calls 'clap_host_tuning::get_relative()'.",
+                processing::TUNING_CHANGE_INTERVAL
+            ),
+            PluginTestCase::ProcessGainAdjustmentMetering => format!(
+                "Processes {} blocks of random audio and note events, some of which are silent, and queries the \
+                 plugin's gain adjustment through the 'gain-adjustment-metering' extension after every block. \
+                 Asserts that the reported gain adjustment is always a finite value.",
+                processing::GAIN_ADJUSTMENT_METERING_NUM_BLOCKS
+            ),
             PluginTestCase::ProcessVaryingSampleRates => String::from(
                 "Processes random audio and random note events through the plugin with its default parameter values \
                  while trying different sample rates ranging from 1kHz to 768kHz, including fractional rates, and \
@@ -199,6 +347,37 @@ impl<'a> TestCase<'a> for PluginTestCase {
                 "Asserts that resetting the plugin via 'clap_plugin::reset()' and via re-activation does not cause \
                  any crashes, and that the plugin still produces valid (non-NaN and non-infinite) output",
             ),
+            PluginTestCase::ProcessTimerSupport => format!(
+                "Processes random audio and random note events in real time for {} milliseconds while firing the \
+                 timers the plugin registered through the host's 'timer-support' extension. Timers may only be \
+                 registered and unregistered from the main thread, unknown timer IDs may not be unregistered, and \
+                 all timers need to be unregistered by the time the plugin is destroyed.",
+                processing::TIMER_PROCESSING_DURATION.as_millis()
+            ),
+            PluginTestCase::RenderOfflineBasic => String::from(
+                "Switches the plugin to offline rendering through the 'render' extension before activating it, and \
+                 then processes random audio and note events while checking that the output is valid. Plugins with a \
+                 hard realtime requirement should refuse to switch to offline rendering instead.",
+            ),
+            PluginTestCase::RenderOfflineDeterministic => format!(
+                "Same as '{}', but renders the same input with two separate plugin instances and asserts that their \
+                 outputs are identical.",
+                PluginTestCase::RenderOfflineBasic,
+            ),
+            PluginTestCase::TrackInfoChanged => String::from(
+                "Processes random audio and note events while randomizing the track information exposed through the \
+                 host's 'track-info' extension and calling 'clap_plugin_track_info::changed()' between process calls. \
+                 The track information includes unusual flag combinations, missing names, and audio port types that \
+                 do not match the plugin's ports. The plugin's output should remain valid.",
+            ),
+            PluginTestCase::ProjectLocationChanged => format!(
+                "Processes {} blocks of random audio and note events while passing a new randomized project location \
+                 to the draft 'location' extension between process calls. The locations include deeply nested \
+                 paths, missing IDs and names, and unusual colors. The plugin's output should remain valid, its \
+                 parameter values should not change, and it should not allocate memory from within its process \
+                 calls. Allocations are only tracked on Linux with glibc.",
+                processing::PROJECT_LOCATION_NUM_BLOCKS,
+            ),
             PluginTestCase::ParamConversions => String::from(
                 "Asserts that value to string and string to value conversions are supported for ether all or none of \
                  the plugin's parameters, and that conversions between values and strings roundtrip consistently.",
@@ -242,6 +421,25 @@ impl<'a> TestCase<'a> for PluginTestCase {
                 "Asserts that the values for all parameters are set correctly to their default values when the plugin \
                  is initialized.",
             ),
+            PluginTestCase::ParamIndicationFuzz => format!(
+                "Sends random mapping and automation indications for every parameter and for unknown parameter IDs \
+                 through the 'param-indication' extension from the main thread while the plugin is processing audio \
+                 {} times in a row. Colors, labels, and descriptions are sometimes omitted. Asserts that this does \
+                 not change any parameter values.",
+                params::PARAM_INDICATION_FUZZ_RUNS,
+            ),
+            PluginTestCase::RemoteControlsPages => format!(
+                "Queries the plugin's remote controls pages and asserts that all page IDs are unique, that all pages \
+                 and section names are valid, that pages contain at most {} controls, and that all mapped \
+                 parameter IDs refer to existing parameters.",
+                clap_sys::ext::remote_controls::CLAP_REMOTE_CONTROLS_COUNT,
+            ),
+            PluginTestCase::ResourceDirectoryCollect => String::from(
+                "Gives the plugin a private resource directory through the 'resource-directory' extension, randomizes \
+                 its parameters, and asks it to collect its resources. Asserts that all reported files exist and are \
+                 located inside of the resource directory, and that the saved state restores the same parameter \
+                 values in a new instance that uses the same resource directory.",
+            ),
             PluginTestCase::StateInvalidEmpty => String::from(
                 "The plugin should return false when 'clap_plugin_state::load()' is called with an empty state.",
             ),
@@ -267,6 +465,44 @@ impl<'a> TestCase<'a> for PluginTestCase {
                  memory or random bytes.",
                 PluginTestCase::StateReproducibilityBasic
             ),
+            PluginTestCase::StateContextReproducibilityPreset => format!(
+                "Performs the same parameter reproducibility check as in '{}', but this time the state is saved and \
+                 loaded using the 'state-context' extension with 'CLAP_STATE_CONTEXT_FOR_PRESET'. Like in '{}', the \
+                 state saved after reloading must be exactly the same byte for byte.",
+                PluginTestCase::StateReproducibilityBasic,
+                PluginTestCase::StateReproducibilityBinary
+            ),
+            PluginTestCase::StateContextReproducibilityDuplicate => format!(
+                "Performs the same parameter reproducibility check as in '{}', but this time the state is saved and \
+                 loaded using the 'state-context' extension with 'CLAP_STATE_CONTEXT_FOR_DUPLICATE'. Like in '{}', the \
+                 state saved after reloading must be exactly the same byte for byte.",
+                PluginTestCase::StateReproducibilityBasic,
+                PluginTestCase::StateReproducibilityBinary
+            ),
+            PluginTestCase::StateContextReproducibilityProject => format!(
+                "Performs the same parameter reproducibility check as in '{}', but this time the state is saved and \
+                 loaded using the 'state-context' extension with 'CLAP_STATE_CONTEXT_FOR_PROJECT'. Like in '{}', the \
+                 state saved after reloading must be exactly the same byte for byte.",
+                PluginTestCase::StateReproducibilityBasic,
+                PluginTestCase::StateReproducibilityBinary
+            ),
+            PluginTestCase::StateContextCrossLoad => format!(
+                "Performs the same check as in '{}', but the state is saved with one context type and loaded with \
+                 every other context type. Mismatching parameter values result in a warning, since the plugin may \
+                 store different information for different contexts.",
+                PluginTestCase::StateContextReproducibilityPreset
+            ),
+            PluginTestCase::TailAccuracy => format!(
+                "Processes {} blocks of random audio, and then switches to silent input while measuring how long it \
+                 takes for the plugin's output to decay below -80 dBFS. The output must decay within the tail \
+                 reported through the 'tail' extension plus the plugin's latency and a margin of {} samples. Plugins \
+                 whose output does not decay must report an infinite tail. Only plugins with audio inputs are \
+                 tested, since an instrument's release cannot be told apart from its tail. Calls to \
+                 'clap_host_tail::changed()' should be followed by a different tail, and a warning is emitted if the \
+                 tail did not change.",
+                tail::TAIL_NUM_ACTIVE_BLOCKS,
+                tail::TAIL_MARGIN
+            ),
             PluginTestCase::TransportNull => String::from(
                 "Performs audio processing with a 'null' transport pointer, simulating a free-running transport \
                  state. The plugin passes the test if it doesn't produce any infinite or NaN values, and doesn't \
@@ -282,6 +518,40 @@ impl<'a> TestCase<'a> for PluginTestCase {
                  the test if it doesn't produce any infinite or NaN values, and doesn't crash.",
                 PluginTestCase::TransportFuzz
             ),
+            PluginTestCase::TransportControlRequests => format!(
+                "Processes audio in real time for {} milliseconds while the plugin may make requests through the \
+                 host's draft 'transport-control' extension. Every requested play, pause, record, jump, or loop \
+                 state must be reflected in the 'clap_event_transport' passed to the next process call. The test is \
+                 skipped if the plugin does not make any transport requests.",
+                transport::TRANSPORT_CONTROL_DURATION.as_millis()
+            ),
+            PluginTestCase::TriggersFuzz => String::from(
+                "Processes random audio and note events while sending random sample-accurate trigger events from \
+                 the draft 'triggers' extension, some of which target trigger IDs that do not exist. The plugin's \
+                 triggers are validated first, and they are rescanned between activations when the plugin asks for \
+                 that. The plugin passes the test if it doesn't produce any infinite or NaN values, and doesn't \
+                 crash.",
+            ),
+            PluginTestCase::UndoDeltaRoundtrip => format!(
+                "Randomizes the plugin's parameters {} times while recording the changes the plugin reports through \
+                 the host's 'undo' extension. Every recorded step is then undone and redone again using the deltas \
+                 and the 'undo-delta' extension, checking that the parameter values match the values from before \
+                 and after that step. If the plugin subscribes to undo context updates, it is told about the host's \
+                 undo stack through the 'undo-context' extension.",
+                undo::UNDO_NUM_STEPS
+            ),
+            PluginTestCase::VoiceInfoConsistency => format!(
+                "Queries the plugin's voice count and capacity through the 'voice-info' extension after activating \
+                 it, and then plays {} more notes than the voice capacity, up to {} notes. If the plugin supports \
+                 overlapping notes, then {} additional notes share their key and channel with notes that are still \
+                 playing. Overlapping notes are never sent to plugins that do not support them. If the plugin sends \
+                 'CLAP_EVENT_NOTE_END' events, then every note must eventually end after being released, and the \
+                 plugin may not end notes that were never started or that already ended. The voice count may never \
+                 exceed the voice capacity.",
+                notes::VOICE_INFO_EXTRA_NOTES,
+                notes::VOICE_INFO_MAX_NOTES,
+                notes::VOICE_INFO_NUM_OVERLAPPING_NOTES
+            ),
         }
     }
 
@@ -310,6 +580,19 @@ impl<'a> TestCase<'a> for PluginTestCase {
             PluginTestCase::LayoutConfigurableAudioPorts => {
                 layout::test_layout_configurable_audio_ports(library, plugin_id)
             }
+            PluginTestCase::LayoutExtensibleAudioPorts => {
+                layout::test_layout_extensible_audio_ports(library, plugin_id)
+            }
+            PluginTestCase::ContextMenuPopulate => context_menu::test_context_menu_populate(library, plugin_id),
+            PluginTestCase::EventsForeignSpace => events::test_events_foreign_space(library, plugin_id),
+            PluginTestCase::GuiLifecycle => gui::test_gui_lifecycle(library, plugin_id),
+            PluginTestCase::GuiSizeNegotiation => gui::test_gui_size_negotiation(library, plugin_id),
+            PluginTestCase::GuiCreateDestroyProcessing => gui::test_gui_create_destroy_processing(library, plugin_id),
+            PluginTestCase::LatencyAccuracy => latency::test_latency_accuracy(library, plugin_id),
+            PluginTestCase::MiniCurveDisplayRender => {
+                mini_curve_display::test_mini_curve_display_render(library, plugin_id)
+            }
+            PluginTestCase::NoteNamesConsistency => notes::test_note_names_consistency(library, plugin_id),
             PluginTestCase::ProcessAudioBasicOutOfPlace => {
                 processing::test_process_audio_basic(library, plugin_id, false)
             }
@@ -333,6 +616,10 @@ impl<'a> TestCase<'a> for PluginTestCase {
             PluginTestCase::ProcessNoteInconsistent => {
                 processing::test_process_note_out_of_place(library, plugin_id, false)
             }
+            PluginTestCase::ProcessNoteTuning => processing::test_process_note_tuning(library, plugin_id),
+            PluginTestCase::ProcessGainAdjustmentMetering => {
+                processing::test_process_gain_adjustment_metering(library, plugin_id)
+            }
             PluginTestCase::ProcessVaryingSampleRates => {
                 processing::test_process_varying_sample_rates(library, plugin_id)
             }
@@ -341,6 +628,11 @@ impl<'a> TestCase<'a> for PluginTestCase {
             }
             PluginTestCase::ProcessRandomBlockSizes => processing::test_process_random_block_sizes(library, plugin_id),
             PluginTestCase::ProcessResetReactivate => processing::test_process_reset_reactivate(library, plugin_id),
+            PluginTestCase::ProcessTimerSupport => processing::test_process_timer_support(library, plugin_id),
+            PluginTestCase::RenderOfflineBasic => render::test_render_offline_basic(library, plugin_id),
+            PluginTestCase::RenderOfflineDeterministic => render::test_render_offline_deterministic(library, plugin_id),
+            PluginTestCase::TrackInfoChanged => processing::test_track_info_changed(library, plugin_id),
+            PluginTestCase::ProjectLocationChanged => processing::test_project_location_changed(library, plugin_id),
             PluginTestCase::ParamConversions => params::test_param_conversions(library, plugin_id),
             PluginTestCase::ParamSetEvents => params::test_param_set_events(library, plugin_id, false),
             PluginTestCase::ParamSetNoCookies => params::test_param_set_events(library, plugin_id, true),
@@ -350,6 +642,11 @@ impl<'a> TestCase<'a> for PluginTestCase {
             PluginTestCase::ParamFuzzSampleAccurate => params::test_param_fuzz_sample_accurate(library, plugin_id),
             PluginTestCase::ParamFuzzModulation => params::test_param_fuzz_modulation(library, plugin_id),
             PluginTestCase::ParamDefaultValues => params::test_param_default_values(library, plugin_id),
+            PluginTestCase::ParamIndicationFuzz => params::test_param_indication_fuzz(library, plugin_id),
+            PluginTestCase::RemoteControlsPages => params::test_remote_controls_pages(library, plugin_id),
+            PluginTestCase::ResourceDirectoryCollect => {
+                resource_directory::test_resource_directory_collect(library, plugin_id)
+            }
             PluginTestCase::StateInvalidEmpty => state::test_state_invalid_empty(library, plugin_id),
             PluginTestCase::StateInvalidRandom => state::test_state_invalid_random(library, plugin_id),
             PluginTestCase::StateReproducibilityBasic => {
@@ -361,12 +658,27 @@ impl<'a> TestCase<'a> for PluginTestCase {
             PluginTestCase::StateReproducibilityBinary => {
                 state::test_state_reproducibility(library, plugin_id, false, true)
             }
+            PluginTestCase::StateContextReproducibilityPreset => {
+                state::test_state_context_reproducibility(library, plugin_id, CLAP_STATE_CONTEXT_FOR_PRESET)
+            }
+            PluginTestCase::StateContextReproducibilityDuplicate => {
+                state::test_state_context_reproducibility(library, plugin_id, CLAP_STATE_CONTEXT_FOR_DUPLICATE)
+            }
+            PluginTestCase::StateContextReproducibilityProject => {
+                state::test_state_context_reproducibility(library, plugin_id, CLAP_STATE_CONTEXT_FOR_PROJECT)
+            }
+            PluginTestCase::StateContextCrossLoad => state::test_state_context_cross_load(library, plugin_id),
 
+            PluginTestCase::TailAccuracy => tail::test_tail_accuracy(library, plugin_id),
             PluginTestCase::TransportNull => transport::test_transport_null(library, plugin_id),
             PluginTestCase::TransportFuzz => transport::test_transport_fuzz(library, plugin_id),
             PluginTestCase::TransportFuzzSampleAccurate => {
                 transport::test_transport_fuzz_sample_accurate(library, plugin_id)
             }
+            PluginTestCase::TransportControlRequests => transport::test_transport_control_requests(library, plugin_id),
+            PluginTestCase::TriggersFuzz => triggers::test_triggers_fuzz(library, plugin_id),
+            PluginTestCase::UndoDeltaRoundtrip => undo::test_undo_delta_roundtrip(library, plugin_id),
+            PluginTestCase::VoiceInfoConsistency => notes::test_voice_info_consistency(library, plugin_id),
         }
     }
 }
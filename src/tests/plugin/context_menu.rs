@@ -0,0 +1,108 @@
+//! Tests for the `context-menu` extension.
+
+use crate::plugin::ext::context_menu::{ContextMenu, ContextMenuItem};
+use crate::plugin::ext::params::Params;
+use crate::plugin::library::PluginLibrary;
+use crate::tests::TestStatus;
+use anyhow::{Context, Result};
+use clap_sys::ext::context_menu::*;
+use std::collections::BTreeSet;
+
+/// The test for `PluginTestCase::ContextMenuPopulate`.
+pub fn test_context_menu_populate(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let context_menu = match plugin.get_extension::<ContextMenu>() {
+        Some(context_menu) => context_menu,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin does not implement the 'context-menu' extension.",
+                )),
+            });
+        }
+    };
+
+    let param_info = plugin
+        .get_extension::<Params>()
+        .map(|params| params.info())
+        .transpose()
+        .context("Failure while fetching the parameters")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    // The global menu is requested first, followed by a menu for every parameter
+    let targets = std::iter::once(clap_context_menu_target {
+        kind: CLAP_CONTEXT_MENU_TARGET_KIND_GLOBAL,
+        id: 0,
+    })
+    .chain(param_info.keys().map(|&param_id| clap_context_menu_target {
+        kind: CLAP_CONTEXT_MENU_TARGET_KIND_PARAM,
+        id: param_id,
+    }));
+
+    let mut num_populated_menus = 0;
+    for target in targets {
+        let target_name = match target.kind {
+            CLAP_CONTEXT_MENU_TARGET_KIND_PARAM => format!("parameter {}", target.id),
+            _ => String::from("the global target"),
+        };
+
+        let items = context_menu
+            .populate(&target)
+            .with_context(|| format!("Error while populating the context menu for {target_name}"))?;
+        let Some(items) = items else {
+            continue;
+        };
+
+        num_populated_menus += 1;
+        plugin.poll_callback(|_| Ok(()))?;
+
+        // Entries within the same menu should not share action IDs, as there would otherwise be no
+        // way to tell them apart in `perform()`
+        let mut action_ids = BTreeSet::new();
+        for item in &items {
+            if let Some(action_id) = item.action_id()
+                && !action_ids.insert(action_id)
+            {
+                anyhow::bail!(
+                    "The context menu for {target_name} contains multiple entries with action ID {action_id}."
+                );
+            }
+        }
+
+        for item in &items {
+            let Some(action_id) = item.action_id() else {
+                continue;
+            };
+
+            let handled = context_menu.perform(&target, action_id);
+            if !handled && item.is_enabled() {
+                anyhow::bail!(
+                    "'clap_plugin_context_menu::perform()' returned false for the enabled entry with action ID \
+                     {action_id} ('{}') in the context menu for {target_name}.",
+                    match item {
+                        ContextMenuItem::Entry { label, .. } | ContextMenuItem::CheckEntry { label, .. } => label,
+                        _ => unreachable!(),
+                    }
+                );
+            }
+
+            plugin.poll_callback(|_| Ok(()))?;
+        }
+    }
+
+    if num_populated_menus == 0 {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin implements the 'context-menu' extension, but it did not populate any context menus.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
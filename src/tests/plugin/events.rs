@@ -0,0 +1,108 @@
+//! Tests for events outside of the core event space and the `event-registry` extension.
+
+use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::note_ports::NotePorts;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffers, Event, ProcessScope};
+use crate::tests::TestStatus;
+use crate::tests::rng::{NoteGenerator, new_prng, random_foreign_events};
+use anyhow::{Context, Result};
+use rand::RngExt;
+use std::ffi::CStr;
+
+const BUFFER_SIZE: u32 = 512;
+
+/// The number of blocks processed in [`test_events_foreign_space()`].
+const FOREIGN_SPACE_NUM_BLOCKS: usize = 20;
+
+/// The event spaces registered with the host in [`test_events_foreign_space()`]. The plugin may
+/// query these, but it is very unlikely to know about them.
+const FOREIGN_EVENT_SPACES: &[&CStr] = &[c"com.example.foreign-events", c"org.clap-validator.unused-space"];
+
+/// The test for `PluginTestCase::EventsForeignSpace`.
+pub fn test_events_foreign_space(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+
+    // These need to be registered before initializing the plugin, as that is when most plugins
+    // query their event spaces
+    {
+        let mut event_registry = plugin.shared().event_registry.lock().unwrap();
+        for space_name in FOREIGN_EVENT_SPACES {
+            event_registry.register(space_name);
+        }
+    }
+
+    plugin.init().context("Error during initialization")?;
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    // The foreign events are sent in both the registered spaces and in spaces the host doesn't
+    // know about, but never in spaces the plugin has successfully queried
+    let foreign_space_ids: Vec<u16> = {
+        let event_registry = plugin.shared().event_registry.lock().unwrap();
+        event_registry
+            .space_ids()
+            .chain((0..8).map(|_| prng.random_range(1..u16::MAX)))
+            .filter(|&space_id| !event_registry.is_known_to_plugin(space_id))
+            .collect()
+    };
+
+    let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+    let unknown_output_event = plugin.on_audio_thread(|plugin| -> Result<Option<(usize, Event)>> {
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for block_idx in 0..FOREIGN_SPACE_NUM_BLOCKS {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.add_events(random_foreign_events(&mut prng, &foreign_space_ids, BUFFER_SIZE));
+            process.run()?;
+
+            // Output events in spaces the plugin never obtained from the host cannot be interpreted
+            // by the host, and are parsed as `Event::Unknown`
+            let event_registry = plugin.shared().event_registry.lock().unwrap();
+            if let Some(event) = process.read_events().into_iter().find(|event| {
+                matches!(event, Event::Unknown(_)) && !event_registry.is_known_to_plugin(event.header().space_id)
+            }) {
+                return Ok(Some((block_idx, event)));
+            }
+        }
+
+        Ok(None)
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if let Some((block_idx, event)) = unknown_output_event {
+        let header = event.header();
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "In block {block_idx}, the plugin output an event with type {} in event space {}, which it never \
+                 obtained through 'clap_host_event_registry::query()'. The host does not know which event space this \
+                 is and cannot interpret the event, so it will likely be dropped. This usually means the plugin \
+                 forwards the events it receives in unknown event spaces to its output instead of ignoring them.",
+                header.type_, header.space_id
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
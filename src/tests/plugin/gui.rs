@@ -0,0 +1,475 @@
+//! Tests for the `gui` extension. These need an X11 display, and they are skipped if there is
+//! none. See [`TestDisplay`].
+
+use crate::cli::Config;
+use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::gui::Gui;
+use crate::plugin::ext::note_ports::NotePorts;
+use crate::plugin::instance::{CallbackEvent, Plugin};
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffers, ProcessScope};
+use crate::tests::TestStatus;
+use crate::tests::display::{ParentWindow, TestDisplay};
+use crate::tests::rng::{NoteGenerator, new_prng};
+use anyhow::{Context, Result};
+use clap_sys::ext::gui::{CLAP_WINDOW_API_X11, clap_window};
+use std::ffi::CStr;
+use std::time::{Duration, Instant};
+
+const BUFFER_SIZE: u32 = 512;
+
+/// The number of times the GUI is created, shown, hidden, and destroyed in
+/// [`test_gui_lifecycle()`].
+pub const GUI_NUM_CYCLES: usize = 5;
+/// How long the GUI is shown for during every cycle in [`test_gui_lifecycle()`]. The plugin's
+/// callbacks and timers are handled in the meantime.
+pub const GUI_SHOW_DURATION: Duration = Duration::from_millis(250);
+/// The scales passed to `clap_plugin_gui::set_scale()` in [`test_gui_size_negotiation()`].
+pub const GUI_SCALES: &[f64] = &[1.0, 1.25, 1.5, 2.0, 3.0];
+/// The sizes passed to `clap_plugin_gui::adjust_size()` in [`test_gui_size_negotiation()`].
+/// These range from a single pixel to larger than most screens.
+const GUI_SIZE_REQUESTS: &[(u32, u32)] = &[(1, 1), (100, 100), (640, 480), (1000, 300), (300, 1000), (4000, 3000)];
+/// How long the plugin's callbacks are handled after every resize in
+/// [`test_gui_size_negotiation()`].
+const GUI_RESIZE_DURATION: Duration = Duration::from_millis(50);
+/// The number of blocks processed in [`test_gui_create_destroy_processing()`]. The GUI is created
+/// and destroyed on the main thread once for every block.
+pub const GUI_PROCESSING_NUM_BLOCKS: usize = 20;
+/// The size of the parent window used in [`test_gui_create_destroy_processing()`]. The GUI's size
+/// cannot be negotiated from the audio thread, so this is simply large enough for most GUIs.
+const GUI_PROCESSING_PARENT_SIZE: (u32, u32) = (1280, 960);
+/// The title suggested for floating windows.
+const GUI_TITLE: &CStr = c"clap-validator";
+
+/// The message used when skipping the GUI tests because there is no display.
+const NO_DISPLAY_MESSAGE: &str = "No X11 display is available. Either set the 'DISPLAY' environment variable, or \
+                                  configure a virtual X server under 'gui.xvfb' in 'clap-validator.toml'.";
+
+/// The test for `PluginTestCase::GuiLifecycle`.
+pub fn test_gui_lifecycle(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    // The display needs to outlive the plugin, since the plugin may still hold a connection to it
+    let Some(display) = TestDisplay::open(&Config::from_current()?.gui)? else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(NO_DISPLAY_MESSAGE)),
+        });
+    };
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let gui = match plugin.get_extension::<Gui>() {
+        Some(gui) => gui,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'gui' extension.")),
+            });
+        }
+    };
+
+    let Some(is_floating) = x11_gui_mode(&gui) else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin supports neither embedded nor floating GUIs using the 'x11' API.",
+            )),
+        });
+    };
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    for cycle in 0..GUI_NUM_CYCLES {
+        gui.create(CLAP_WINDOW_API_X11, is_floating)?;
+
+        // The parent window may only be destroyed after the GUI embedded in it
+        let mut parent = None;
+        let result = show_and_hide(&plugin, &gui, &display, is_floating, &mut parent);
+        if plugin.shared().gui.load().is_some() {
+            gui.destroy();
+        }
+        drop(parent);
+
+        result.with_context(|| format!("Error during GUI lifecycle {} of {GUI_NUM_CYCLES}", cycle + 1))?;
+        plugin.poll_callback(|_| Ok(()))?;
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::GuiSizeNegotiation`.
+pub fn test_gui_size_negotiation(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let Some(display) = TestDisplay::open(&Config::from_current()?.gui)? else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(NO_DISPLAY_MESSAGE)),
+        });
+    };
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let gui = match plugin.get_extension::<Gui>() {
+        Some(gui) => gui,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'gui' extension.")),
+            });
+        }
+    };
+
+    // Size negotiation only applies to embedded GUIs, the plugin manages floating windows itself
+    if !gui.is_api_supported(CLAP_WINDOW_API_X11, false) {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not support embedded GUIs using the 'x11' API.",
+            )),
+        });
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    gui.create(CLAP_WINDOW_API_X11, false)?;
+
+    let mut parent = None;
+    let result = negotiate_size(&plugin, &gui, &display, &mut parent);
+    if plugin.shared().gui.load().is_some() {
+        gui.destroy();
+    }
+    drop(parent);
+
+    let status = result?;
+    plugin.poll_callback(|_| Ok(()))?;
+
+    Ok(status)
+}
+
+/// The test for `PluginTestCase::GuiCreateDestroyProcessing`.
+pub fn test_gui_create_destroy_processing(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let Some(display) = TestDisplay::open(&Config::from_current()?.gui)? else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(NO_DISPLAY_MESSAGE)),
+        });
+    };
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let gui = match plugin.get_extension::<Gui>() {
+        Some(gui) => gui,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'gui' extension.")),
+            });
+        }
+    };
+
+    let Some(is_floating) = x11_gui_mode(&gui) else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin supports neither embedded nor floating GUIs using the 'x11' API.",
+            )),
+        });
+    };
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let parent = if is_floating {
+        None
+    } else {
+        Some(display.create_window(GUI_PROCESSING_PARENT_SIZE.0, GUI_PROCESSING_PARENT_SIZE.1)?)
+    };
+    let window = parent.as_ref().map(ParentWindow::clap_window);
+
+    // The GUI is created and destroyed on the main thread while the audio thread keeps processing,
+    // so any errors need to be sent back
+    let (error_sender, error_receiver) = std::sync::mpsc::channel();
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for _ in 0..GUI_PROCESSING_NUM_BLOCKS {
+            let error_sender = error_sender.clone();
+            plugin.send_main_thread(move |plugin| {
+                if let Err(err) = create_and_destroy(plugin, is_floating, window) {
+                    let _ = error_sender.send(err);
+                }
+            });
+
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+        }
+
+        // Tasks are handled in order, so this waits for the last GUI to be destroyed
+        plugin.on_main_thread(|_| ());
+
+        Ok(())
+    })?;
+
+    if let Ok(err) = error_receiver.try_recv() {
+        return Err(err.context("Error while creating and destroying the GUI during audio processing"));
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+    drop(parent);
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// Determine how the plugin's GUI should be created using the X11 API. Returns `Some(false)` for
+/// embedded GUIs, `Some(true)` for floating GUIs, and `None` if the plugin supports neither.
+/// Embedded GUIs are preferred since that's what most hosts use.
+fn x11_gui_mode(gui: &Gui) -> Option<bool> {
+    if gui.is_api_supported(CLAP_WINDOW_API_X11, false) {
+        Some(false)
+    } else if gui.is_api_supported(CLAP_WINDOW_API_X11, true) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Embed the created GUI in a new parent window, or suggest a title for a floating GUI, show it
+/// for [`GUI_SHOW_DURATION`] while handling its callbacks, and then hide it again. The parent window
+/// is stored in `parent` so the caller can destroy it after the GUI.
+fn show_and_hide<'a>(
+    plugin: &Plugin,
+    gui: &Gui,
+    display: &'a TestDisplay,
+    is_floating: bool,
+    parent: &mut Option<ParentWindow<'a>>,
+) -> Result<()> {
+    // Floating windows are scaled by the plugin itself, so this may be rejected
+    gui.set_scale(1.0);
+
+    if is_floating {
+        gui.suggest_title(GUI_TITLE);
+    } else {
+        let (width, height) = gui.get_size()?;
+        anyhow::ensure!(
+            width > 0 && height > 0,
+            "'clap_plugin_gui::get_size()' returned a size of {width}x{height} pixels."
+        );
+
+        let window = parent.insert(display.create_window(width, height)?);
+        gui.set_parent(&window.clap_window())?;
+    }
+
+    gui.show()?;
+    handle_gui_callbacks(plugin, gui, parent.as_ref(), GUI_SHOW_DURATION)?;
+
+    // The plugin may have destroyed its GUI after the floating window was closed
+    if plugin.shared().gui.load().is_some() {
+        gui.hide()?;
+    }
+
+    Ok(())
+}
+
+/// Check the scales and sizes the created embedded GUI accepts, and resize it to every size it
+/// adjusts the requests in [`GUI_SIZE_REQUESTS`] to. The parent window is stored in `parent` so
+/// the caller can destroy it after the GUI.
+fn negotiate_size<'a>(
+    plugin: &Plugin,
+    gui: &Gui,
+    display: &'a TestDisplay,
+    parent: &mut Option<ParentWindow<'a>>,
+) -> Result<TestStatus> {
+    // The scale should only affect the GUI's size, which should stay valid
+    for &scale in GUI_SCALES {
+        if gui.set_scale(scale) {
+            let (width, height) = gui.get_size()?;
+            if width == 0 || height == 0 {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "'clap_plugin_gui::get_size()' returned a size of {width}x{height} pixels after setting the \
+                         GUI's scale to {scale}."
+                    )),
+                });
+            }
+        }
+    }
+    gui.set_scale(1.0);
+
+    let (mut width, mut height) = gui.get_size()?;
+    if width == 0 || height == 0 {
+        return Ok(TestStatus::Failed {
+            details: Some(format!(
+                "'clap_plugin_gui::get_size()' returned a size of {width}x{height} pixels."
+            )),
+        });
+    }
+
+    let window = parent.insert(display.create_window(width, height)?);
+    gui.set_parent(&window.clap_window())?;
+    gui.show()?;
+    handle_gui_callbacks(plugin, gui, Some(window), GUI_RESIZE_DURATION)?;
+
+    if !gui.can_resize() {
+        gui.hide()?;
+        return Ok(TestStatus::Success {
+            details: Some(String::from(
+                "The plugin's GUI cannot be resized, so only its scaling was tested.",
+            )),
+        });
+    }
+
+    let hints = gui.get_resize_hints();
+    if let Some(hints) = hints
+        && !hints.can_resize_horizontally
+        && !hints.can_resize_vertically
+    {
+        return Ok(TestStatus::Failed {
+            details: Some(String::from(
+                "'clap_plugin_gui::can_resize()' returned true, but the GUI's resize hints state that it can be \
+                 resized neither horizontally nor vertically.",
+            )),
+        });
+    }
+
+    let mut warnings = Vec::new();
+    for &(requested_width, requested_height) in GUI_SIZE_REQUESTS {
+        let Some((adjusted_width, adjusted_height)) = gui.adjust_size(requested_width, requested_height) else {
+            warnings.push(format!(
+                "'clap_plugin_gui::adjust_size()' returned false for {requested_width}x{requested_height} pixels, \
+                 even though the GUI can be resized."
+            ));
+            continue;
+        };
+
+        if adjusted_width == 0 || adjusted_height == 0 {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "'clap_plugin_gui::adjust_size()' adjusted {requested_width}x{requested_height} pixels to \
+                     {adjusted_width}x{adjusted_height} pixels."
+                )),
+            });
+        }
+
+        // An adjusted size is a size the GUI can be resized to, so adjusting it again should not
+        // change it
+        if let Some((readjusted_width, readjusted_height)) = gui.adjust_size(adjusted_width, adjusted_height)
+            && (readjusted_width, readjusted_height) != (adjusted_width, adjusted_height)
+        {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "'clap_plugin_gui::adjust_size()' adjusted {requested_width}x{requested_height} pixels to \
+                     {adjusted_width}x{adjusted_height} pixels, but it then adjusted that size to \
+                     {readjusted_width}x{readjusted_height} pixels."
+                )),
+            });
+        }
+
+        if let Some(hints) = hints
+            && ((!hints.can_resize_horizontally && adjusted_width != width)
+                || (!hints.can_resize_vertically && adjusted_height != height))
+        {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "'clap_plugin_gui::adjust_size()' adjusted {requested_width}x{requested_height} pixels to \
+                     {adjusted_width}x{adjusted_height} pixels starting from {width}x{height} pixels, which \
+                     contradicts the GUI's resize hints."
+                )),
+            });
+        }
+
+        window.resize(adjusted_width, adjusted_height);
+        gui.set_size(adjusted_width, adjusted_height)?;
+        handle_gui_callbacks(plugin, gui, Some(window), GUI_RESIZE_DURATION)?;
+
+        (width, height) = gui.get_size()?;
+        if (width, height) != (adjusted_width, adjusted_height) {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "After resizing the GUI to {adjusted_width}x{adjusted_height} pixels, \
+                     'clap_plugin_gui::get_size()' returned {width}x{height} pixels."
+                )),
+            });
+        }
+    }
+
+    gui.hide()?;
+
+    if warnings.is_empty() {
+        Ok(TestStatus::Success { details: None })
+    } else {
+        Ok(TestStatus::Warning {
+            details: Some(warnings.join("\n")),
+        })
+    }
+}
+
+/// Create the GUI, embed it in `window` or suggest a title for a floating GUI, show it, hide it,
+/// and then destroy it again. Used on the main thread while the audio thread is processing.
+fn create_and_destroy(plugin: &Plugin, is_floating: bool, window: Option<clap_window>) -> Result<()> {
+    let gui = plugin.get_extension::<Gui>().expect("already checked");
+    gui.create(CLAP_WINDOW_API_X11, is_floating)?;
+
+    let result = match &window {
+        Some(window) => gui.set_parent(window),
+        None => {
+            gui.suggest_title(GUI_TITLE);
+            Ok(())
+        }
+    }
+    .and_then(|()| gui.show())
+    .and_then(|()| gui.hide());
+
+    gui.destroy();
+    result
+}
+
+/// Handle the plugin's callbacks and timers for `duration`. Show and hide requests are granted,
+/// resize requests resize the parent window, and if the plugin destroyed its floating GUI after it
+/// was closed, then the GUI is destroyed on the host's side as well.
+fn handle_gui_callbacks(plugin: &Plugin, gui: &Gui, parent: Option<&ParentWindow>, duration: Duration) -> Result<()> {
+    let start = Instant::now();
+    loop {
+        plugin.poll_callback(|event| {
+            // The GUI may have been destroyed in between the request and handling it
+            if plugin.shared().gui.load().is_none() {
+                return Ok(());
+            }
+
+            match event {
+                CallbackEvent::GuiRequestResize(width, height) => {
+                    if let Some(parent) = parent {
+                        parent.resize(width, height);
+                    }
+                }
+                CallbackEvent::GuiRequestShow => gui.show()?,
+                CallbackEvent::GuiRequestHide => gui.hide()?,
+                CallbackEvent::GuiClosed(true) => gui.destroy(),
+                _ => (),
+            }
+
+            Ok(())
+        })?;
+
+        if start.elapsed() >= duration {
+            return Ok(());
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
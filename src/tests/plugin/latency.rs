@@ -0,0 +1,268 @@
+//! Tests for the `latency` extension.
+
+use crate::plugin::ext::audio_ports::{AudioPort, AudioPorts};
+use crate::plugin::ext::latency::Latency;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffers, ConstantMask, ProcessScope};
+use crate::tests::TestStatus;
+use anyhow::{Context, Result};
+use either::Either;
+
+const BUFFER_SIZE: u32 = 512;
+
+/// The amplitudes of the impulses sent to the plugin's main input in [`test_latency_accuracy()`].
+/// The responses are normalized by these amplitudes, so if the plugin's output is a delayed copy of
+/// its input then the normalized responses are identical.
+pub const LATENCY_IMPULSE_AMPLITUDES: &[f64] = &[1.0, 0.5, 0.25, 0.125];
+/// The number of samples past the reported latency that are searched for the impulse response.
+pub const LATENCY_SEARCH_MARGIN: usize = 4096;
+/// The largest reported latency that is measured, in samples. This is ten seconds at the sample
+/// rate used for processing.
+const LATENCY_MAX_MEASURED: u32 = 441_000;
+/// The maximum RMS difference between two normalized responses, relative to the RMS of the first
+/// response, for the responses to still be considered identical.
+const LATENCY_RESPONSE_TOLERANCE: f64 = 1e-3;
+/// The minimum fraction of the response's energy that needs to be contained in its peak for the
+/// output to be considered a delayed copy of the input.
+const LATENCY_MIN_PEAK_ENERGY: f64 = 0.5;
+/// If the response's energy is below this value, then the plugin's output is considered silent.
+const LATENCY_SILENCE_ENERGY: f64 = 1e-10;
+
+/// The cross-correlation between the impulses and the plugin's main output, indexed by
+/// `[impulse_idx][channel_idx][lag]`.
+type ImpulseResponses = Vec<Vec<Vec<f64>>>;
+
+/// The test for `PluginTestCase::LatencyAccuracy`.
+pub fn test_latency_accuracy(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.get_extension::<Latency>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from("The plugin does not implement the 'latency' extension.")),
+        });
+    }
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let (Some(input_port), Some(output_port)) = (
+        main_port_index(&audio_ports_config.inputs),
+        main_port_index(&audio_ports_config.outputs),
+    ) else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin needs both an audio input and an audio output for its latency to be measured.",
+            )),
+        });
+    };
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let (latency, responses) = plugin.on_audio_thread(|plugin| -> Result<(u32, Option<ImpulseResponses>)> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        // The latency can only be queried once the plugin has been activated, so the first block is
+        // silent and only used to activate the plugin
+        process.audio_buffers().fill_silence();
+        process.run()?;
+
+        let latency = plugin.on_main_thread(|plugin| plugin.get_extension::<Latency>().expect("already checked").get());
+        if latency > LATENCY_MAX_MEASURED {
+            return Ok((latency, None));
+        }
+
+        // Every impulse gets its own window so the responses don't overlap
+        let response_len = latency as usize + LATENCY_SEARCH_MARGIN;
+        let num_samples = response_len * LATENCY_IMPULSE_AMPLITUDES.len();
+        let mut input = vec![0.0; num_samples];
+        for (impulse_idx, amplitude) in LATENCY_IMPULSE_AMPLITUDES.iter().enumerate() {
+            input[impulse_idx * response_len] = *amplitude;
+        }
+
+        let mut output: Vec<Vec<f64>> =
+            vec![Vec::with_capacity(num_samples); audio_ports_config.outputs[output_port].channel_count as usize];
+        for block_start in (0..num_samples).step_by(BUFFER_SIZE as usize) {
+            let block_end = (block_start + BUFFER_SIZE as usize).min(num_samples);
+
+            process.audio_buffers().fill_silence();
+            if input[block_start..block_end].iter().any(|sample| *sample != 0.0) {
+                for buffer in process.audio_buffers().iter_mut() {
+                    if buffer.port().input() != Some(input_port) {
+                        continue;
+                    }
+
+                    for channel in 0..buffer.channels() {
+                        match buffer.channel_mut(channel) {
+                            Either::Left(data) => {
+                                for (sample, input) in data.iter_mut().zip(&input[block_start..block_end]) {
+                                    *sample = *input as f32;
+                                }
+                            }
+                            Either::Right(data) => {
+                                data[..block_end - block_start].copy_from_slice(&input[block_start..block_end]);
+                            }
+                        }
+                    }
+                    buffer.set_input_constant_mask(ConstantMask::DYNAMIC);
+                }
+            }
+
+            process.run()?;
+
+            let buffer = process
+                .audio_buffers()
+                .iter()
+                .find(|buffer| buffer.port().output() == Some(output_port))
+                .expect("Missing output buffer for the main output port");
+            for (channel, output) in output.iter_mut().enumerate() {
+                match buffer.channel(channel as u32) {
+                    Either::Left(data) => {
+                        output.extend(data[..block_end - block_start].iter().map(|sample| *sample as f64))
+                    }
+                    Either::Right(data) => output.extend_from_slice(&data[..block_end - block_start]),
+                }
+            }
+        }
+
+        let responses = (0..LATENCY_IMPULSE_AMPLITUDES.len())
+            .map(|impulse_idx| {
+                let window = impulse_idx * response_len..(impulse_idx + 1) * response_len;
+                output
+                    .iter()
+                    .map(|output| cross_correlate(&input[window.clone()], &output[window.clone()]))
+                    .collect()
+            })
+            .collect();
+
+        Ok((latency, Some(responses)))
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let Some(responses) = responses else {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "The plugin reports a latency of {latency} samples, which is too large to be measured."
+            )),
+        });
+    };
+
+    // The cross-correlation with the impulse is the impulse response scaled by the impulse's energy
+    let responses: ImpulseResponses = responses
+        .into_iter()
+        .zip(LATENCY_IMPULSE_AMPLITUDES)
+        .map(|(response, amplitude)| {
+            response
+                .into_iter()
+                .map(|channel| channel.into_iter().map(|x| x / (amplitude * amplitude)).collect())
+                .collect()
+        })
+        .collect();
+
+    let reference = &responses[0];
+    let response_len = reference.first().map_or(0, Vec::len);
+    let energy_per_lag: Vec<f64> = (0..response_len)
+        .map(|lag| reference.iter().map(|channel| channel[lag] * channel[lag]).sum())
+        .collect();
+    let total_energy: f64 = energy_per_lag.iter().sum();
+    if total_energy < LATENCY_SILENCE_ENERGY {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "The plugin's main output did not respond to impulses sent to its main input, so its reported \
+                 latency of {latency} samples could not be verified."
+            )),
+        });
+    }
+
+    // A plugin that simply delays its input, possibly with some linear filtering, responds the same
+    // way to every impulse regardless of its amplitude
+    for (response, amplitude) in responses.iter().zip(LATENCY_IMPULSE_AMPLITUDES).skip(1) {
+        let difference_energy: f64 = response
+            .iter()
+            .zip(reference)
+            .flat_map(|(channel, reference_channel)| channel.iter().zip(reference_channel))
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum();
+        if difference_energy > total_energy * LATENCY_RESPONSE_TOLERANCE * LATENCY_RESPONSE_TOLERANCE {
+            return Ok(TestStatus::Warning {
+                details: Some(format!(
+                    "The plugin's response to an impulse with an amplitude of {amplitude} differs from its response \
+                     to an impulse with an amplitude of {}, so its output is not a delayed copy of its input. The \
+                     reported latency of {latency} samples could not be verified.",
+                    LATENCY_IMPULSE_AMPLITUDES[0]
+                )),
+            });
+        }
+    }
+
+    let (measured_latency, peak_energy) =
+        energy_per_lag
+            .iter()
+            .copied()
+            .enumerate()
+            .fold((0, 0.0), |(peak_lag, peak_energy), (lag, energy)| {
+                if energy > peak_energy {
+                    (lag, energy)
+                } else {
+                    (peak_lag, peak_energy)
+                }
+            });
+    if peak_energy < total_energy * LATENCY_MIN_PEAK_ENERGY {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "The plugin's impulse response is spread out over time with its peak at {measured_latency} samples, \
+                 so its output is not a delayed copy of its input. The reported latency of {latency} samples could \
+                 not be verified."
+            )),
+        });
+    }
+
+    if measured_latency != latency as usize {
+        return Ok(TestStatus::Failed {
+            details: Some(format!(
+                "The plugin reports a latency of {latency} samples, but its output is a copy of its input delayed by \
+                 {measured_latency} samples."
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// Get the index of the main port, or the first port if none of the ports is marked as main.
+/// Returns `None` if there are no ports.
+fn main_port_index(ports: &[AudioPort]) -> Option<usize> {
+    if ports.is_empty() {
+        None
+    } else {
+        Some(ports.iter().position(|port| port.is_main).unwrap_or(0))
+    }
+}
+
+/// Compute the cross-correlation between `input` and `output` for every lag from zero up to the
+/// length of `output`. Only the nonzero input samples contribute, so this is cheap for sparse
+/// inputs like impulses.
+fn cross_correlate(input: &[f64], output: &[f64]) -> Vec<f64> {
+    let mut correlation = vec![0.0; output.len()];
+    for (input_idx, input_sample) in input.iter().enumerate() {
+        if *input_sample == 0.0 {
+            continue;
+        }
+
+        for (lag, correlation) in correlation.iter_mut().enumerate() {
+            if let Some(output_sample) = output.get(input_idx + lag) {
+                *correlation += input_sample * output_sample;
+            }
+        }
+    }
+
+    correlation
+}
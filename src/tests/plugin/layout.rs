@@ -1,9 +1,12 @@
 use crate::cli::tracing::{Span, from_fn, record};
-use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::ambisonic::Ambisonic;
+use crate::plugin::ext::audio_ports::{AudioPort, AudioPorts};
 use crate::plugin::ext::audio_ports_activation::AudioPortsActivation;
 use crate::plugin::ext::audio_ports_config::{AudioPortsConfig, AudioPortsConfigInfo};
 use crate::plugin::ext::configurable_audio_ports::{AudioPortsRequest, AudioPortsRequestInfo, ConfigurableAudioPorts};
+use crate::plugin::ext::extensible_audio_ports::ExtensibleAudioPorts;
 use crate::plugin::ext::note_ports::{NotePortConfig, NotePorts};
+use crate::plugin::ext::surround::Surround;
 use crate::plugin::instance::CallbackEvent;
 use crate::plugin::library::PluginLibrary;
 use crate::plugin::process::{AudioBuffers, ProcessRun, ProcessScope};
@@ -16,6 +19,10 @@ use rand::RngExt;
 
 const BUFFER_SIZE: u32 = 512;
 
+/// The number of times [`test_layout_extensible_audio_ports()`] adds random ports to the plugin and
+/// then removes them again.
+pub const EXTENSIBLE_AUDIO_PORTS_NUM_ATTEMPTS: usize = 20;
+
 /// The test for `PluginTestCase::LayoutAudioPortsConfig`.
 pub fn test_layout_audio_ports_config(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
     let mut prng = new_prng();
@@ -277,8 +284,8 @@ pub fn test_layout_configurable_audio_ports(library: &PluginLibrary, plugin_id:
         }
     };
 
-    let ambisonic = plugin.get_extension::<crate::plugin::ext::ambisonic::Ambisonic>();
-    let surround = plugin.get_extension::<crate::plugin::ext::surround::Surround>();
+    let ambisonic = plugin.get_extension::<Ambisonic>();
+    let surround = plugin.get_extension::<Surround>();
 
     let note_ports_config = plugin
         .get_extension::<NotePorts>()
@@ -345,61 +352,7 @@ pub fn test_layout_configurable_audio_ports(library: &PluginLibrary, plugin_id:
                 None => continue, // we assume that the plugin being overly defensive and accepts configurations with out-of-range port indices, but then ignores the invalid requests instead of rejecting the whole configuration
             };
 
-            if port.channel_count != request.request_info.channel_count() {
-                anyhow::bail!(
-                    "Wrong number of channels set for {} port (index {}) in response to the layout request: \n{}\n \
-                     Expected: {}, got: {}",
-                    if request.is_input { "input" } else { "output" },
-                    request.port_index,
-                    print_layout(&requests),
-                    request.request_info.channel_count(),
-                    port.channel_count,
-                );
-            }
-
-            match request.request_info {
-                AudioPortsRequestInfo::Ambisonic { config, .. } => {
-                    if port.port_type.as_deref() == Some(CLAP_PORT_AMBISONIC) {
-                        let result = ambisonic
-                            .as_ref()
-                            .expect("already checked")
-                            .get_config(request.is_input, request.port_index);
-
-                        if result
-                            .is_none_or(|x| x.normalization != config.normalization && x.ordering != config.ordering)
-                        {
-                            anyhow::bail!(
-                                "Wrong ambisonic config set for {} port (index {}) in response to the layout request: \
-                                 \n{}",
-                                if request.is_input { "input" } else { "output" },
-                                request.port_index,
-                                print_layout(&requests),
-                            );
-                        }
-                    }
-                }
-
-                AudioPortsRequestInfo::Surround { channel_map } => {
-                    if port.port_type.as_deref() == Some(CLAP_PORT_SURROUND) {
-                        let result_map = surround.as_ref().expect("already checked").get_channel_map(
-                            request.is_input,
-                            request.port_index,
-                            channel_map.len() as u32,
-                        );
-
-                        if channel_map != result_map {
-                            anyhow::bail!(
-                                "Wrong surround map set for {} port (index {}) in response to the layout request: \n{}",
-                                if request.is_input { "input" } else { "output" },
-                                request.port_index,
-                                print_layout(&requests),
-                            );
-                        }
-                    }
-                }
-
-                _ => {}
-            }
+            check_port_matches_request(request, port, ambisonic.as_ref(), surround.as_ref(), &requests)?;
         }
 
         plugin
@@ -438,6 +391,206 @@ pub fn test_layout_configurable_audio_ports(library: &PluginLibrary, plugin_id:
     Ok(TestStatus::Success { details: None })
 }
 
+/// The test for `PluginTestCase::LayoutExtensibleAudioPorts`.
+pub fn test_layout_extensible_audio_ports(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let audio_ports = match plugin.get_extension::<AudioPorts>() {
+        Some(audio_ports) => audio_ports,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin does not implement the 'audio-ports' extension.",
+                )),
+            });
+        }
+    };
+
+    let extensible_audio_ports = match plugin.get_extension::<ExtensibleAudioPorts>() {
+        Some(extension) => extension,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin does not implement the 'extensible-audio-ports' extension.",
+                )),
+            });
+        }
+    };
+
+    let ambisonic = plugin.get_extension::<Ambisonic>();
+    let surround = plugin.get_extension::<Surround>();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let original_config = audio_ports
+        .config()
+        .context("Error while querying 'audio-ports' IO configuration")?;
+
+    if original_config.inputs.is_empty() && original_config.outputs.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not have any audio ports. New ports are only added next to existing ones.",
+            )),
+        });
+    }
+
+    let mut num_attempts = 0;
+    let mut num_added_ports = 0;
+    for _ in 0..EXTENSIBLE_AUDIO_PORTS_NUM_ATTEMPTS {
+        // Every existing port may get a new port of a random type added next to it. The requests'
+        // port indices are replaced with the index the new port should end up at.
+        let mut added_ports: Vec<AudioPortsRequest> = Vec::new();
+        for request in random_layout_requests(&original_config, &mut prng) {
+            num_attempts += 1;
+
+            let num_existing_ports = if request.is_input {
+                original_config.inputs.len()
+            } else {
+                original_config.outputs.len()
+            };
+            let port_index = num_existing_ports
+                + added_ports
+                    .iter()
+                    .filter(|added| added.is_input == request.is_input)
+                    .count();
+
+            if extensible_audio_ports.add_port(request.is_input, request.request_info) {
+                added_ports.push(AudioPortsRequest {
+                    port_index: port_index as u32,
+                    ..request
+                });
+            }
+        }
+
+        if added_ports.is_empty() {
+            continue;
+        }
+        num_added_ports += added_ports.len();
+
+        let _span = Span::begin(
+            "AddedPorts",
+            from_fn(|record| {
+                for (i, request) in added_ports.iter().enumerate() {
+                    record.record(&format!("requests.{}", i), *request);
+                }
+            }),
+        );
+
+        let enlarged_config = audio_ports.config().with_context(|| {
+            format!(
+                "Error while querying 'audio-ports' IO configuration after adding the following ports: \n{}",
+                print_layout(&added_ports)
+            )
+        })?;
+
+        let num_added_inputs = added_ports.iter().filter(|request| request.is_input).count();
+        let num_added_outputs = added_ports.len() - num_added_inputs;
+        if enlarged_config.inputs.len() != original_config.inputs.len() + num_added_inputs
+            || enlarged_config.outputs.len() != original_config.outputs.len() + num_added_outputs
+        {
+            anyhow::bail!(
+                "After adding the following ports, the plugin reports {} input and {} output ports, but {} input and \
+                 {} output ports were expected: \n{}",
+                enlarged_config.inputs.len(),
+                enlarged_config.outputs.len(),
+                original_config.inputs.len() + num_added_inputs,
+                original_config.outputs.len() + num_added_outputs,
+                print_layout(&added_ports)
+            );
+        }
+
+        if enlarged_config.inputs[..original_config.inputs.len()] != original_config.inputs[..]
+            || enlarged_config.outputs[..original_config.outputs.len()] != original_config.outputs[..]
+        {
+            anyhow::bail!(
+                "Adding the following ports changed the plugin's existing ports. New ports should be added at the \
+                 end of the port list: \n{}",
+                print_layout(&added_ports)
+            );
+        }
+
+        for request in &added_ports {
+            let port = if request.is_input {
+                &enlarged_config.inputs[request.port_index as usize]
+            } else {
+                &enlarged_config.outputs[request.port_index as usize]
+            };
+
+            check_port_matches_request(request, port, ambisonic.as_ref(), surround.as_ref(), &added_ports)?;
+        }
+
+        plugin
+            .on_audio_thread(|plugin| -> Result<()> {
+                let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&enlarged_config, BUFFER_SIZE);
+                let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-1..=128);
+                let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+                for _ in 0..5 {
+                    process.audio_buffers().fill_white_noise(&mut prng);
+                    process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+                    process.run()?;
+                }
+
+                Ok(())
+            })
+            .with_context(|| {
+                format!(
+                    "Error while processing audio after adding the following ports: \n{}",
+                    print_layout(&added_ports)
+                )
+            })?;
+
+        // The added ports are removed again starting from the last one, which should restore the
+        // original layout
+        for request in added_ports.iter().rev() {
+            if !extensible_audio_ports.remove_port(request.is_input, request.port_index) {
+                anyhow::bail!(
+                    "The plugin refused to remove the {} port at index {} it previously added.",
+                    if request.is_input { "input" } else { "output" },
+                    request.port_index
+                );
+            }
+        }
+
+        let restored_config = audio_ports.config().with_context(|| {
+            format!(
+                "Error while querying 'audio-ports' IO configuration after removing the following ports again: \n{}",
+                print_layout(&added_ports)
+            )
+        })?;
+        if restored_config.inputs != original_config.inputs || restored_config.outputs != original_config.outputs {
+            anyhow::bail!(
+                "After adding and then removing the following ports, the plugin's audio ports do not match the \
+                 original audio ports: \n{}",
+                print_layout(&added_ports)
+            );
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if num_added_ports == 0 {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "Tried adding {num_attempts} random audio ports, but none were accepted."
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
 /// The test for `PluginTestCase::LayoutAudioPortsActivation`.
 pub fn test_layout_audio_ports_activation(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
     #[derive(Debug, Clone, Copy)]
@@ -593,6 +746,72 @@ pub fn test_layout_audio_ports_activation(library: &PluginLibrary, plugin_id: &s
     Ok(TestStatus::Success { details: None })
 }
 
+/// Check that `port` matches the channel count and, if the plugin supports them, the ambisonic
+/// config or surround channel map from `request`. `requests` is the entire layout `request` was
+/// part of, which is included in the error messages.
+fn check_port_matches_request(
+    request: &AudioPortsRequest,
+    port: &AudioPort,
+    ambisonic: Option<&Ambisonic>,
+    surround: Option<&Surround>,
+    requests: &[AudioPortsRequest],
+) -> Result<()> {
+    if port.channel_count != request.request_info.channel_count() {
+        anyhow::bail!(
+            "Wrong number of channels set for {} port (index {}) in response to the layout request: \n{}\n \
+             Expected: {}, got: {}",
+            if request.is_input { "input" } else { "output" },
+            request.port_index,
+            print_layout(requests),
+            request.request_info.channel_count(),
+            port.channel_count,
+        );
+    }
+
+    match request.request_info {
+        AudioPortsRequestInfo::Ambisonic { config, .. } => {
+            if port.port_type.as_deref() == Some(CLAP_PORT_AMBISONIC) {
+                let result = ambisonic
+                    .expect("already checked")
+                    .get_config(request.is_input, request.port_index);
+
+                if result.is_none_or(|x| x.normalization != config.normalization && x.ordering != config.ordering) {
+                    anyhow::bail!(
+                        "Wrong ambisonic config set for {} port (index {}) in response to the layout request: \
+                         \n{}",
+                        if request.is_input { "input" } else { "output" },
+                        request.port_index,
+                        print_layout(requests),
+                    );
+                }
+            }
+        }
+
+        AudioPortsRequestInfo::Surround { channel_map } => {
+            if port.port_type.as_deref() == Some(CLAP_PORT_SURROUND) {
+                let result_map = surround.expect("already checked").get_channel_map(
+                    request.is_input,
+                    request.port_index,
+                    channel_map.len() as u32,
+                );
+
+                if channel_map != result_map {
+                    anyhow::bail!(
+                        "Wrong surround map set for {} port (index {}) in response to the layout request: \n{}",
+                        if request.is_input { "input" } else { "output" },
+                        request.port_index,
+                        print_layout(requests),
+                    );
+                }
+            }
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
 fn print_layout(requests: &[AudioPortsRequest<'_>]) -> String {
     requests
         .iter()
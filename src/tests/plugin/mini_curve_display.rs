@@ -0,0 +1,181 @@
+//! Tests for the draft `mini-curve-display` extension.
+
+use crate::plugin::ext::mini_curve_display::{MiniCurveDisplay, curve_kind_hints};
+use crate::plugin::ffi::mini_curve_display::*;
+use crate::plugin::library::PluginLibrary;
+use crate::tests::TestStatus;
+use anyhow::{Context, Result};
+
+/// The number of values per curve the plugin is asked to render curves at in
+/// [`test_mini_curve_display_render()`]. These range from a single value to larger than any
+/// reasonable display.
+pub const MINI_CURVE_DISPLAY_SIZES: &[u32] = &[1, 7, 64, 100, 256, 2048];
+
+/// The value the curve buffers are filled with for the first render at every size.
+const FILL_VALUE_A: u16 = 0x5a5a;
+/// The value the curve buffers are filled with for the second render at every size. Values that
+/// are still equal to the fill value after both renders have not been written by the plugin.
+const FILL_VALUE_B: u16 = 0xa5a5;
+
+/// The test for `PluginTestCase::MiniCurveDisplayRender`.
+pub fn test_mini_curve_display_render(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let mini_curve_display = match plugin.get_extension::<MiniCurveDisplay>() {
+        Some(mini_curve_display) => mini_curve_display,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin does not implement the 'mini-curve-display' extension.",
+                )),
+            });
+        }
+    };
+
+    let num_curves = mini_curve_display.curve_count();
+    if num_curves == 0 {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin implements the 'mini-curve-display' extension, but it reports zero curves.",
+            )),
+        });
+    }
+
+    mini_curve_display.set_observed(true);
+    plugin.poll_callback(|_| Ok(()))?;
+
+    // The kind of every curve is tied to the hints the host uses to draw it, so a curve should not
+    // change its kind just because it's rendered at a different size
+    let mut curve_kinds: Option<Vec<i32>> = None;
+    for &num_values in MINI_CURVE_DISPLAY_SIZES {
+        let curves_a = mini_curve_display
+            .render(num_curves, num_values, FILL_VALUE_A)
+            .with_context(|| format!("Error while rendering the plugin's curves with {num_values} values"))?;
+        let curves_b = mini_curve_display
+            .render(num_curves, num_values, FILL_VALUE_B)
+            .with_context(|| format!("Error while rendering the plugin's curves with {num_values} values"))?;
+
+        if curves_a.len() != curves_b.len() {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "'clap_plugin_mini_curve_display::render()' rendered {} curves the first time and {} curves the \
+                     second time when rendering {num_values} values, even though nothing changed in between.",
+                    curves_a.len(),
+                    curves_b.len()
+                )),
+            });
+        }
+
+        for (curve_idx, (curve_a, curve_b)) in curves_a.iter().zip(&curves_b).enumerate() {
+            if let Some(value_idx) = curve_a
+                .values
+                .iter()
+                .zip(&curve_b.values)
+                .position(|(&a, &b)| a == FILL_VALUE_A && b == FILL_VALUE_B)
+            {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "When rendering {num_values} values, the plugin did not write value {value_idx} of curve \
+                         {curve_idx}. Every value in the curve buffer needs to be written."
+                    )),
+                });
+            }
+
+            if !(CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED as i32
+                ..=CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TIME_SERIES as i32)
+                .contains(&curve_a.kind)
+                || curve_a.kind != curve_b.kind
+            {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "When rendering {num_values} values, curve {curve_idx} was rendered with the curve kinds {} \
+                         and {}. The curve kind needs to be one of the 'CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_*' \
+                         constants, and it should not change between renders.",
+                        curve_a.kind, curve_b.kind
+                    )),
+                });
+            }
+
+            // Dynamic curves may legitimately change between two renders
+            if !plugin.shared().mini_curve_display_dynamic.load() && curve_a.values != curve_b.values {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "When rendering {num_values} values, curve {curve_idx} changed between two renders even \
+                         though the plugin did not mark its curves as dynamic and nothing changed in between."
+                    )),
+                });
+            }
+        }
+
+        let kinds: Vec<i32> = curves_a.iter().map(|curve| curve.kind).collect();
+        match &curve_kinds {
+            Some(expected_kinds) if *expected_kinds != kinds => {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "The plugin's curves were rendered with the kinds {expected_kinds:?} at one size, but with \
+                         the kinds {kinds:?} when rendering {num_values} values."
+                    )),
+                });
+            }
+            Some(_) => (),
+            None => curve_kinds = Some(kinds),
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+    }
+
+    for curve_idx in 0..num_curves {
+        mini_curve_display
+            .axis_names(curve_idx)
+            .with_context(|| format!("Error while querying the axis names for curve {curve_idx}"))?;
+    }
+
+    mini_curve_display.set_observed(false);
+    plugin.poll_callback(|_| Ok(()))?;
+
+    // The curve values are scaled to the hints the host provides for the curve's kind, so the
+    // plugin needs to have requested those hints to render a curve of that kind consistently
+    let mut warnings = Vec::new();
+    let hint_kinds = plugin.shared().mini_curve_display_hint_kinds.lock().unwrap().clone();
+    for (curve_idx, kind) in curve_kinds.unwrap_or_default().into_iter().enumerate() {
+        let Some(hints) = curve_kind_hints(kind as u32) else {
+            continue;
+        };
+
+        let x_starts_at_zero = !matches!(
+            kind as u32,
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_REDUCTION | CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TIME_SERIES
+        ) || hints.x_min == 0.0;
+        if ![hints.x_min, hints.x_max, hints.y_min, hints.y_max]
+            .iter()
+            .all(|value| value.is_finite())
+            || hints.x_min >= hints.x_max
+            || hints.y_min >= hints.y_max
+            || !x_starts_at_zero
+        {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "The hints for curve {curve_idx}'s kind {kind} are inconsistent: {hints:?}."
+                )),
+            });
+        }
+
+        if !hint_kinds.contains(&(kind as u32)) {
+            warnings.push(format!(
+                "Curve {curve_idx} was rendered with kind {kind}, but the plugin never requested the hints for that \
+                 kind through 'clap_host_mini_curve_display::get_hints()'."
+            ));
+        }
+    }
+
+    if warnings.is_empty() {
+        Ok(TestStatus::Success { details: None })
+    } else {
+        Ok(TestStatus::Warning {
+            details: Some(warnings.join("\n")),
+        })
+    }
+}
@@ -0,0 +1,394 @@
+//! Tests for the note related extensions.
+
+use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::note_name::{NoteName, NoteNameInfo};
+use crate::plugin::ext::note_ports::NotePorts;
+use crate::plugin::ext::voice_info::VoiceInfo;
+use crate::plugin::instance::CallbackEvent;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffers, Event, ProcessScope};
+use crate::tests::TestStatus;
+use crate::tests::rng::{NoteGenerator, new_prng};
+use anyhow::{Context, Result};
+use clap_sys::events::{CLAP_CORE_EVENT_SPACE_ID, CLAP_EVENT_NOTE_END};
+use clap_sys::ext::voice_info::{CLAP_VOICE_INFO_SUPPORTS_OVERLAPPING_NOTES, clap_voice_info};
+use rand::RngExt;
+use std::collections::{HashMap, HashSet};
+
+const BUFFER_SIZE: u32 = 512;
+
+/// The number of notes played on top of the plugin's voice capacity in
+/// [`test_voice_info_consistency()`].
+pub const VOICE_INFO_EXTRA_NOTES: u32 = 16;
+/// The maximum number of notes played in [`test_voice_info_consistency()`], for plugins that
+/// report very large voice capacities.
+pub const VOICE_INFO_MAX_NOTES: u32 = 512;
+/// The number of note on events sent per block.
+const VOICE_INFO_NOTES_PER_BLOCK: usize = 16;
+/// The number of notes that share their key and channel with another playing note, if the plugin
+/// supports overlapping notes.
+pub const VOICE_INFO_NUM_OVERLAPPING_NOTES: usize = 8;
+/// The number of blocks the notes are held for after the last note on.
+const VOICE_INFO_HOLD_BLOCKS: usize = 10;
+/// The maximum amount of time processed after releasing the notes while waiting for them to end, in
+/// seconds.
+const VOICE_INFO_MAX_RELEASE_SECONDS: f64 = 10.0;
+
+/// The test for `PluginTestCase::NoteNamesConsistency`.
+pub fn test_note_names_consistency(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let note_name = match plugin.get_extension::<NoteName>() {
+        Some(note_name) => note_name,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'note-name' extension.")),
+            });
+        }
+    };
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_names = note_name
+        .names()
+        .context("Error while querying the plugin's note names")?;
+    for note_name in &note_names {
+        check_note_name_valid(note_name, note_ports_config.inputs.len())?;
+    }
+
+    // The note names should stay the same as long as the plugin doesn't tell the host that they
+    // changed, so we'll send some notes its way and then query the names again
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for _ in 0..5 {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+        }
+
+        Ok(())
+    })?;
+
+    let mut note_names_changed = false;
+    plugin.poll_callback(|event| {
+        if event == CallbackEvent::NoteNameChanged {
+            note_names_changed = true;
+        }
+
+        Ok(())
+    })?;
+
+    let new_note_names = note_name
+        .names()
+        .context("Error while querying the plugin's note names for the second time")?;
+    for note_name in &new_note_names {
+        check_note_name_valid(note_name, note_ports_config.inputs.len())?;
+    }
+
+    if !note_names_changed && new_note_names != note_names {
+        anyhow::bail!(
+            "The plugin's note names changed between two queries ({} names before, {} names after), but the plugin \
+             did not call 'clap_host_note_name::changed()'.",
+            note_names.len(),
+            new_note_names.len()
+        );
+    }
+
+    if new_note_names.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin implements the 'note-name' extension, but it does not provide any note names.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::VoiceInfoConsistency`.
+pub fn test_voice_info_consistency(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.get_extension::<VoiceInfo>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not implement the 'voice-info' extension.",
+            )),
+        });
+    }
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    if !note_ports_config.inputs.iter().any(|port| port.supports_clap()) {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not have any note input ports that support CLAP note events, so its notes cannot be \
+                 tracked by note ID.",
+            )),
+        });
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let (voice_info, num_notes, num_note_ends) = plugin.on_audio_thread(|plugin| -> Result<_> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        let query_voice_info = || -> Result<clap_voice_info> {
+            let voice_info = plugin
+                .on_main_thread(|plugin| plugin.get_extension::<VoiceInfo>().expect("already checked").get())
+                .context("'clap_plugin_voice_info::get()' returned false")?;
+            check_voice_info(&voice_info)?;
+
+            Ok(voice_info)
+        };
+
+        // The voice info can only be queried while the plugin is active, so the first block is only
+        // used to activate the plugin
+        process.audio_buffers().fill_silence();
+        process.run()?;
+        let voice_info = query_voice_info()?;
+
+        // The plugin's voice info may change at any point, but it must stay valid
+        let check_voice_info_changed = || -> Result<()> {
+            let mut voice_info_changed = false;
+            plugin.poll_callback(|_, event| {
+                voice_info_changed |= event == CallbackEvent::VoiceInfoChanged;
+                Ok(())
+            })?;
+
+            if voice_info_changed {
+                query_voice_info()?;
+            }
+
+            Ok(())
+        };
+
+        // Overlapping notes can only be sent if the plugin can tell them apart by their note IDs
+        let num_notes = voice_info
+            .voice_capacity
+            .saturating_add(VOICE_INFO_EXTRA_NOTES)
+            .min(VOICE_INFO_MAX_NOTES) as usize;
+        let num_overlapping_notes = if voice_info.flags & CLAP_VOICE_INFO_SUPPORTS_OVERLAPPING_NOTES != 0 {
+            VOICE_INFO_NUM_OVERLAPPING_NOTES
+        } else {
+            0
+        };
+
+        let mut playing_notes = HashMap::new();
+        let mut ended_note_ids = HashSet::new();
+        let mut num_notes_sent = 0;
+        while num_notes_sent < num_notes + num_overlapping_notes {
+            let mut times: Vec<u32> = (0..VOICE_INFO_NOTES_PER_BLOCK)
+                .map(|_| prng.random_range(0..BUFFER_SIZE))
+                .collect();
+            times.sort_unstable();
+
+            for time in times {
+                if num_notes_sent >= num_notes + num_overlapping_notes {
+                    break;
+                }
+
+                let overlapping = num_notes_sent >= num_notes;
+                let event = note_rng
+                    .generate_note_on(&mut prng, time, overlapping)
+                    .context("Could not generate a note on event")?;
+                if let Event::Note(note) = &event {
+                    playing_notes.insert(note.note_id, (note.port_index, note.channel, note.key));
+                }
+
+                process.add_events([event]);
+                num_notes_sent += 1;
+            }
+
+            process.audio_buffers().fill_silence();
+            process.run()?;
+            check_note_ends(process.read_events(), &mut playing_notes, &mut ended_note_ids)?;
+            check_voice_info_changed()?;
+        }
+
+        for _ in 0..VOICE_INFO_HOLD_BLOCKS {
+            process.audio_buffers().fill_silence();
+            process.run()?;
+            check_note_ends(process.read_events(), &mut playing_notes, &mut ended_note_ids)?;
+            check_voice_info_changed()?;
+        }
+
+        let max_release_blocks =
+            (VOICE_INFO_MAX_RELEASE_SECONDS * process.sample_rate() / BUFFER_SIZE as f64).ceil() as usize;
+        process.add_events(note_rng.stop_all_voices(0));
+        for _ in 0..max_release_blocks {
+            process.audio_buffers().fill_silence();
+            process.run()?;
+            check_note_ends(process.read_events(), &mut playing_notes, &mut ended_note_ids)?;
+            check_voice_info_changed()?;
+
+            if playing_notes.is_empty() {
+                break;
+            }
+        }
+
+        // Plugins are not required to send note end events at all, but if they do then every note
+        // needs to end eventually
+        if !ended_note_ids.is_empty() && !playing_notes.is_empty() {
+            let mut remaining_note_ids: Vec<i32> = playing_notes.into_keys().collect();
+            remaining_note_ids.sort_unstable();
+            anyhow::bail!(
+                "{} out of {num_notes_sent} notes did not end within {max_release_blocks} blocks \
+                 ({VOICE_INFO_MAX_RELEASE_SECONDS} seconds) after being released. The plugin did not send a 'CLAP_EVENT_NOTE_END' event for the notes with IDs {:?}.",
+                remaining_note_ids.len(),
+                remaining_note_ids
+            );
+        }
+
+        Ok((voice_info, num_notes_sent, ended_note_ids.len()))
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if num_note_ends == 0 {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "The plugin did not send any 'CLAP_EVENT_NOTE_END' events for the {num_notes} notes played with a \
+                 voice capacity of {}, so the host cannot tell when its voices end.",
+                voice_info.voice_capacity
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// Check whether the plugin's voice info is valid.
+fn check_voice_info(voice_info: &clap_voice_info) -> Result<()> {
+    anyhow::ensure!(
+        voice_info.voice_count > 0,
+        "'clap_plugin_voice_info::get()' reported a voice count of zero."
+    );
+    anyhow::ensure!(
+        voice_info.voice_count <= voice_info.voice_capacity,
+        "'clap_plugin_voice_info::get()' reported a voice count of {}, which exceeds the voice capacity of {}.",
+        voice_info.voice_count,
+        voice_info.voice_capacity
+    );
+
+    Ok(())
+}
+
+/// Check the note end events in the plugin's output. Every note end event must refer to a note that
+/// is still playing, which is then removed from `playing_notes` and added to `ended_note_ids`. The
+/// playing notes are indexed by their note IDs and contain the note's port index, channel, and key.
+fn check_note_ends(
+    events: Vec<Event>,
+    playing_notes: &mut HashMap<i32, (i16, i16, i16)>,
+    ended_note_ids: &mut HashSet<i32>,
+) -> Result<()> {
+    for event in events {
+        let Event::Note(note) = event else {
+            continue;
+        };
+        if note.header.space_id != CLAP_CORE_EVENT_SPACE_ID || note.header.type_ != CLAP_EVENT_NOTE_END {
+            continue;
+        }
+
+        match playing_notes.remove(&note.note_id) {
+            Some((port_index, channel, key)) => {
+                // The plugin may use wildcards for the note's other fields
+                anyhow::ensure!(
+                    (note.port_index == -1 || note.port_index == port_index)
+                        && (note.channel == -1 || note.channel == channel)
+                        && (note.key == -1 || note.key == key),
+                    "The plugin sent a 'CLAP_EVENT_NOTE_END' event for note ID {} with port index {}, channel {}, and \
+                     key {}, but that note was started with port index {port_index}, channel {channel}, and key \
+                     {key}.",
+                    note.note_id,
+                    note.port_index,
+                    note.channel,
+                    note.key
+                );
+                ended_note_ids.insert(note.note_id);
+            }
+            None if ended_note_ids.contains(&note.note_id) => anyhow::bail!(
+                "The plugin sent a second 'CLAP_EVENT_NOTE_END' event for the note with ID {}.",
+                note.note_id
+            ),
+            None => anyhow::bail!(
+                "The plugin sent a 'CLAP_EVENT_NOTE_END' event for note ID {} (port index {}, channel {}, key {}), \
+                 but no note with that ID was started.",
+                note.note_id,
+                note.port_index,
+                note.channel,
+                note.key
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether a note name's port, key, and channel are in range. Any of these may also be -1 to
+/// act as a wildcard.
+fn check_note_name_valid(note_name: &NoteNameInfo, num_input_note_ports: usize) -> Result<()> {
+    if note_name.port != -1 && !(0..num_input_note_ports as i64).contains(&(note_name.port as i64)) {
+        anyhow::bail!(
+            "The note name '{}' refers to input note port {}, but the plugin only has {} input note ports.",
+            note_name.name,
+            note_name.port,
+            num_input_note_ports
+        );
+    }
+
+    if note_name.key != -1 && !(0..=127).contains(&note_name.key) {
+        anyhow::bail!(
+            "The note name '{}' has key {}, which is neither -1 nor in the range [0, 127].",
+            note_name.name,
+            note_name.key
+        );
+    }
+
+    if note_name.channel != -1 && !(0..=15).contains(&note_name.channel) {
+        anyhow::bail!(
+            "The note name '{}' has channel {}, which is neither -1 nor in the range [0, 15].",
+            note_name.name,
+            note_name.channel
+        );
+    }
+
+    Ok(())
+}
@@ -4,22 +4,34 @@ use super::PluginTestCase;
 use crate::cli::tracing::{Span, record};
 use crate::plugin::ext::audio_ports::{AudioPortConfig, AudioPorts};
 use crate::plugin::ext::note_ports::{NotePortConfig, NotePorts};
+use crate::plugin::ext::param_indication::ParamIndication;
 use crate::plugin::ext::params::{Param, ParamInfo, Params};
+use crate::plugin::ext::remote_controls::RemoteControls;
+use crate::plugin::instance::CallbackEvent;
 use crate::plugin::library::PluginLibrary;
 use crate::plugin::process::{AudioBuffers, Event, InputEventQueue, OutputEventQueue, ProcessScope};
 use crate::tests::rng::{NoteGenerator, ParamFuzzer, new_prng};
 use crate::tests::{TestCase, TestStatus};
 use anyhow::{Context, Result};
+use clap_sys::color::clap_color;
 use clap_sys::events::CLAP_EVENT_PARAM_VALUE;
-use clap_sys::id::clap_id;
+use clap_sys::ext::param_indication::*;
+use clap_sys::id::{CLAP_INVALID_ID, clap_id};
+use rand::RngExt;
+use rand::seq::IndexedRandom;
+use rand_pcg::Pcg32;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::ffi::CStr;
 use std::ptr::null_mut;
 
 /// The fixed buffer size to use for these tests.
 const BUFFER_SIZE: u32 = 512;
 /// The number of different parameter combinations to try in the parameter fuzzing tests.
 pub const FUZZ_NUM_PERMUTATIONS: usize = 50;
+/// The number of process calls during the param indication fuzzing test. Before each call, every
+/// parameter gets a new random indication.
+pub const PARAM_INDICATION_FUZZ_RUNS: usize = 20;
 /// How many buffers of [`BUFFER_SIZE`] samples to process at each parameter permutation. This
 /// allows the state to settle in before moving to the next set of parameter values.
 pub const FUZZ_RUNS_PER_PERMUTATION: usize = 5;
@@ -653,6 +665,251 @@ pub fn test_param_default_values(library: &PluginLibrary, plugin_id: &str) -> Re
     Ok(TestStatus::Success { details: None })
 }
 
+/// A single random call to one of the `param-indication` extension's functions.
+#[derive(Debug, Clone, Copy)]
+enum ParamIndicationCall {
+    Mapping {
+        param_id: clap_id,
+        has_mapping: bool,
+        color: Option<clap_color>,
+        label: Option<&'static CStr>,
+        description: Option<&'static CStr>,
+    },
+    Automation {
+        param_id: clap_id,
+        automation_state: u32,
+        color: Option<clap_color>,
+    },
+}
+
+impl ParamIndicationCall {
+    fn random(prng: &mut Pcg32, param_id: clap_id) -> Self {
+        const LABELS: &[Option<&CStr>] = &[None, Some(c""), Some(c"CC 74"), Some(c"Knob 3 🎛")];
+        const DESCRIPTIONS: &[Option<&CStr>] = &[
+            None,
+            Some(c""),
+            Some(c"Mapped to the first encoder on the controller's second page"),
+        ];
+
+        let color = prng.random_bool(0.5).then(|| clap_color {
+            alpha: prng.random(),
+            red: prng.random(),
+            green: prng.random(),
+            blue: prng.random(),
+        });
+
+        if prng.random_bool(0.5) {
+            ParamIndicationCall::Mapping {
+                param_id,
+                has_mapping: prng.random_bool(0.5),
+                color,
+                label: *LABELS.choose(prng).unwrap(),
+                description: *DESCRIPTIONS.choose(prng).unwrap(),
+            }
+        } else {
+            ParamIndicationCall::Automation {
+                param_id,
+                automation_state: prng
+                    .random_range(CLAP_PARAM_INDICATION_AUTOMATION_NONE..=CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING),
+                color,
+            }
+        }
+    }
+
+    fn send(&self, param_indication: &ParamIndication) {
+        match *self {
+            ParamIndicationCall::Mapping {
+                param_id,
+                has_mapping,
+                color,
+                label,
+                description,
+            } => param_indication.set_mapping(param_id, has_mapping, color.as_ref(), label, description),
+            ParamIndicationCall::Automation {
+                param_id,
+                automation_state,
+                color,
+            } => param_indication.set_automation(param_id, automation_state, color.as_ref()),
+        }
+    }
+}
+
+/// The test for `PluginTestCase::ParamIndicationFuzz`.
+pub fn test_param_indication_fuzz(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.get_extension::<ParamIndication>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not implement the 'param-indication' extension.",
+            )),
+        });
+    }
+
+    let params = match plugin.get_extension::<Params>() {
+        Some(params) => params,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'params' extension.")),
+            });
+        }
+    };
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    // Besides the plugin's own parameters, the plugin also gets indications for parameter IDs it
+    // doesn't know about. It should simply ignore those.
+    let param_info = params.info().context("Failure while fetching the parameters")?;
+    let unknown_param_id = (0..).find(|param_id| !param_info.contains_key(param_id)).unwrap();
+    let param_ids: Vec<clap_id> = param_info
+        .keys()
+        .copied()
+        .chain([unknown_param_id, CLAP_INVALID_ID])
+        .collect();
+
+    let initial_param_values = param_get_values(&params)?;
+
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for _ in 0..PARAM_INDICATION_FUZZ_RUNS {
+            // Hosts send these indications from the main thread while the plugin is processing
+            // audio, so the calls are dispatched without waiting for them to complete
+            let calls: Vec<ParamIndicationCall> = param_ids
+                .iter()
+                .map(|&param_id| ParamIndicationCall::random(&mut prng, param_id))
+                .collect();
+            plugin.send_main_thread(move |plugin| {
+                let param_indication = plugin.get_extension::<ParamIndication>().expect("already checked");
+                for call in calls {
+                    call.send(&param_indication);
+                }
+            });
+
+            // No notes are sent here, since a plugin may map MIDI CCs to its parameters
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.run()?;
+        }
+
+        Ok(())
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let actual_param_values = param_get_values(&params)?;
+    if let Some(diff) = param_generate_diff(&actual_param_values, &initial_param_values, &params)? {
+        return Ok(TestStatus::Failed {
+            details: Some(format!(
+                "Sending parameter mapping and automation indications to the plugin through the 'param-indication' \
+                 extension caused its parameter values to change: \n{diff}"
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::RemoteControlsPages`.
+pub fn test_remote_controls_pages(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let remote_controls = match plugin.get_extension::<RemoteControls>() {
+        Some(remote_controls) => remote_controls,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin does not implement the 'remote-controls' extension.",
+                )),
+            });
+        }
+    };
+
+    // Plugins without parameters can't map anything to their pages, but they may still have pages
+    let param_info = plugin
+        .get_extension::<Params>()
+        .map(|params| params.info())
+        .transpose()
+        .context("Failure while fetching the parameters")?
+        .unwrap_or_default();
+
+    let pages = remote_controls
+        .pages()
+        .context("Error while querying the plugin's remote controls pages")?;
+
+    let mut page_ids = BTreeMap::new();
+    for page in &pages {
+        if page.page_id == CLAP_INVALID_ID {
+            anyhow::bail!(
+                "The remote controls page '{}' uses 'CLAP_INVALID_ID' as its page ID.",
+                page.page_name
+            );
+        }
+
+        if let Some(other_page_name) = page_ids.insert(page.page_id, &page.page_name) {
+            anyhow::bail!(
+                "The remote controls pages '{}' and '{}' both have page ID {}.",
+                other_page_name,
+                page.page_name,
+                page.page_id
+            );
+        }
+
+        if page.page_name.is_empty() {
+            anyhow::bail!("The remote controls page with ID {} has an empty name.", page.page_id);
+        }
+
+        for (control_index, &param_id) in page.param_ids.iter().enumerate() {
+            if param_id != CLAP_INVALID_ID && !param_info.contains_key(&param_id) {
+                anyhow::bail!(
+                    "Control {control_index} on remote controls page '{}' (ID {}) is mapped to parameter {param_id}, \
+                     but the plugin does not have a parameter with that ID.",
+                    page.page_name,
+                    page.page_id
+                );
+            }
+        }
+    }
+
+    // If the plugin suggested a page in the meantime, then that page should of course exist
+    plugin.poll_callback(|event| {
+        if let CallbackEvent::RemoteControlsSuggestPage(page_id) = event
+            && !page_ids.contains_key(&page_id)
+        {
+            anyhow::bail!(
+                "The plugin called 'clap_host_remote_controls::suggest_page()' with page ID {page_id}, but it does \
+                 not have a remote controls page with that ID."
+            );
+        }
+
+        Ok(())
+    })?;
+
+    if pages.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin implements the 'remote-controls' extension, but it does not provide any pages.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
 pub fn param_get_values(params: &Params) -> Result<BTreeMap<clap_id, f64>> {
     params
         .info()?
@@ -2,21 +2,46 @@
 
 use crate::cli::tracing::{Span, record};
 use crate::plugin::ext::audio_ports::{AudioPortConfig, AudioPorts};
+use crate::plugin::ext::gain_adjustment_metering::GainAdjustmentMetering;
 use crate::plugin::ext::note_ports::{NotePortConfig, NotePorts};
+use crate::plugin::ext::params::Params;
+use crate::plugin::ext::project_location::ProjectLocation;
 use crate::plugin::ext::tail::Tail;
-use crate::plugin::instance::{CallbackEvent, ProcessStatus};
+use crate::plugin::ext::timer_support::TimerSupport;
+use crate::plugin::ext::track_info::TrackInfo;
+use crate::plugin::ext::tuning::Tuning;
+use crate::plugin::instance::{ALLOCATION_TRACKING_SUPPORTED, CallbackEvent, ProcessStatus};
 use crate::plugin::library::PluginLibrary;
-use crate::plugin::process::{AudioBuffers, ConstantMask, ProcessRun, ProcessScope};
+use crate::plugin::process::{AudioBuffers, ConstantMask, Event, ProcessRun, ProcessScope};
 use crate::tests::TestStatus;
-use crate::tests::rng::{NoteGenerator, new_prng};
+use crate::tests::plugin::params::{param_generate_diff, param_get_values};
+use crate::tests::rng::{NoteGenerator, new_prng, random_project_location, random_track_info, random_tunings};
 use anyhow::{Context, Result};
+use clap_sys::events::clap_event_header;
+use clap_sys::ext::draft::tuning::{CLAP_EXT_TUNING, clap_event_tuning};
+use clap_sys::id::CLAP_INVALID_ID;
 use either::Either;
 use rand::RngExt;
 use std::f32;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 const BUFFER_SIZE: u32 = 512;
 
+/// How long the plugin processes audio in real time while its timers are being fired.
+pub const TIMER_PROCESSING_DURATION: Duration = Duration::from_millis(500);
+
+/// The number of blocks processed in [`test_process_note_tuning()`].
+const TUNING_NUM_BLOCKS: usize = 30;
+/// The host's tuning pool is replaced every this many blocks in [`test_process_note_tuning()`].
+pub const TUNING_CHANGE_INTERVAL: usize = 5;
+
+/// The number of blocks processed in [`test_project_location_changed()`]. The location changes
+/// after every block.
+pub const PROJECT_LOCATION_NUM_BLOCKS: usize = 20;
+
+/// The number of blocks processed in [`test_process_gain_adjustment_metering()`].
+pub const GAIN_ADJUSTMENT_METERING_NUM_BLOCKS: usize = 50;
+
 /// The test for `PluginTestCase::ProcessAudioOutOfPlaceBasic` and `PluginTestCase::ProcessAudioInPlaceBasic`.
 pub fn test_process_audio_basic(library: &PluginLibrary, plugin_id: &str, in_place: bool) -> Result<TestStatus> {
     let mut prng = new_prng();
@@ -764,6 +789,436 @@ pub fn test_process_reset_reactivate(library: &PluginLibrary, plugin_id: &str) -
     Ok(result)
 }
 
+/// The test for `PluginTestCase::ProcessTimerSupport`.
+pub fn test_process_timer_support(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.get_extension::<TimerSupport>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not implement the 'timer-support' extension.",
+            )),
+        });
+    }
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        // The blocks are processed in real time so the main thread has a chance to fire the timers
+        // in between process calls, like it would in a regular host
+        let block_duration = Duration::from_secs_f64(BUFFER_SIZE as f64 / process.sample_rate());
+        let start = Instant::now();
+        let mut num_blocks = 0;
+
+        while start.elapsed() < TIMER_PROCESSING_DURATION {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+
+            num_blocks += 1;
+            if let Some(remaining) = (start + block_duration * num_blocks).checked_duration_since(Instant::now()) {
+                std::thread::sleep(remaining);
+            }
+        }
+
+        Ok(())
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if plugin.shared().timers.num_ticks() == 0 {
+        return Ok(TestStatus::Skipped {
+            details: Some(format!(
+                "The plugin implements the 'timer-support' extension, but none of its timers fired while processing \
+                 audio for {} milliseconds.",
+                TIMER_PROCESSING_DURATION.as_millis()
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::TrackInfoChanged`.
+pub fn test_track_info_changed(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.get_extension::<TrackInfo>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not implement the 'track-info' extension.",
+            )),
+        });
+    }
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for _ in 0..10 {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+
+            // The track information is changed from the main thread in between process calls, just
+            // like a user renaming or recoloring the track would
+            let track_info = random_track_info(&mut prng);
+            plugin.on_main_thread(|plugin| {
+                *plugin.shared().track_info.lock().unwrap() = track_info;
+                plugin.get_extension::<TrackInfo>().expect("already checked").changed();
+            });
+        }
+
+        Ok(())
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::ProjectLocationChanged`.
+pub fn test_project_location_changed(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let project_location = match plugin.get_extension::<ProjectLocation>() {
+        Some(project_location) => project_location,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'location' extension.")),
+            });
+        }
+    };
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    // The plugin's location is not part of its parameters, so moving the plugin around should not
+    // change them
+    let params = plugin.get_extension::<Params>();
+    let expected_param_values = params.as_ref().map(param_get_values).transpose()?;
+
+    project_location.set(&random_project_location(&mut prng));
+    plugin.poll_callback(|_| Ok(()))?;
+
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for block_idx in 0..PROJECT_LOCATION_NUM_BLOCKS {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+
+            // The first process call directly follows activation, so only the allocations made in the
+            // process calls following a location change are counted
+            if block_idx == 0 {
+                plugin.shared().process_allocations.store(0);
+            }
+
+            // The location is changed from the main thread in between process calls, just like a
+            // user moving the track or regrouping tracks would
+            let location = random_project_location(&mut prng);
+            plugin.on_main_thread(|plugin| {
+                plugin
+                    .get_extension::<ProjectLocation>()
+                    .expect("already checked")
+                    .set(&location);
+            });
+        }
+
+        // The plugin should be silent again after all notes are released, so any parameter values
+        // the plugin may set from the audio thread are flushed before the values are compared
+        process.add_events(note_rng.stop_all_voices(0));
+        process.audio_buffers().fill_silence();
+        process.run()?;
+
+        Ok(())
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let num_allocations = plugin.shared().process_allocations.load();
+    if num_allocations > 0 {
+        return Ok(TestStatus::Failed {
+            details: Some(format!(
+                "The plugin allocated memory {num_allocations} time(s) from within 'clap_plugin::process()' after \
+                 its location was changed through 'clap_plugin_location::set()'. Plugins should not allocate \
+                 memory on the audio thread."
+            )),
+        });
+    }
+
+    if let (Some(params), Some(expected_param_values)) = (&params, &expected_param_values) {
+        let actual_param_values = param_get_values(params)?;
+        if let Some(diff) = param_generate_diff(&actual_param_values, expected_param_values, params)? {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "The plugin's parameter values changed after changing its project location: \n{diff}"
+                )),
+            });
+        }
+    }
+
+    if !ALLOCATION_TRACKING_SUPPORTED {
+        return Ok(TestStatus::Success {
+            details: Some(String::from(
+                "Allocations cannot be tracked on this platform, so the plugin was not checked for allocations \
+                 on the audio thread.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::ProcessNoteTuning`.
+pub fn test_process_note_tuning(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+
+    // Plugins usually look up the tuning event space and the tuning pool during initialization
+    let tuning_space_id = plugin.shared().event_registry.lock().unwrap().register(CLAP_EXT_TUNING);
+    let tunings = random_tunings(&mut prng);
+    let mut tuning_ids: Vec<_> = tunings.iter().map(|tuning| tuning.tuning_id).collect();
+    plugin.shared().tunings.set(tunings);
+
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.get_extension::<Tuning>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from("The plugin does not implement the 'tuning' extension.")),
+        });
+    }
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    if note_ports_config.inputs.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from("The plugin does not have any input note ports.")),
+        });
+    }
+
+    // Tuning events can only be sent if the plugin knows which event space they belong to
+    let send_tuning_events = plugin
+        .shared()
+        .event_registry
+        .lock()
+        .unwrap()
+        .is_known_to_plugin(tuning_space_id);
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    plugin.on_audio_thread(|plugin| -> Result<()> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for block_idx in 0..TUNING_NUM_BLOCKS {
+            // The tuning pool is replaced from the main thread in between process calls, just like
+            // a user loading a different set of tuning files would
+            if block_idx > 0 && block_idx % TUNING_CHANGE_INTERVAL == 0 {
+                let tunings = random_tunings(&mut prng);
+                tuning_ids = tunings.iter().map(|tuning| tuning.tuning_id).collect();
+                plugin.on_main_thread(|plugin| {
+                    plugin.shared().tunings.set(tunings);
+                    plugin.get_extension::<Tuning>().expect("already checked").changed();
+                });
+            }
+
+            if send_tuning_events {
+                // This occasionally selects a tuning that isn't in the pool, which the plugin
+                // should handle gracefully
+                let tuning_id = if prng.random_bool(0.1) {
+                    CLAP_INVALID_ID
+                } else {
+                    tuning_ids[prng.random_range(0..tuning_ids.len())]
+                };
+
+                process.add_events([Event::Tuning(clap_event_tuning {
+                    header: clap_event_header {
+                        size: std::mem::size_of::<clap_event_tuning>() as u32,
+                        time: prng.random_range(0..BUFFER_SIZE),
+                        space_id: tuning_space_id,
+                        type_: 0,
+                        flags: 0,
+                    },
+                    port_index: prng.random_range(0..note_ports_config.inputs.len()) as i16,
+                    channel: prng.random_range(-1..16),
+                    tunning_id: tuning_id,
+                })]);
+            }
+
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+        }
+
+        Ok(())
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if plugin.shared().tunings.num_get_relative_calls() == 0 {
+        return Ok(TestStatus::Warning {
+            details: Some(String::from(
+                "The plugin implements the 'tuning' extension, but it never called \
+                 'clap_host_tuning::get_relative()'.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::ProcessGainAdjustmentMetering`.
+pub fn test_process_gain_adjustment_metering(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    if plugin.shared().raw_extension::<GainAdjustmentMetering>().is_none() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not implement the 'gain-adjustment-metering' extension.",
+            )),
+        });
+    }
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let result = plugin.on_audio_thread(|plugin| -> Result<Option<TestStatus>> {
+        let gain_adjustment_metering = plugin
+            .get_extension::<GainAdjustmentMetering>()
+            .expect("already checked");
+
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        for block_idx in 0..GAIN_ADJUSTMENT_METERING_NUM_BLOCKS {
+            // Some blocks are silent so the metering also covers the plugin's idle state
+            if prng.random_bool(0.2) {
+                process.add_events(note_rng.stop_all_voices(0));
+                process.audio_buffers().fill_silence();
+            } else {
+                process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+                process.audio_buffers().fill_white_noise(&mut prng);
+            }
+            process.run()?;
+
+            let gain_adjustment = gain_adjustment_metering.get();
+            if !gain_adjustment.is_finite() {
+                return Ok(Some(TestStatus::Failed {
+                    details: Some(format!(
+                        "'clap_plugin_gain_adjustment_metering::get_adjustment_metering()' returned {gain_adjustment} \
+                         after processing block {block_idx}. The gain adjustment must be a finite value."
+                    )),
+                }));
+            }
+        }
+
+        Ok(None)
+    })?;
+
+    if let Some(status) = result {
+        return Ok(status);
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    Ok(TestStatus::Success { details: None })
+}
+
 /// A channel is considered quiet if the signal is below -60 dbfs, ignoring DC.
 ///
 /// This function is designed to be very lenient in what it considers "quiet", to avoid false positives.
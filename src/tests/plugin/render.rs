@@ -0,0 +1,159 @@
+//! Tests for the `render` extension.
+
+use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::note_ports::NotePorts;
+use crate::plugin::ext::render::Render;
+use crate::plugin::instance::Plugin;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffer, AudioBuffers, ProcessScope};
+use crate::tests::TestStatus;
+use crate::tests::rng::{NoteGenerator, new_prng};
+use anyhow::{Context, Result};
+use clap_sys::ext::render::CLAP_RENDER_OFFLINE;
+
+const BUFFER_SIZE: u32 = 512;
+const NUM_BLOCKS: usize = 5;
+
+/// The test for `PluginTestCase::RenderOfflineBasic`.
+pub fn test_render_offline_basic(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let render = match plugin.get_extension::<Render>() {
+        Some(render) => render,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'render' extension.")),
+            });
+        }
+    };
+
+    // Plugins with a hard realtime requirement cannot be rendered offline, so the only thing we can
+    // check is that they refuse the offline mode instead of silently accepting it
+    if render.has_hard_realtime_requirement() {
+        if render.set(CLAP_RENDER_OFFLINE) {
+            anyhow::bail!(
+                "The plugin reports a hard realtime requirement, but 'clap_plugin_render::set()' accepted \
+                 'CLAP_RENDER_OFFLINE'."
+            );
+        }
+
+        // The plugin should still work normally after refusing the offline mode
+        process_random_input(&plugin, false)?;
+
+        return Ok(TestStatus::Success {
+            details: Some(String::from(
+                "The plugin has a hard realtime requirement and correctly refused to switch to offline rendering.",
+            )),
+        });
+    }
+
+    process_random_input(&plugin, true)?;
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// The test for `PluginTestCase::RenderOfflineDeterministic`.
+pub fn test_render_offline_deterministic(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut outputs = Vec::with_capacity(2);
+
+    // Two separate instances get the exact same input, and the rendered output should be identical
+    for _ in 0..2 {
+        let plugin = library
+            .create_plugin(plugin_id)
+            .context("Could not create the plugin instance")?;
+        plugin.init().context("Error during initialization")?;
+
+        let render = match plugin.get_extension::<Render>() {
+            Some(render) => render,
+            None => {
+                return Ok(TestStatus::Skipped {
+                    details: Some(String::from("The plugin does not implement the 'render' extension.")),
+                });
+            }
+        };
+
+        if render.has_hard_realtime_requirement() {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin has a hard realtime requirement, so it cannot be rendered offline.",
+                )),
+            });
+        }
+
+        outputs.push(process_random_input(&plugin, true)?);
+    }
+
+    let (first_output, second_output) = (&outputs[0], &outputs[1]);
+    for (block_idx, (first_block, second_block)) in first_output.iter().zip(second_output).enumerate() {
+        for (first_buffer, second_buffer) in first_block.iter().zip(second_block) {
+            if !first_buffer.is_same(second_buffer) {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "Rendering the same input offline with two separate plugin instances resulted in different \
+                         output for output port {} in block {block_idx}. Offline rendering should be deterministic.",
+                        first_buffer.port().output().unwrap_or_default()
+                    )),
+                });
+            }
+        }
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// Process [`NUM_BLOCKS`] blocks of random audio and note events through the plugin, optionally
+/// after switching it to offline rendering. The input is the same every time this is called. Returns
+/// the output buffers for every block.
+fn process_random_input(plugin: &Plugin, offline: bool) -> Result<Vec<Vec<AudioBuffer>>> {
+    let mut prng = new_prng();
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let output = plugin.on_audio_thread(|plugin| -> Result<Vec<Vec<AudioBuffer>>> {
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+        if offline {
+            process.set_render_mode(CLAP_RENDER_OFFLINE);
+        }
+
+        let mut output = Vec::with_capacity(NUM_BLOCKS);
+        for _ in 0..NUM_BLOCKS {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.run()?;
+
+            output.push(
+                process
+                    .audio_buffers()
+                    .iter()
+                    .filter(|buffer| buffer.port().output().is_some())
+                    .cloned()
+                    .collect(),
+            );
+        }
+
+        Ok(output)
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    Ok(output)
+}
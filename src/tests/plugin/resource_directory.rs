@@ -0,0 +1,159 @@
+//! Tests for the draft `resource-directory` extension.
+
+use super::PluginTestCase;
+use crate::plugin::ext::params::Params;
+use crate::plugin::ext::resource_directory::ResourceDirectory;
+use crate::plugin::ext::state::State;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{InputEventQueue, OutputEventQueue};
+use crate::tests::plugin::params::{param_generate_diff, param_get_values};
+use crate::tests::rng::{ParamFuzzer, new_prng};
+use crate::tests::{TestCase, TestStatus};
+use anyhow::{Context, Result};
+
+/// The name of the resource directory created for [`test_resource_directory_collect()`].
+const RESOURCE_DIRECTORY_NAME: &str = "resources";
+
+/// The test for `PluginTestCase::ResourceDirectoryCollect`.
+pub fn test_resource_directory_collect(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let resource_directory_path =
+        PluginTestCase::ResourceDirectoryCollect.temporary_directory(plugin_id, RESOURCE_DIRECTORY_NAME)?;
+    let canonical_resource_directory_path = resource_directory_path
+        .canonicalize()
+        .context("Could not canonicalize the resource directory's path")?;
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+
+    let (expected_state, expected_param_values, num_files) = {
+        plugin.init().context("Error during initialization")?;
+
+        let resource_directory = match plugin.get_extension::<ResourceDirectory>() {
+            Some(resource_directory) => resource_directory,
+            None => {
+                return Ok(TestStatus::Skipped {
+                    details: Some(String::from(
+                        "The plugin does not implement the 'resource-directory' extension.",
+                    )),
+                });
+            }
+        };
+
+        let state = match plugin.get_extension::<State>() {
+            Some(state) => state,
+            None => {
+                return Ok(TestStatus::Skipped {
+                    details: Some(String::from("The plugin does not implement the 'state' extension.")),
+                });
+            }
+        };
+
+        resource_directory.set_directory(Some(&resource_directory_path), false)?;
+
+        // If the plugin has parameters, then those are randomized first so the saved state is not
+        // just the default state
+        let params = plugin.get_extension::<Params>();
+        if let Some(params) = &params {
+            let param_info = params
+                .info()
+                .context("Failure while fetching the plugin's parameters")?;
+            let param_fuzzer = ParamFuzzer::new(&param_info);
+
+            let input_queue = InputEventQueue::new();
+            let output_queue = OutputEventQueue::new();
+            input_queue.add_events(param_fuzzer.randomize_params_at(&mut prng, 0));
+            params.flush(&input_queue, &output_queue);
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        resource_directory.collect(true);
+        let files = resource_directory
+            .files()
+            .context("Error while querying the plugin's resource files")?;
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        for file in &files {
+            // Relative paths are interpreted relative to the resource directory
+            let canonical_file = resource_directory_path.join(file).canonicalize().with_context(|| {
+                format!(
+                    "The plugin reported '{}' as one of its resource files, but this file does not exist.",
+                    file.display()
+                )
+            })?;
+
+            if !canonical_file.starts_with(&canonical_resource_directory_path) {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "The plugin reported '{}' as one of its resource files, but this file is not located inside \
+                         of the resource directory at '{}'.",
+                        file.display(),
+                        resource_directory_path.display()
+                    )),
+                });
+            }
+        }
+
+        let expected_param_values = params.as_ref().map(param_get_values).transpose()?;
+        let expected_state = state.save()?;
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        (expected_state, expected_param_values, files.len())
+    };
+
+    drop(plugin);
+
+    // The saved state together with the collected files should be enough for a new instance to
+    // restore the same state
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance a second time")?;
+    plugin
+        .init()
+        .context("Error while initializing the second plugin instance")?;
+
+    let resource_directory = plugin
+        .get_extension::<ResourceDirectory>()
+        .context("The plugin's second instance does not implement the 'resource-directory' extension")?;
+    let state = plugin
+        .get_extension::<State>()
+        .context("The plugin's second instance does not implement the 'state' extension")?;
+
+    resource_directory.set_directory(Some(&resource_directory_path), false)?;
+    plugin.poll_callback(|_| Ok(()))?;
+
+    state.load(&expected_state)?;
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if let Some(expected_param_values) = expected_param_values {
+        let params = plugin
+            .get_extension::<Params>()
+            .context("The plugin's second instance does not implement the 'params' extension")?;
+
+        let actual_param_values = param_get_values(&params)?;
+        if let Some(diff) = param_generate_diff(&actual_param_values, &expected_param_values, &params)? {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "After collecting the plugin's resources, saving its state, and loading that state into a new \
+                     instance that uses the same resource directory, these parameter values do not match the old \
+                     values: \n{diff}"
+                )),
+            });
+        }
+    }
+
+    if num_files == 0 {
+        return Ok(TestStatus::Success {
+            details: Some(String::from(
+                "The plugin did not report any files after collecting its resources.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
@@ -3,15 +3,24 @@
 use super::PluginTestCase;
 use crate::plugin::ext::params::Params;
 use crate::plugin::ext::state::State;
+use crate::plugin::ext::state_context::{StateContext, context_type_name};
 use crate::plugin::library::PluginLibrary;
 use crate::plugin::process::{InputEventQueue, OutputEventQueue};
 use crate::tests::plugin::params::{param_generate_diff, param_get_values};
 use crate::tests::rng::{ParamFuzzer, new_prng};
 use crate::tests::{TestCase, TestStatus};
 use anyhow::{Context, Result};
+use clap_sys::ext::state_context::*;
 use rand::RngExt;
 use std::io::Write;
 
+/// All context types defined by the `state-context` extension.
+pub const STATE_CONTEXT_TYPES: [clap_plugin_state_context_type; 3] = [
+    CLAP_STATE_CONTEXT_FOR_PRESET,
+    CLAP_STATE_CONTEXT_FOR_DUPLICATE,
+    CLAP_STATE_CONTEXT_FOR_PROJECT,
+];
+
 /// The file name we'll use to dump the expected state when a test fails.
 const EXPECTED_STATE_FILE_NAME: &str = "state-expected";
 /// The file name we'll use to dump the actual state when a test fails.
@@ -248,3 +257,209 @@ pub fn test_state_reproducibility(
         })
     }
 }
+
+/// The test for `PluginTestCase::StateContextReproducibilityPreset`,
+/// `PluginTestCase::StateContextReproducibilityDuplicate`, and
+/// `PluginTestCase::StateContextReproducibilityProject`. This performs the same check as
+/// [`test_state_reproducibility()`] with binary equality using the `state-context` extension,
+/// saving and loading the state with the same context type.
+pub fn test_state_context_reproducibility(
+    library: &PluginLibrary,
+    plugin_id: &str,
+    context_type: clap_plugin_state_context_type,
+) -> Result<TestStatus> {
+    state_context_roundtrip(library, plugin_id, context_type, context_type, true)
+}
+
+/// The test for `PluginTestCase::StateContextCrossLoad`. The state is saved with one context type
+/// and loaded with all other context types. Since the plugin may store different information for
+/// different contexts, mismatching parameter values only result in a warning here.
+pub fn test_state_context_cross_load(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut warnings = Vec::new();
+    for save_context_type in STATE_CONTEXT_TYPES {
+        for load_context_type in STATE_CONTEXT_TYPES {
+            if load_context_type == save_context_type {
+                continue;
+            }
+
+            match state_context_roundtrip(library, plugin_id, save_context_type, load_context_type, false)? {
+                TestStatus::Success { .. } => (),
+                TestStatus::Warning { details } => warnings.extend(details),
+                status => return Ok(status),
+            }
+        }
+    }
+
+    if warnings.is_empty() {
+        Ok(TestStatus::Success { details: None })
+    } else {
+        Ok(TestStatus::Warning {
+            details: Some(warnings.join("\n\n")),
+        })
+    }
+}
+
+/// Randomize the parameters of a fresh plugin instance, save its state with `save_context_type`,
+/// and load that state into a second instance with `load_context_type`. The parameter values of
+/// both instances are then compared, after which the second instance's state is saved and loaded
+/// once more. A parameter mismatch is a failure if both context types are the same, and a warning
+/// otherwise. If `binary_equality` is set, then the state saved by the second instance must also be
+/// byte for byte identical to the original state.
+fn state_context_roundtrip(
+    library: &PluginLibrary,
+    plugin_id: &str,
+    save_context_type: clap_plugin_state_context_type,
+    load_context_type: clap_plugin_state_context_type,
+    binary_equality: bool,
+) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+
+    let (expected_state, expected_param_values) = {
+        plugin.init().context("Error during initialization")?;
+
+        let params = match plugin.get_extension::<Params>() {
+            Some(params) => params,
+            None => {
+                return Ok(TestStatus::Skipped {
+                    details: Some(String::from("The plugin does not implement the 'params' extension.")),
+                });
+            }
+        };
+
+        let state_context = match plugin.get_extension::<StateContext>() {
+            Some(state_context) => state_context,
+            None => {
+                return Ok(TestStatus::Skipped {
+                    details: Some(String::from(
+                        "The plugin does not implement the 'state-context' extension.",
+                    )),
+                });
+            }
+        };
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        let param_info = params
+            .info()
+            .context("Failure while fetching the plugin's parameters")?;
+
+        let param_fuzzer = ParamFuzzer::new(&param_info);
+        let param_events: Vec<_> = param_fuzzer.randomize_params_at(&mut prng, 0).collect();
+
+        {
+            let input_queue = InputEventQueue::new();
+            let output_queue = OutputEventQueue::new();
+            input_queue.add_events(param_events);
+            params.flush(&input_queue, &output_queue);
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        let expected_param_values = param_get_values(&params)?;
+        let expected_state = state_context.save(save_context_type)?;
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        (expected_state, expected_param_values)
+    };
+
+    drop(plugin);
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance a second time")?;
+
+    plugin
+        .init()
+        .context("Error while initializing the second plugin instance")?;
+
+    let params = match plugin.get_extension::<Params>() {
+        Some(params) => params,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'params' extension.")),
+            });
+        }
+    };
+
+    let state_context = match plugin.get_extension::<StateContext>() {
+        Some(state_context) => state_context,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin's second instance does not implement the 'state-context' extension.",
+                )),
+            });
+        }
+    };
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    state_context.load(&expected_state, load_context_type)?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let actual_param_values = param_get_values(&params)?;
+    if let Some(diff) = param_generate_diff(&actual_param_values, &expected_param_values, &params)? {
+        let details = format!(
+            "After saving the state with '{}' and reloading it with '{}', these parameter values do not match the old \
+             values: \n{}",
+            context_type_name(save_context_type),
+            context_type_name(load_context_type),
+            diff
+        );
+
+        if save_context_type == load_context_type {
+            anyhow::bail!(details);
+        } else {
+            return Ok(TestStatus::Warning { details: Some(details) });
+        }
+    }
+
+    // Saving and loading the state again in the second instance should not change anything either
+    let resaved_state = state_context.save(save_context_type)?;
+    state_context.load(&resaved_state, load_context_type)?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let resaved_param_values = param_get_values(&params)?;
+    if let Some(diff) = param_generate_diff(&resaved_param_values, &actual_param_values, &params)? {
+        anyhow::bail!(
+            "Saving the reloaded state with '{}' and loading it again with '{}' changed these parameter values: \n{}",
+            context_type_name(save_context_type),
+            context_type_name(load_context_type),
+            diff
+        );
+    }
+
+    if !binary_equality || resaved_state == expected_state {
+        Ok(TestStatus::Success { details: None })
+    } else {
+        let test_case = match save_context_type {
+            CLAP_STATE_CONTEXT_FOR_PRESET => PluginTestCase::StateContextReproducibilityPreset,
+            CLAP_STATE_CONTEXT_FOR_DUPLICATE => PluginTestCase::StateContextReproducibilityDuplicate,
+            _ => PluginTestCase::StateContextReproducibilityProject,
+        };
+        let (expected_state_file_path, mut expected_state_file) =
+            test_case.temporary_file(plugin_id, EXPECTED_STATE_FILE_NAME)?;
+        let (actual_state_file_path, mut actual_state_file) =
+            test_case.temporary_file(plugin_id, ACTUAL_STATE_FILE_NAME)?;
+
+        expected_state_file.write_all(&expected_state)?;
+        actual_state_file.write_all(&resaved_state)?;
+
+        Ok(TestStatus::Failed {
+            details: Some(format!(
+                "The state saved with '{}' after loading differs from the original saved state. \nExpected: '{}'. \
+                 \nActual: '{}'.",
+                context_type_name(save_context_type),
+                expected_state_file_path.display(),
+                actual_state_file_path.display(),
+            )),
+        })
+    }
+}
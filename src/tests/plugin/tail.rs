@@ -0,0 +1,230 @@
+//! Tests for the `tail` extension.
+
+use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::latency::Latency;
+use crate::plugin::ext::tail::Tail;
+use crate::plugin::instance::CallbackEvent;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffers, ProcessScope};
+use crate::tests::TestStatus;
+use crate::tests::rng::new_prng;
+use anyhow::{Context, Result};
+use either::Either;
+
+const BUFFER_SIZE: u32 = 512;
+
+/// The number of blocks of random audio processed before the input goes silent in
+/// [`test_tail_accuracy()`].
+pub const TAIL_NUM_ACTIVE_BLOCKS: usize = 20;
+/// The level the plugin's output needs to decay below to be considered silent. This is -80 dBFS.
+const TAIL_SILENCE_THRESHOLD: f64 = 1e-4;
+/// The number of samples the output is allowed to take to decay past the reported tail and the
+/// plugin's latency.
+pub const TAIL_MARGIN: u32 = 4096;
+/// The maximum duration of silence processed while measuring the tail, in seconds. Longer tails
+/// cannot be verified.
+const TAIL_MAX_MEASURED_SECONDS: f64 = 5.0;
+
+/// The result of processing audio in [`test_tail_accuracy()`].
+struct TailMeasurement {
+    /// The tail reported by the plugin at the moment the input went silent.
+    reported_tail: u32,
+    /// The plugin's latency, or zero if it does not implement the `latency` extension.
+    latency: u32,
+    /// The number of samples it took for the output to decay below [`TAIL_SILENCE_THRESHOLD`], or
+    /// `None` if the tail was not measured because it's infinite.
+    decay_samples: Option<u32>,
+    /// The number of silent samples processed, which bounds `decay_samples`.
+    num_measured_samples: u32,
+    /// The tail values that did not change after a `clap_host_tail::changed()` call.
+    unchanged_tails: Vec<u32>,
+}
+
+/// The test for `PluginTestCase::TailAccuracy`.
+pub fn test_tail_accuracy(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    if audio_ports_config.outputs.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not have any audio outputs, so it does not have a tail.",
+            )),
+        });
+    }
+
+    // The release of an instrument's notes cannot be told apart from its tail, so only effects are
+    // tested, and no note events are sent
+    if audio_ports_config.inputs.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin does not have any audio inputs. Only the tails of effects can be measured.",
+            )),
+        });
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let measurement = plugin.on_audio_thread(|plugin| -> Result<Option<TailMeasurement>> {
+        let Some(tail) = plugin.get_extension::<Tail>() else {
+            return Ok(None);
+        };
+
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        // Every `clap_host_tail::changed()` call should be followed by a different tail
+        let mut current_tail = None;
+        let mut unchanged_tails = Vec::new();
+        let mut check_tail_changed = |current_tail: &mut Option<u32>| -> Result<()> {
+            let mut tail_changed = false;
+            plugin.poll_callback(|_, event| {
+                tail_changed |= matches!(event, CallbackEvent::TailChanged);
+                Ok(())
+            })?;
+
+            match *current_tail {
+                Some(previous_tail) if tail_changed => {
+                    let new_tail = tail.get();
+                    if new_tail == previous_tail {
+                        unchanged_tails.push(new_tail);
+                    }
+                    *current_tail = Some(new_tail);
+                }
+                Some(_) => (),
+                // The tail can only be queried once the plugin has been activated. Any
+                // `clap_host_tail::changed()` calls made up to this point have already been drained,
+                // so they are not compared against the value they announced.
+                None => *current_tail = Some(tail.get()),
+            }
+
+            Ok(())
+        };
+
+        for _ in 0..TAIL_NUM_ACTIVE_BLOCKS {
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.run()?;
+            check_tail_changed(&mut current_tail)?;
+        }
+
+        let reported_tail = tail.get();
+        let latency =
+            plugin.on_main_thread(|plugin| plugin.get_extension::<Latency>().map_or(0, |latency| latency.get()));
+
+        // An infinite tail cannot be measured, and the output may keep going forever. Any value of
+        // `INT32_MAX` or above means that the tail is infinite.
+        if reported_tail >= i32::MAX as u32 {
+            return Ok(Some(TailMeasurement {
+                reported_tail,
+                latency,
+                decay_samples: None,
+                num_measured_samples: 0,
+                unchanged_tails,
+            }));
+        }
+
+        let max_measured_samples = (TAIL_MAX_MEASURED_SECONDS * process.sample_rate()) as u32;
+        let num_measured_samples = reported_tail
+            .saturating_add(latency)
+            .saturating_add(TAIL_MARGIN * 2)
+            .min(max_measured_samples);
+        let mut decay_samples = 0;
+        for block_start in (0..num_measured_samples).step_by(BUFFER_SIZE as usize) {
+            process.audio_buffers().fill_silence();
+            process.run()?;
+            check_tail_changed(&mut current_tail)?;
+
+            for buffer in process.audio_buffers().iter() {
+                if buffer.port().output().is_none() {
+                    continue;
+                }
+
+                for channel in 0..buffer.channels() {
+                    let last_loud_sample = match buffer.channel(channel) {
+                        Either::Left(data) => data
+                            .iter()
+                            .rposition(|sample| sample.abs() as f64 > TAIL_SILENCE_THRESHOLD),
+                        Either::Right(data) => data.iter().rposition(|sample| sample.abs() > TAIL_SILENCE_THRESHOLD),
+                    };
+                    if let Some(last_loud_sample) = last_loud_sample {
+                        decay_samples = decay_samples.max(block_start + last_loud_sample as u32 + 1);
+                    }
+                }
+            }
+        }
+
+        Ok(Some(TailMeasurement {
+            reported_tail,
+            latency,
+            decay_samples: Some(decay_samples),
+            num_measured_samples,
+            unchanged_tails,
+        }))
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let Some(measurement) = measurement else {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from("The plugin does not implement the 'tail' extension.")),
+        });
+    };
+
+    let TailMeasurement {
+        reported_tail,
+        latency,
+        decay_samples,
+        num_measured_samples,
+        unchanged_tails,
+    } = measurement;
+    if let Some(decay_samples) = decay_samples {
+        let allowed_samples = reported_tail.saturating_add(latency).saturating_add(TAIL_MARGIN);
+        if decay_samples >= num_measured_samples && num_measured_samples > allowed_samples {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "The plugin reports a tail of {reported_tail} samples, but its output did not decay below -80 \
+                     dBFS within {num_measured_samples} samples after the input went silent. Plugins with infinite \
+                     tails should report a tail of 'INT32_MAX' or above."
+                )),
+            });
+        } else if decay_samples > allowed_samples {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "The plugin reports a tail of {reported_tail} samples and a latency of {latency} samples, but its \
+                     output only decayed below -80 dBFS {decay_samples} samples after the input went silent."
+                )),
+            });
+        }
+    }
+
+    if !unchanged_tails.is_empty() {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "The plugin called 'clap_host_tail::changed()' {} time(s) without changing the value returned by \
+                 'clap_plugin_tail::get()' ({unchanged_tails:?}).",
+                unchanged_tails.len()
+            )),
+        });
+    }
+
+    if decay_samples.is_none() {
+        return Ok(TestStatus::Success {
+            details: Some(String::from(
+                "The plugin reports an infinite tail, so the length of its tail was not measured.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
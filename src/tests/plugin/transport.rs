@@ -1,14 +1,35 @@
 use crate::cli::tracing::{Span, record};
 use crate::plugin::ext::audio_ports::{AudioPortConfig, AudioPorts};
 use crate::plugin::ext::note_ports::{NotePortConfig, NotePorts};
+use crate::plugin::instance::TransportRequest;
 use crate::plugin::library::PluginLibrary;
 use crate::plugin::process::{AudioBuffers, Event, ProcessScope, TransportState};
 use crate::tests::TestStatus;
 use crate::tests::rng::{NoteGenerator, TransportFuzzer, new_prng};
 use anyhow::{Context, Result};
+use clap_sys::events::{
+    CLAP_TRANSPORT_IS_LOOP_ACTIVE, CLAP_TRANSPORT_IS_PLAYING, CLAP_TRANSPORT_IS_RECORDING, clap_event_transport,
+};
+use clap_sys::fixedpoint::clap_beattime;
+use std::time::{Duration, Instant};
 
 const BUFFER_SIZE: u32 = 128;
 
+/// How long the plugin gets to make transport requests in `test_transport_control_requests()`.
+/// Audio is processed in real time during this period.
+pub const TRANSPORT_CONTROL_DURATION: Duration = Duration::from_secs(1);
+
+/// The transport state the plugin's `clap_host_transport_control` requests should result in. This
+/// is tracked separately from the transport that is sent to the plugin so the two can be compared.
+#[derive(Debug, Default)]
+struct RequestedTransport {
+    is_playing: bool,
+    is_recording: bool,
+    is_loop_enabled: bool,
+    /// The requested loop region's start and end in beats.
+    loop_region: Option<(clap_beattime, clap_beattime)>,
+}
+
 /// The test for `PluginTestCase::TransportNull`
 pub fn test_transport_null(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
     let mut prng = new_prng();
@@ -179,3 +200,183 @@ pub fn test_transport_fuzz_sample_accurate(library: &PluginLibrary, plugin_id: &
 
     Ok(TestStatus::Success { details: None })
 }
+
+/// The test for `PluginTestCase::TransportControlRequests`.
+pub fn test_transport_control_requests(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let audio_ports_config = match plugin.get_extension::<AudioPorts>() {
+        Some(audio_ports) => audio_ports
+            .config()
+            .context("Error while querying 'audio-ports' IO configuration")?,
+        None => AudioPortConfig::default(),
+    };
+
+    let note_ports_config = match plugin.get_extension::<NotePorts>() {
+        Some(note_ports) => note_ports
+            .config()
+            .context("Error while querying 'note-ports' IO configuration")?,
+        None => NotePortConfig::default(),
+    };
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    let num_requests = plugin.on_audio_thread(|plugin| -> Result<usize> {
+        let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-1..=128);
+        let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+        let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+        // The blocks are processed in real time so requests the plugin makes from the main thread
+        // end up in between process calls, like they would in a regular host
+        let block_duration = Duration::from_secs_f64(BUFFER_SIZE as f64 / process.sample_rate());
+        let start = Instant::now();
+        let mut num_blocks = 0;
+
+        let mut requested = RequestedTransport::default();
+        let mut num_requests = 0;
+        while start.elapsed() < TRANSPORT_CONTROL_DURATION {
+            process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+            process.audio_buffers().fill_white_noise(&mut prng);
+            process.run()?;
+
+            let requests = process.transport_requests();
+            if !requests.is_empty() {
+                num_requests += requests.len();
+
+                let transport = process
+                    .last_transport()
+                    .context("The plugin did not receive a transport during the process call")?;
+                let position = requested.apply(requests);
+                check_requested_transport(&requested, position, transport)
+                    .with_context(|| format!("After the plugin made the transport requests {requests:?}"))?;
+            }
+
+            num_blocks += 1;
+            if let Some(remaining) = (start + block_duration * num_blocks).checked_duration_since(Instant::now()) {
+                std::thread::sleep(remaining);
+            }
+        }
+
+        Ok(num_requests)
+    })?;
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    if num_requests == 0 {
+        return Ok(TestStatus::Skipped {
+            details: Some(format!(
+                "The plugin did not make any transport requests while processing audio for {} milliseconds.",
+                TRANSPORT_CONTROL_DURATION.as_millis()
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success {
+        details: Some(format!(
+            "All {num_requests} transport requests made by the plugin were reflected in the transport."
+        )),
+    })
+}
+
+impl RequestedTransport {
+    /// Update the requested state with a block's transport requests. Returns the position in beats
+    /// the transport should have jumped to, if any of the requests moved the playhead.
+    fn apply(&mut self, requests: &[TransportRequest]) -> Option<clap_beattime> {
+        let mut position = None;
+        for request in requests {
+            match *request {
+                TransportRequest::Start => {
+                    self.is_playing = true;
+                    position = Some(0);
+                }
+                TransportRequest::Stop => {
+                    self.is_playing = false;
+                    self.is_recording = false;
+                    position = Some(0);
+                }
+                TransportRequest::Continue => self.is_playing = true,
+                TransportRequest::Pause => self.is_playing = false,
+                TransportRequest::TogglePlay => self.is_playing = !self.is_playing,
+                TransportRequest::Jump(beats) => position = Some(beats),
+                TransportRequest::LoopRegion(start, duration) => {
+                    self.loop_region = Some((start, start.saturating_add(duration)));
+                }
+                TransportRequest::ToggleLoop => self.is_loop_enabled = !self.is_loop_enabled,
+                TransportRequest::EnableLoop(is_enabled) => self.is_loop_enabled = is_enabled,
+                TransportRequest::Record(is_recording) => self.is_recording = is_recording,
+                TransportRequest::ToggleRecord => self.is_recording = !self.is_recording,
+            }
+        }
+
+        position
+    }
+}
+
+/// Check whether the transport passed to the plugin matches the state it requested.
+fn check_requested_transport(
+    requested: &RequestedTransport,
+    position: Option<clap_beattime>,
+    transport: &clap_event_transport,
+) -> Result<()> {
+    let is_playing = transport.flags & CLAP_TRANSPORT_IS_PLAYING != 0;
+    anyhow::ensure!(
+        is_playing == requested.is_playing,
+        "The next process call's transport was {}, even though the plugin requested it to be {}.",
+        if is_playing { "playing" } else { "paused" },
+        if requested.is_playing { "playing" } else { "paused" }
+    );
+
+    let is_recording = transport.flags & CLAP_TRANSPORT_IS_RECORDING != 0;
+    anyhow::ensure!(
+        is_recording == requested.is_recording,
+        "The next process call's transport was {}recording, even though the plugin requested it to {}record.",
+        if is_recording { "" } else { "not " },
+        if requested.is_recording { "" } else { "not " }
+    );
+
+    if let Some(position) = position {
+        anyhow::ensure!(
+            beattime_matches(transport.song_pos_beats, position),
+            "The next process call's transport was at beat time {}, but the plugin requested a jump to {position}.",
+            transport.song_pos_beats
+        );
+    }
+
+    let requested_loop_region = requested.loop_region.filter(|_| requested.is_loop_enabled);
+    let is_loop_active = transport.flags & CLAP_TRANSPORT_IS_LOOP_ACTIVE != 0;
+    match requested_loop_region {
+        Some((start, end)) => anyhow::ensure!(
+            is_loop_active
+                && beattime_matches(transport.loop_start_beats, start)
+                && beattime_matches(transport.loop_end_beats, end),
+            "The plugin requested looping between beat times {start} and {end}, but the next process call's \
+             transport {}.",
+            if is_loop_active {
+                format!(
+                    "looped between {} and {}",
+                    transport.loop_start_beats, transport.loop_end_beats
+                )
+            } else {
+                String::from("did not loop")
+            }
+        ),
+        None => anyhow::ensure!(
+            !is_loop_active,
+            "The next process call's transport was looping, even though the plugin did not request an enabled loop \
+             region."
+        ),
+    }
+
+    Ok(())
+}
+
+/// Whether two beat times are equal, allowing for the rounding that happens when the transport
+/// converts them to floating point values and back.
+fn beattime_matches(actual: clap_beattime, expected: clap_beattime) -> bool {
+    (actual as f64 - expected as f64).abs() <= 1.0 + expected.unsigned_abs() as f64 * f64::EPSILON
+}
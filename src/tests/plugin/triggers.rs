@@ -0,0 +1,143 @@
+//! Tests for the draft `triggers` extension.
+
+use crate::plugin::ext::audio_ports::AudioPorts;
+use crate::plugin::ext::note_ports::NotePorts;
+use crate::plugin::ext::triggers::Triggers;
+use crate::plugin::instance::CallbackEvent;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{AudioBuffers, ProcessScope};
+use crate::tests::TestStatus;
+use crate::tests::rng::{NoteGenerator, new_prng, random_trigger_events};
+use anyhow::{Context, Result};
+use clap_sys::ext::draft::triggers::CLAP_EXT_TRIGGERS;
+
+const BUFFER_SIZE: u32 = 512;
+
+/// The number of times the plugin is activated in [`test_triggers_fuzz()`]. The plugin's trigger
+/// information is rescanned in between activations if the plugin asked for that.
+const TRIGGERS_NUM_ACTIVATIONS: usize = 4;
+/// The number of blocks processed during every activation in [`test_triggers_fuzz()`].
+const TRIGGERS_NUM_BLOCKS: usize = 10;
+
+/// The test for `PluginTestCase::TriggersFuzz`.
+pub fn test_triggers_fuzz(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+
+    // The trigger event space needs to be known before the plugin queries it during initialization
+    let trigger_space_id = plugin
+        .shared()
+        .event_registry
+        .lock()
+        .unwrap()
+        .register(CLAP_EXT_TRIGGERS);
+
+    plugin.init().context("Error during initialization")?;
+
+    let triggers = match plugin.get_extension::<Triggers>() {
+        Some(triggers) => triggers,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'triggers' extension.")),
+            });
+        }
+    };
+
+    let audio_ports_config = plugin
+        .get_extension::<AudioPorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'audio-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let note_ports_config = plugin
+        .get_extension::<NotePorts>()
+        .map(|x| x.config())
+        .transpose()
+        .context("Error while querying 'note-ports' IO configuration")?
+        .unwrap_or_default();
+
+    let mut trigger_info = triggers
+        .info()
+        .context("Failure while fetching the plugin's triggers")?;
+
+    if trigger_info.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin implements the 'triggers' extension, but it does not have any triggers.",
+            )),
+        });
+    }
+
+    if !plugin
+        .shared()
+        .event_registry
+        .lock()
+        .unwrap()
+        .is_known_to_plugin(trigger_space_id)
+    {
+        return Ok(TestStatus::Warning {
+            details: Some(format!(
+                "The plugin exposes {} trigger(s), but it never queried the '{}' event space through \
+                 'clap_host_event_registry::query()'. The host can thus not send any trigger events to the plugin.",
+                trigger_info.len(),
+                CLAP_EXT_TRIGGERS.to_string_lossy()
+            )),
+        });
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    for _ in 0..TRIGGERS_NUM_ACTIVATIONS {
+        plugin.on_audio_thread(|plugin| -> Result<()> {
+            let mut audio_buffers = AudioBuffers::new_out_of_place_f32(&audio_ports_config, BUFFER_SIZE);
+            let mut note_rng = NoteGenerator::new(&note_ports_config).with_sample_offset_range(-4..=64);
+            let mut process = ProcessScope::new(&plugin, &mut audio_buffers)?;
+
+            for _ in 0..TRIGGERS_NUM_BLOCKS {
+                process.add_events(random_trigger_events(
+                    &mut prng,
+                    &trigger_info,
+                    trigger_space_id,
+                    note_ports_config.inputs.len(),
+                    BUFFER_SIZE,
+                ));
+
+                process.audio_buffers().fill_white_noise(&mut prng);
+                process.add_events(note_rng.generate_events(&mut prng, BUFFER_SIZE));
+                process.run()?;
+            }
+
+            Ok(())
+        })?;
+
+        // The plugin may change its triggers at any point, so the new information is used for the
+        // next activation
+        let mut rescan = false;
+        plugin.poll_callback(|event| {
+            match event {
+                CallbackEvent::TriggersRescanInfo | CallbackEvent::TriggersRescanAll => rescan = true,
+                CallbackEvent::TriggersClear(trigger_id) => anyhow::ensure!(
+                    trigger_info.contains_key(&trigger_id),
+                    "The plugin called 'clap_host_triggers::clear()' for trigger ID {trigger_id}, which does not \
+                     exist."
+                ),
+                _ => (),
+            }
+
+            Ok(())
+        })?;
+
+        if rescan {
+            trigger_info = triggers
+                .info()
+                .context("Failure while fetching the plugin's triggers after a rescan")?;
+        }
+    }
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    Ok(TestStatus::Success { details: None })
+}
@@ -0,0 +1,236 @@
+//! Tests for the draft `undo` extensions.
+
+use crate::plugin::ext::params::Params;
+use crate::plugin::ext::undo_context::UndoContext;
+use crate::plugin::ext::undo_delta::UndoDelta;
+use crate::plugin::instance::{Plugin, UndoChange};
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::process::{InputEventQueue, OutputEventQueue};
+use crate::tests::TestStatus;
+use crate::tests::plugin::params::{param_generate_diff, param_get_values};
+use crate::tests::rng::{ParamFuzzer, new_prng};
+use anyhow::{Context, Result};
+use std::ffi::CString;
+
+/// The number of times the parameters are randomized in [`test_undo_delta_roundtrip()`]. Every
+/// randomization is a separate undo step.
+pub const UNDO_NUM_STEPS: usize = 10;
+
+/// The test for `PluginTestCase::UndoDeltaRoundtrip`.
+pub fn test_undo_delta_roundtrip(library: &PluginLibrary, plugin_id: &str) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let plugin = library
+        .create_plugin(plugin_id)
+        .context("Could not create the plugin instance")?;
+    plugin.init().context("Error during initialization")?;
+
+    let params = match plugin.get_extension::<Params>() {
+        Some(params) => params,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from("The plugin does not implement the 'params' extension.")),
+            });
+        }
+    };
+
+    let undo_delta = match plugin.get_extension::<UndoDelta>() {
+        Some(undo_delta) => undo_delta,
+        None => {
+            return Ok(TestStatus::Skipped {
+                details: Some(String::from(
+                    "The plugin does not implement the 'undo-delta' extension.",
+                )),
+            });
+        }
+    };
+
+    let properties = undo_delta.get_delta_properties();
+    if !properties.has_delta {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin implements the 'undo-delta' extension, but it reports that it does not provide deltas.",
+            )),
+        });
+    }
+
+    let format_version = properties.format_version;
+    if !undo_delta.can_use_delta_format_version(format_version) {
+        anyhow::bail!(
+            "'clap_plugin_undo_delta::can_use_delta_format_version()' returned false for the plugin's own delta \
+             format version {format_version}."
+        );
+    }
+
+    let param_info = params
+        .info()
+        .context("Failure while fetching the plugin's parameters")?;
+    let param_fuzzer = ParamFuzzer::new(&param_info);
+
+    plugin.poll_callback(|_| Ok(()))?;
+
+    // `snapshots[i]` contains the parameter values before `steps[i]`, and `snapshots[i + 1]`
+    // contains the values after it
+    let mut snapshots = vec![param_get_values(&params)?];
+    let mut steps: Vec<Vec<UndoChange>> = Vec::with_capacity(UNDO_NUM_STEPS);
+    for _ in 0..UNDO_NUM_STEPS {
+        let history_start = plugin.shared().undo_history.num_changes();
+
+        {
+            let input_queue = InputEventQueue::new();
+            let output_queue = OutputEventQueue::new();
+            input_queue.add_events(param_fuzzer.randomize_params_at(&mut prng, 0));
+            params.flush(&input_queue, &output_queue);
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+
+        steps.push(plugin.shared().undo_history.changes_since(history_start));
+        snapshots.push(param_get_values(&params)?);
+        update_undo_context(&plugin, &steps, steps.len())?;
+    }
+
+    if steps.iter().all(Vec::is_empty) {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin did not report any undoable changes through 'clap_host_undo::change_made()' while its \
+                 parameters were being changed.",
+            )),
+        });
+    }
+
+    // Steps are undone from the most recent one backwards. Steps without any reported changes have
+    // nothing to undo and are skipped over, but a step can only be undone if all of its changes
+    // have a delta that can be used for undoing. Since the earlier steps build on top of that step,
+    // undoing stops there.
+    let mut first_undone_step = steps.len();
+    let mut undo_stopped_at = None;
+    for (step_idx, changes) in steps.iter().enumerate().rev() {
+        if changes.is_empty() {
+            if param_generate_diff(&snapshots[step_idx + 1], &snapshots[step_idx], &params)?.is_some() {
+                undo_stopped_at = Some((
+                    step_idx,
+                    "the plugin's parameter values changed without it reporting an undoable change",
+                ));
+                break;
+            }
+
+            first_undone_step = step_idx;
+            continue;
+        }
+
+        if changes
+            .iter()
+            .any(|change| change.delta.is_none() || !change.delta_can_undo)
+        {
+            undo_stopped_at = Some((step_idx, "its changes did not have deltas that can be used for undoing"));
+            break;
+        }
+
+        for change in changes.iter().rev() {
+            undo_delta
+                .undo(format_version, change.delta.as_deref().unwrap_or_default())
+                .with_context(|| format!("Error while undoing the change '{}'", change.name))?;
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+        update_undo_context(&plugin, &steps, step_idx)?;
+
+        let actual_param_values = param_get_values(&params)?;
+        if let Some(diff) = param_generate_diff(&actual_param_values, &snapshots[step_idx], &params)? {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "After undoing the {} change(s) reported for randomization step {step_idx}, these parameter \
+                     values do not match the values from before that step: \n{diff}",
+                    changes.len()
+                )),
+            });
+        }
+
+        first_undone_step = step_idx;
+    }
+
+    let num_undone_changes: usize = steps[first_undone_step..].iter().map(Vec::len).sum();
+    if num_undone_changes == 0 {
+        let (step_idx, reason) = undo_stopped_at.expect("At least one step has changes");
+        return Ok(TestStatus::Skipped {
+            details: Some(format!(
+                "The plugin reported undoable changes, but none of them could be undone. Randomization step \
+                 {step_idx} could not be undone because {reason}, and the steps before it build on top of it."
+            )),
+        });
+    }
+
+    // And then everything that was undone gets redone again, in the original order
+    for step_idx in first_undone_step..steps.len() {
+        let changes = &steps[step_idx];
+        if changes.is_empty() {
+            continue;
+        }
+
+        for change in changes {
+            undo_delta
+                .redo(format_version, change.delta.as_deref().unwrap_or_default())
+                .with_context(|| format!("Error while redoing the change '{}'", change.name))?;
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+        update_undo_context(&plugin, &steps, step_idx + 1)?;
+
+        let actual_param_values = param_get_values(&params)?;
+        if let Some(diff) = param_generate_diff(&actual_param_values, &snapshots[step_idx + 1], &params)? {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "After redoing the {} change(s) reported for randomization step {step_idx}, these parameter \
+                     values do not match the values from after that step: \n{diff}",
+                    changes.len()
+                )),
+            });
+        }
+    }
+
+    if let Some((step_idx, reason)) = undo_stopped_at {
+        return Ok(TestStatus::Success {
+            details: Some(format!(
+                "Only randomization steps {first_undone_step} through {} could be undone. Step {step_idx} could not \
+                 be undone because {reason}, and the steps before it build on top of it.",
+                steps.len() - 1
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
+
+/// If the plugin subscribed to undo context updates through
+/// `clap_host_undo::set_wants_context_updates()`, tell it what the host's undo stack looks like
+/// when the changes from the first `num_applied_steps` steps are applied and the changes from the
+/// remaining steps have been undone. Returns an error if the plugin subscribed to these updates but
+/// does not implement the `undo-context` extension.
+fn update_undo_context(plugin: &Plugin, steps: &[Vec<UndoChange>], num_applied_steps: usize) -> Result<()> {
+    if !plugin.shared().undo_history.wants_context_updates() {
+        return Ok(());
+    }
+
+    let Some(undo_context) = plugin.get_extension::<UndoContext>() else {
+        anyhow::bail!(
+            "The plugin subscribed to undo context updates through 'clap_host_undo::set_wants_context_updates()', \
+             but it does not implement the 'undo-context' extension."
+        );
+    };
+
+    let (applied, undone) = steps.split_at(num_applied_steps);
+    let next_undo = applied.iter().flatten().next_back();
+    let next_redo = undone.iter().flatten().next();
+
+    undo_context.set_can_undo(next_undo.is_some());
+    undo_context.set_can_redo(next_redo.is_some());
+    if let Some(change) = next_undo {
+        undo_context.set_undo_name(&CString::new(change.name.as_str())?);
+    }
+    if let Some(change) = next_redo {
+        undo_context.set_redo_name(&CString::new(change.name.as_str())?);
+    }
+
+    Ok(())
+}
@@ -10,6 +10,7 @@ use std::path::Path;
 mod factories;
 mod preset_discovery;
 mod scanning;
+mod state_converter;
 
 /// Tests for entire CLAP libraries. These are mostly to ensure good plugin scanning practices. See
 /// the module's heading for more information, and the `description` function below for a
@@ -38,6 +39,10 @@ pub enum PluginLibraryTestCase {
     QueryNonexistentFactory,
     #[strum(serialize = "create-id-with-trailing-garbage")]
     CreateIdWithTrailingGarbage,
+    #[strum(serialize = "invalidation-factory-consistency")]
+    InvalidationFactoryConsistency,
+    #[strum(serialize = "state-converter-roundtrip")]
+    StateConverterRoundtrip,
 }
 
 impl<'a> TestCase<'a> for PluginLibraryTestCase {
@@ -76,6 +81,20 @@ impl<'a> TestCase<'a> for PluginLibraryTestCase {
                 "Attempts to create a plugin instance using an existing plugin ID with some extra text appended to \
                  the end. This should return a null pointer.",
             ),
+            PluginLibraryTestCase::InvalidationFactoryConsistency => String::from(
+                "If the plugin library exposes a plugin invalidation factory, then this test ensures that the \
+                 directories of all of its invalidation sources exist, and that calling \
+                 'clap_plugin_invalidation_factory::refresh()' without any changes on disk returns true and does not \
+                 change the plugin factory's descriptors.",
+            ),
+            PluginLibraryTestCase::StateConverterRoundtrip => format!(
+                "If the plugin library exposes a plugin state converter factory, then this test creates all of its \
+                 converters. If a converter's source plugin is part of the library, then that plugin's state is \
+                 converted and loaded into the destination plugin. The converters are also given an empty state, \
+                 truncated states, and {} buffers of random bytes, which should fail with an error message instead \
+                 of crashing. The test is skipped if none of the converters could be tested with a real state.",
+                state_converter::STATE_CONVERTER_NUM_RANDOM_INPUTS
+            ),
         }
     }
 
@@ -99,6 +118,12 @@ impl<'a> TestCase<'a> for PluginLibraryTestCase {
             PluginLibraryTestCase::CreateIdWithTrailingGarbage => {
                 factories::test_create_id_with_trailing_garbage(library_path)
             }
+            PluginLibraryTestCase::InvalidationFactoryConsistency => {
+                factories::test_invalidation_factory_consistency(library_path)
+            }
+            PluginLibraryTestCase::StateConverterRoundtrip => {
+                state_converter::test_state_converter_roundtrip(library_path)
+            }
         }
     }
 }
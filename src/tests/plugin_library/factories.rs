@@ -3,6 +3,7 @@
 use crate::plugin::library::PluginLibrary;
 use crate::tests::TestStatus;
 use anyhow::{Context, Result};
+use clap_sys::factory::draft::plugin_invalidation::CLAP_PLUGIN_INVALIDATION_FACTORY_ID;
 use clap_sys::version::clap_version_is_compatible;
 use std::path::Path;
 
@@ -89,3 +90,79 @@ pub fn test_create_id_with_trailing_garbage(library_path: &Path) -> Result<TestS
         Ok(TestStatus::Success { details: None })
     }
 }
+
+/// The test for `PluginLibraryTestCase::InvalidationFactoryConsistency`.
+pub fn test_invalidation_factory_consistency(library_path: &Path) -> Result<TestStatus> {
+    let library =
+        PluginLibrary::load(library_path).with_context(|| format!("Could not load '{}'", library_path.display()))?;
+
+    let plugin_invalidation_factory = match library.plugin_invalidation_factory() {
+        Ok(plugin_invalidation_factory) => plugin_invalidation_factory,
+        Err(_) => {
+            return Ok(TestStatus::Skipped {
+                details: Some(format!(
+                    "The plugin does not implement the '{}' factory.",
+                    CLAP_PLUGIN_INVALIDATION_FACTORY_ID.to_str().unwrap()
+                )),
+            });
+        }
+    };
+
+    let sources = plugin_invalidation_factory
+        .sources()
+        .context("Could not query the plugin invalidation factory's sources")?;
+
+    // The host would be watching these directories, so they should exist
+    let missing_directories: Vec<&str> = sources
+        .iter()
+        .filter(|source| !Path::new(&source.directory).is_dir())
+        .map(|source| source.directory.as_str())
+        .collect();
+    if !missing_directories.is_empty() {
+        return Ok(TestStatus::Failed {
+            details: Some(format!(
+                "The plugin invalidation factory declares sources for directories that do not exist: '{}'.",
+                missing_directories.join("', '")
+            )),
+        });
+    }
+
+    let metadata_before = library.metadata().context("Could not query the plugin's metadata")?;
+
+    // None of the invalidation sources changed, so refreshing should not change anything
+    if !plugin_invalidation_factory.refresh() {
+        return Ok(TestStatus::Warning {
+            details: Some(String::from(
+                "'clap_plugin_invalidation_factory::refresh()' returned false even though none of the invalidation \
+                 sources changed. This means the host needs to reload the entire plugin library.",
+            )),
+        });
+    }
+
+    let metadata_after = library
+        .metadata()
+        .context("Could not query the plugin's metadata after calling 'clap_plugin_invalidation_factory::refresh()'")?;
+    if metadata_after.plugins != metadata_before.plugins {
+        return Ok(TestStatus::Failed {
+            details: Some(String::from(
+                "The plugin factory's descriptors changed after calling 'clap_plugin_invalidation_factory::refresh()', \
+                 even though none of the invalidation sources changed.",
+            )),
+        });
+    }
+
+    let sources_after = plugin_invalidation_factory.sources().context(
+        "Could not query the plugin invalidation factory's sources after calling \
+         'clap_plugin_invalidation_factory::refresh()'",
+    )?;
+    if sources_after != sources {
+        return Ok(TestStatus::Failed {
+            details: Some(String::from(
+                "The plugin invalidation factory's sources changed after calling \
+                 'clap_plugin_invalidation_factory::refresh()', even though none of the invalidation sources changed.",
+            )),
+        });
+    }
+
+    Ok(TestStatus::Success { details: None })
+}
@@ -0,0 +1,245 @@
+//! Tests involving the plugin state converter factory.
+
+use crate::plugin::ext::state::State;
+use crate::plugin::library::PluginLibrary;
+use crate::plugin::preset_discovery::PluginAbi;
+use crate::plugin::state_converter::StateConversion;
+use crate::tests::TestStatus;
+use crate::tests::rng::new_prng;
+use anyhow::{Context, Result};
+use clap_sys::factory::draft::plugin_state_converter::CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID;
+use rand::RngExt;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// The number of random byte buffers passed to every state converter in
+/// [`test_state_converter_roundtrip()`].
+pub const STATE_CONVERTER_NUM_RANDOM_INPUTS: usize = 20;
+/// The maximum size of the random byte buffers passed to the state converters.
+const STATE_CONVERTER_MAX_RANDOM_INPUT_SIZE: usize = 64 * 1024;
+
+/// The test for `PluginLibraryTestCase::StateConverterRoundtrip`.
+pub fn test_state_converter_roundtrip(library_path: &Path) -> Result<TestStatus> {
+    let mut prng = new_prng();
+
+    let library =
+        PluginLibrary::load(library_path).with_context(|| format!("Could not load '{}'", library_path.display()))?;
+    let state_converter_factory = match library.plugin_state_converter_factory() {
+        Ok(state_converter_factory) => state_converter_factory,
+        Err(_) => {
+            return Ok(TestStatus::Skipped {
+                details: Some(format!(
+                    "The plugin does not implement the '{}' factory.",
+                    CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID.to_str().unwrap(),
+                )),
+            });
+        }
+    };
+
+    let metadata = state_converter_factory
+        .metadata()
+        .context("Could not fetch the state converter descriptors from the factory")?;
+    if metadata.is_empty() {
+        return Ok(TestStatus::Skipped {
+            details: Some(String::from(
+                "The plugin's state converter factory does not contain any converters.",
+            )),
+        });
+    }
+
+    let num_converters = metadata.len();
+    let plugin_ids: HashSet<String> = library
+        .metadata()
+        .context("Could not query the plugin's metadata")?
+        .plugins
+        .into_iter()
+        .map(|plugin_metadata| plugin_metadata.id)
+        .collect();
+
+    let mut warnings = Vec::new();
+    // The converters for which no real state was available to convert, along with their source
+    // plugin's ID. Only their handling of invalid input has been tested.
+    let mut unexercised_converters = Vec::new();
+    for converter_metadata in metadata {
+        let dst_plugin_id = &converter_metadata.dst_plugin_id;
+        if dst_plugin_id.abi != PluginAbi::Clap || !plugin_ids.contains(&dst_plugin_id.id) {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "The state converter with ID '{}' converts state for '{}', which is not a CLAP plugin exposed by \
+                     this plugin library.",
+                    converter_metadata.id, dst_plugin_id.id
+                )),
+            });
+        }
+
+        let converter = state_converter_factory
+            .create_converter(&converter_metadata)
+            .with_context(|| {
+                format!(
+                    "Could not create the state converter with ID '{}'",
+                    converter_metadata.id
+                )
+            })?;
+        let descriptor = converter.descriptor().with_context(|| {
+            format!(
+                "Could not read the descriptor of the state converter with ID '{}'",
+                converter_metadata.id
+            )
+        })?;
+        if descriptor != converter_metadata {
+            return Ok(TestStatus::Failed {
+                details: Some(format!(
+                    "The descriptor of the state converter with ID '{}' does not match the one returned by the \
+                     factory.",
+                    converter_metadata.id
+                )),
+            });
+        }
+
+        // If the converter converts state between two CLAP plugins from this library, for instance
+        // between two major versions of the same plugin, then a real state can be used as input
+        let src_plugin_id = &converter_metadata.src_plugin_id;
+        let src_state = if src_plugin_id.abi == PluginAbi::Clap && plugin_ids.contains(&src_plugin_id.id) {
+            let plugin = library
+                .create_plugin(&src_plugin_id.id)
+                .with_context(|| format!("Could not create a plugin instance for '{}'", src_plugin_id.id))?;
+            plugin
+                .init()
+                .with_context(|| format!("Error while initializing '{}'", src_plugin_id.id))?;
+
+            let src_state = plugin
+                .get_extension::<State>()
+                .map(|state| state.save())
+                .transpose()
+                .with_context(|| format!("Could not save the state of '{}'", src_plugin_id.id))?;
+            plugin.poll_callback(|_| Ok(()))?;
+
+            src_state
+        } else {
+            None
+        };
+
+        let plugin = library
+            .create_plugin(&dst_plugin_id.id)
+            .with_context(|| format!("Could not create a plugin instance for '{}'", dst_plugin_id.id))?;
+        plugin
+            .init()
+            .with_context(|| format!("Error while initializing '{}'", dst_plugin_id.id))?;
+
+        let state = match plugin.get_extension::<State>() {
+            Some(state) => state,
+            None => {
+                return Ok(TestStatus::Failed {
+                    details: Some(format!(
+                        "The state converter with ID '{}' converts state for '{}', but that plugin does not \
+                         implement the 'state' extension.",
+                        converter_metadata.id, dst_plugin_id.id
+                    )),
+                });
+            }
+        };
+        plugin.poll_callback(|_| Ok(()))?;
+
+        let mut invalid_inputs: Vec<(String, Vec<u8>)> = vec![(String::from("an empty state"), Vec::new())];
+        if let Some(src_state) = src_state {
+            match converter
+                .convert_state(&src_state)
+                .with_context(|| format!("Error while converting state with '{}'", converter_metadata.id))?
+            {
+                StateConversion::Success(converted_state) => {
+                    if let Err(err) = state.load(&converted_state) {
+                        return Ok(TestStatus::Failed {
+                            details: Some(format!(
+                                "'{}' could not load the state converted from '{}' by the state converter with ID \
+                                 '{}': {err:#}",
+                                dst_plugin_id.id, src_plugin_id.id, converter_metadata.id
+                            )),
+                        });
+                    }
+                }
+                StateConversion::Failed(error) => {
+                    return Ok(TestStatus::Failed {
+                        details: Some(format!(
+                            "The state converter with ID '{}' could not convert the state saved by '{}': '{error}'",
+                            converter_metadata.id, src_plugin_id.id
+                        )),
+                    });
+                }
+            }
+
+            for truncated_len in [src_state.len() / 2, src_state.len().saturating_sub(1)] {
+                if truncated_len > 0 && truncated_len < src_state.len() {
+                    invalid_inputs.push((
+                        format!("a state truncated to {truncated_len} bytes"),
+                        src_state[..truncated_len].to_vec(),
+                    ));
+                }
+            }
+        } else {
+            unexercised_converters.push(format!(
+                "'{}' (converting from '{}')",
+                converter_metadata.id, src_plugin_id.id
+            ));
+        }
+
+        for _ in 0..STATE_CONVERTER_NUM_RANDOM_INPUTS {
+            let mut random_data = vec![0u8; prng.random_range(1..=STATE_CONVERTER_MAX_RANDOM_INPUT_SIZE)];
+            prng.fill(&mut random_data[..]);
+            invalid_inputs.push((format!("{} random bytes", random_data.len()), random_data));
+        }
+
+        for (description, input) in invalid_inputs {
+            match converter
+                .convert_state(&input)
+                .with_context(|| format!("Error while converting {description} with '{}'", converter_metadata.id))?
+            {
+                // The converter may not be able to tell that the input is garbage, but the
+                // destination plugin should then still be able to handle the converted state
+                StateConversion::Success(converted_state) => {
+                    let _ = state.load(&converted_state);
+                }
+                StateConversion::Failed(error) if error.is_empty() => {
+                    warnings.push(format!(
+                        "The state converter with ID '{}' failed to convert {description} without writing an error \
+                         message.",
+                        converter_metadata.id
+                    ));
+                    break;
+                }
+                StateConversion::Failed(_) => (),
+            }
+        }
+
+        plugin.poll_callback(|_| Ok(()))?;
+    }
+
+    // The source plugin needs to be a CLAP plugin in this library with a 'state' extension for the
+    // round trip to be exercised with a real state
+    let unexercised_details = (!unexercised_converters.is_empty()).then(|| {
+        format!(
+            "No state saved by the source plugin was available for the state converter(s) {}, so the conversion of \
+             a real state was not tested. Only their handling of invalid input was checked.",
+            unexercised_converters.join(", ")
+        )
+    });
+
+    if !warnings.is_empty() {
+        Ok(TestStatus::Warning {
+            details: Some(
+                warnings
+                    .into_iter()
+                    .chain(unexercised_details)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        })
+    } else if unexercised_converters.len() == num_converters {
+        Ok(TestStatus::Skipped {
+            details: unexercised_details,
+        })
+    } else {
+        Ok(TestStatus::Success {
+            details: unexercised_details,
+        })
+    }
+}
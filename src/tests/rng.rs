@@ -4,13 +4,24 @@ use crate::plugin::ext::audio_ports::AudioPortConfig;
 use crate::plugin::ext::configurable_audio_ports::{AudioPortsRequest, AudioPortsRequestInfo};
 use crate::plugin::ext::note_ports::NotePortConfig;
 use crate::plugin::ext::params::{Param, ParamInfo};
+use crate::plugin::ext::project_location::LocationElement;
+use crate::plugin::ext::triggers::TriggerInfo;
+use crate::plugin::ffi::project_location::*;
+use crate::plugin::instance::{HostTrackInfo, HostTuning, NUM_TUNING_KEYS};
 use crate::plugin::process::{Event, TransportState};
+use clap_sys::color::clap_color;
 use clap_sys::events::*;
 use clap_sys::ext::ambisonic::*;
+use clap_sys::ext::audio_ports::{CLAP_PORT_MONO, CLAP_PORT_STEREO};
+use clap_sys::ext::draft::triggers::*;
+use clap_sys::ext::surround::CLAP_PORT_SURROUND;
+use clap_sys::ext::track_info::*;
+use clap_sys::id::{CLAP_INVALID_ID, clap_id};
 use midi_consts::channel_event as midi;
 use rand::RngExt;
 use rand::seq::{IndexedRandom, IteratorRandom};
 use rand_pcg::Pcg32;
+use std::ffi::{CStr, CString};
 use std::ops::RangeInclusive;
 
 /// Create a new pseudo-random number generator with a fixed seed.
@@ -605,6 +616,58 @@ impl<'a> NoteGenerator<'a> {
         panic!("Unable to generate a random note event after 1024 tries");
     }
 
+    /// Generate a CLAP note on event for a new note with a new note ID on a random note port that
+    /// supports CLAP note events. The note does not share its key and channel with any of the port's
+    /// active notes, unless `overlapping` is set, in which case it takes the key and channel of one
+    /// of the port's active notes instead. Returns `None` if no such port or note can be found.
+    pub fn generate_note_on(&mut self, prng: &mut Pcg32, time_offset: u32, overlapping: bool) -> Option<Event> {
+        let note_port_idx = (0..self.config.inputs.len())
+            .filter(|&idx| {
+                self.config.inputs[idx].supports_clap() && (!overlapping || !self.active_notes[idx].is_empty())
+            })
+            .choose(prng)?;
+
+        let note_id = self.next_note_id;
+        let active_notes = &mut self.active_notes[note_port_idx];
+        let note = if overlapping {
+            Note {
+                note_id,
+                choked: false,
+                ..*active_notes.choose(prng)?
+            }
+        } else {
+            // All key and channel combinations may already be in use
+            (0..1024)
+                .map(|_| Note {
+                    note_id,
+                    ..Note::random(prng)
+                })
+                .find(|note| {
+                    !active_notes
+                        .iter()
+                        .any(|active_note| active_note.key == note.key && active_note.channel == note.channel)
+                })?
+        };
+        active_notes.push(note);
+        self.next_note_id = self.next_note_id.wrapping_add(1);
+
+        let velocity = prng.random_range(0.0..=1.0);
+        Some(Event::Note(clap_event_note {
+            header: clap_event_header {
+                size: std::mem::size_of::<clap_event_note>() as u32,
+                time: time_offset,
+                space_id: CLAP_CORE_EVENT_SPACE_ID,
+                type_: CLAP_EVENT_NOTE_ON,
+                flags: 0,
+            },
+            note_id: note.note_id,
+            port_index: note_port_idx as i16,
+            channel: note.channel,
+            key: note.key,
+            velocity,
+        }))
+    }
+
     pub fn stop_all_voices(&mut self, time_offset: u32) -> Vec<Event> {
         let mut events = vec![];
         for (note_port_idx, active_notes) in self.active_notes.iter_mut().enumerate() {
@@ -967,3 +1030,245 @@ pub fn random_layout_requests(config: &AudioPortConfig, prng: &mut Pcg32) -> Vec
 
     requests
 }
+
+/// Generate random track information for the host's `track-info` extension. The result may be
+/// `None` to indicate that the host has no track information at all. Flags are combined freely,
+/// names may be missing or empty, and the audio port type does not necessarily match the channel
+/// count or the plugin's own ports.
+pub fn random_track_info(prng: &mut Pcg32) -> Option<HostTrackInfo> {
+    const TRACK_NAMES: &[&str] = &["Track 1", "Drums", "Lead Vocals 🎤", "Bus A", "Master", "", " "];
+    const PORT_TYPES: &[Option<&CStr>] = &[
+        None,
+        Some(CLAP_PORT_MONO),
+        Some(CLAP_PORT_STEREO),
+        Some(CLAP_PORT_SURROUND),
+        Some(CLAP_PORT_AMBISONIC),
+        Some(c"com.example.unknown-port-type"),
+    ];
+
+    if prng.random_bool(0.1) {
+        return None;
+    }
+
+    let mut flags = 0;
+    for flag in [
+        CLAP_TRACK_INFO_HAS_TRACK_NAME,
+        CLAP_TRACK_INFO_HAS_TRACK_COLOR,
+        CLAP_TRACK_INFO_HAS_AUDIO_CHANNEL,
+        CLAP_TRACK_INFO_IS_FOR_RETURN_TRACK,
+        CLAP_TRACK_INFO_IS_FOR_BUS,
+        CLAP_TRACK_INFO_IS_FOR_MASTER,
+    ] {
+        if prng.random_bool(0.5) {
+            flags |= flag;
+        }
+    }
+
+    // Flags from future versions of the extension should be ignored by the plugin
+    if prng.random_bool(0.1) {
+        flags |= 1 << prng.random_range(6..64);
+    }
+
+    // The name may also be longer than `CLAP_NAME_SIZE`, in which case it gets truncated
+    let name = if prng.random_bool(0.1) {
+        "Long Track Name ".repeat(32)
+    } else {
+        String::from(*TRACK_NAMES.choose(prng).unwrap())
+    };
+
+    Some(HostTrackInfo {
+        flags,
+        name,
+        color: clap_color {
+            alpha: prng.random(),
+            red: prng.random(),
+            green: prng.random(),
+            blue: prng.random(),
+        },
+        audio_channel_count: prng.random_range(0..=16),
+        audio_port_type: *PORT_TYPES.choose(prng).unwrap(),
+    })
+}
+
+/// Generate a random number of header-only events in one of the `space_ids` event spaces, spread
+/// out over a block of `num_samples` samples. The event types overlap with the core event types so
+/// a plugin that doesn't check the space ID will misinterpret these events.
+pub fn random_foreign_events(prng: &mut Pcg32, space_ids: &[u16], num_samples: u32) -> Vec<Event> {
+    let num_events = prng.random_range(1..=16);
+    let mut events: Vec<_> = (0..num_events)
+        .filter_map(|_| {
+            let space_id = *space_ids.choose(prng)?;
+            Some(Event::Unknown(clap_event_header {
+                size: std::mem::size_of::<clap_event_header>() as u32,
+                time: prng.random_range(0..num_samples),
+                space_id,
+                type_: prng.random_range(CLAP_EVENT_NOTE_ON..=CLAP_EVENT_MIDI2),
+                flags: if prng.random_bool(0.5) { CLAP_EVENT_IS_LIVE } else { 0 },
+            }))
+        })
+        .collect();
+    events.sort_by_key(|event| event.header().time);
+
+    events
+}
+
+/// Generate a random pool of synthetic tunings for the host's `tuning` extension. This always
+/// contains a couple of equal temperaments, a tuning with random per-key offsets where some keys
+/// should not be played, and a tuning with extreme offsets of up to 48 semitones in either
+/// direction. The tuning IDs are unique, but otherwise random.
+pub fn random_tunings(prng: &mut Pcg32) -> Vec<HostTuning> {
+    const EQUAL_TEMPERAMENTS: &[u32] = &[5, 12, 19, 24, 31, 53];
+
+    let first_tuning_id: clap_id = prng.random_range(0..1_000_000);
+    let mut next_tuning_id = first_tuning_id..;
+
+    let mut tunings: Vec<HostTuning> = EQUAL_TEMPERAMENTS
+        .sample(prng, 3)
+        .map(|&divisions| HostTuning::equal_temperament(next_tuning_id.next().unwrap(), divisions))
+        .collect();
+
+    let mut random_offsets = [0.0; NUM_TUNING_KEYS];
+    let mut should_play = [true; NUM_TUNING_KEYS];
+    for (offset, should_play) in random_offsets.iter_mut().zip(should_play.iter_mut()) {
+        *offset = prng.random_range(-1.0..=1.0);
+        *should_play = prng.random_bool(0.9);
+    }
+    tunings.push(HostTuning {
+        tuning_id: next_tuning_id.next().unwrap(),
+        name: String::from("Random Offsets"),
+        is_dynamic: prng.random_bool(0.5),
+        relative: random_offsets,
+        should_play,
+    });
+
+    let mut extreme_offsets = [0.0; NUM_TUNING_KEYS];
+    for offset in &mut extreme_offsets {
+        *offset = *[-48.0, 48.0].choose(prng).unwrap();
+    }
+    tunings.push(HostTuning {
+        tuning_id: next_tuning_id.next().unwrap(),
+        name: String::from("Extreme ±48 Semitones"),
+        is_dynamic: false,
+        relative: extreme_offsets,
+        should_play: [true; NUM_TUNING_KEYS],
+    });
+
+    tunings
+}
+
+/// Generate a random number of `CLAP_EVENT_TRIGGER` events in the `space_id` event space, spread out
+/// over a block of `num_samples` samples. Roughly one in ten events targets a trigger ID that does
+/// not exist. The note ID, port, key, and channel are only set to specific values if the trigger
+/// supports that kind of automation, and they are wildcards otherwise.
+pub fn random_trigger_events(
+    prng: &mut Pcg32,
+    trigger_info: &TriggerInfo,
+    space_id: u16,
+    num_note_ports: usize,
+    num_samples: u32,
+) -> Vec<Event> {
+    let num_events = prng.random_range(0..=8);
+    let mut events: Vec<_> = (0..num_events)
+        .map(|_| {
+            let known_trigger = if prng.random_bool(0.9) {
+                trigger_info.iter().choose(prng)
+            } else {
+                None
+            };
+            let (trigger_id, cookie, flags) = match known_trigger {
+                Some((&trigger_id, trigger)) => (trigger_id, trigger.cookie, trigger.flags),
+                None => {
+                    let trigger_id = loop {
+                        let trigger_id = prng.random();
+                        if trigger_id != CLAP_INVALID_ID && !trigger_info.contains_key(&trigger_id) {
+                            break trigger_id;
+                        }
+                    };
+
+                    // Unknown triggers get the full range of options, since the plugin should
+                    // ignore them regardless
+                    (
+                        trigger_id,
+                        std::ptr::null_mut(),
+                        CLAP_TRIGGER_IS_AUTOMATABLE_PER_NOTE_ID
+                            | CLAP_TRIGGER_IS_AUTOMATABLE_PER_KEY
+                            | CLAP_TRIGGER_IS_AUTOMATABLE_PER_CHANNEL
+                            | CLAP_TRIGGER_IS_AUTOMATABLE_PER_PORT,
+                    )
+                }
+            };
+
+            let per_note_id = flags & CLAP_TRIGGER_IS_AUTOMATABLE_PER_NOTE_ID != 0 && prng.random_bool(0.5);
+            let per_key = flags & CLAP_TRIGGER_IS_AUTOMATABLE_PER_KEY != 0 && prng.random_bool(0.5);
+            let per_channel = flags & CLAP_TRIGGER_IS_AUTOMATABLE_PER_CHANNEL != 0 && prng.random_bool(0.5);
+            let per_port =
+                flags & CLAP_TRIGGER_IS_AUTOMATABLE_PER_PORT != 0 && num_note_ports > 0 && prng.random_bool(0.5);
+
+            Event::Trigger(clap_event_trigger {
+                header: clap_event_header {
+                    size: std::mem::size_of::<clap_event_trigger>() as u32,
+                    time: prng.random_range(0..num_samples),
+                    space_id,
+                    type_: CLAP_EVENT_TRIGGER,
+                    flags: if prng.random_bool(0.5) { CLAP_EVENT_IS_LIVE } else { 0 },
+                },
+                trigger_id,
+                cookie,
+                note_id: if per_note_id { prng.random_range(0..1024) } else { -1 },
+                port_index: if per_port {
+                    prng.random_range(0..num_note_ports) as i16
+                } else {
+                    -1
+                },
+                channel: if per_channel { prng.random_range(0..16) } else { -1 },
+                key: if per_key { prng.random_range(0..128) } else { -1 },
+            })
+        })
+        .collect();
+    events.sort_by_key(|event| event.header().time);
+
+    events
+}
+
+/// Generate a random path to the plugin's location within a project for the `location` extension. The path always starts with a project and ends with a device for the plugin itself,
+/// but it may contain deeply nested track groups and device chains in between. Names and IDs may be
+/// missing or empty, and colors may be fully transparent or use odd alpha values.
+pub fn random_project_location(prng: &mut Pcg32) -> Vec<LocationElement> {
+    const NAMES: &[&str] = &["Project", "Drums", "Group 🥁", "FX Chain", "Master", "", " ", "a/b\\c"];
+
+    fn random_element(prng: &mut Pcg32, kind: u32) -> LocationElement {
+        LocationElement {
+            id: prng
+                .random_bool(0.8)
+                .then(|| CString::new(format!("{:x}", prng.random::<u64>())).unwrap()),
+            name: prng
+                .random_bool(0.8)
+                .then(|| CString::new(*NAMES.choose(prng).unwrap()).unwrap()),
+            kind,
+            index_in_parent: if prng.random_bool(0.1) {
+                u32::MAX
+            } else {
+                prng.random_range(0..64)
+            },
+            color: clap_color {
+                alpha: *[0, 1, 128, 255].choose(prng).unwrap(),
+                red: prng.random(),
+                green: prng.random(),
+                blue: prng.random(),
+            },
+        }
+    }
+
+    let mut path = vec![random_element(prng, CLAP_PLUGIN_LOCATION_PROJECT)];
+    for _ in 0..prng.random_range(0..=16) {
+        path.push(random_element(prng, CLAP_PLUGIN_LOCATION_TRACK_GROUP));
+    }
+    path.push(random_element(prng, CLAP_PLUGIN_LOCATION_TRACK));
+    for _ in 0..prng.random_range(0..=8) {
+        path.push(random_element(prng, CLAP_PLUGIN_LOCATION_DEVICE));
+        path.push(random_element(prng, CLAP_PLUGIN_LOCATION_NESTED_DEVICE_CHAIN));
+    }
+    path.push(random_element(prng, CLAP_PLUGIN_LOCATION_DEVICE));
+
+    path
+}